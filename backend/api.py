@@ -1,132 +1,570 @@
 """API functions exposed to the frontend (equivalent to Tauri commands)"""
+import codecs
+import ipaddress
+import json
+import logging
+import os
 import time
+import uuid
+import queue
+import shlex
+import socket
+import ssl
 import threading
-from concurrent.futures import ThreadPoolExecutor, as_completed
+from concurrent.futures import ThreadPoolExecutor, TimeoutError as FuturesTimeoutError, as_completed
 from typing import Dict, List, Any, Tuple, Callable, Optional
 from dataclasses import asdict
-from config import AppConfig, ServerConfig, LocalAppConfig, ServiceConfig
-from ssh_client import SSHClient, ContainerStatus
-from process_manager import get_process_manager
+from config import CONFIG_VERSION, PORTAL_DEPENDENCY_PREFIX, AppConfig, ServerConfig, LocalAppConfig, ServiceConfig, Preferences, split_host_port
+import autostart
+import last_status
+import maintenance
+import clipboard
+from ssh_client import SSHClient, ContainerStatus, ServiceAction, CommandCancelled, ComposeProject, HealthState, ProjectState, merge_service_states, quote_path
+from process_manager import ProcessManager
+from connection_pool import get_connection_pool
+from local_api import get_local_api_server
 from vctt_interface import VCTTInterface
+import app_logging
+from errors import Cancelled, CommandFailed, InProgress, InvalidConfig, MaintenanceMode, NotFound, Timeout, command_error
+
+logger = logging.getLogger(__name__)
+
+# Maximum number of servers queried at once by get_all_status
+MAX_STATUS_CONCURRENCY = 4
+
+# Container states that mean a service crashed while waiting for it to become healthy
+CRASHED_STATES = ('exited', 'restarting', 'dead')
+# Log lines included in the error when a container crashes during startup
+CRASH_LOG_LINES = 20
+# Seconds launch_all waits for a dependency without its own startup_timeout_secs to become healthy
+DEPENDENCY_HEALTH_TIMEOUT_SECS = 60
+# Seconds to wait when checking a service's port can be reached from this machine
+CLIENT_REACH_TIMEOUT_SECS = 2
+# Seconds quitting waits for stop_started_services to bring services down
+QUIT_STOP_TIMEOUT_SECS = 30
+# App version reported by ping(); keep in step with __version__ in backend/__init__.py
+APP_VERSION = "1.0.0"
+# OpenSSL verify codes for the certificate problems browsers warn about, by how they're reported
+CERT_ISSUES = {
+    10: 'expired',  # X509_V_ERR_CERT_HAS_EXPIRED
+    18: 'self-signed',  # X509_V_ERR_DEPTH_ZERO_SELF_SIGNED_CERT
+    19: 'self-signed',  # X509_V_ERR_SELF_SIGNED_CERT_IN_CHAIN
+    62: 'hostname-mismatch',  # X509_V_ERR_HOSTNAME_MISMATCH
+}
+# Prefixes generate_id accepts, one per kind of configured item
+ID_KINDS = ('server', 'service', 'app')
 
 
 class API:
     """API class containing all command functions"""
     
-    def __init__(self):
-        self.process_manager = get_process_manager()
+    def __init__(self, process_manager: Optional[ProcessManager] = None):
+        # Owned by OrchestratorApp; a fresh one keeps tests from sharing process state
+        self.process_manager = process_manager or ProcessManager()
+        self.connection_pool = get_connection_pool()
         self._window_creator = None  # Callback to create windows
+        self._event_emitter = None  # Callback to push events to open windows
+        self._config_listener = None  # Callback run after config is saved
+        self._log_streams: Dict[str, threading.Event] = {}  # server_id -> stop event
+        self._log_streams_lock = threading.Lock()
+        self._launch_cancels: Dict[str, threading.Event] = {}  # server_id -> cancel event of the in-flight launch
+        self._launch_teardowns: set = set()  # server_ids whose cancelled launch should run compose down
+        self._launch_lock = threading.Lock()
+        self._shells: Dict[str, Any] = {}  # session_id -> interactive shell channel
+        self._shells_lock = threading.Lock()
+        self._status_cache: Dict[str, Tuple[float, Dict[str, Any]]] = {}  # server_id -> (monotonic time, status)
+        self._status_cache_lock = threading.Lock()
+        # (server_id, service_id) pairs this session brought up, so quitting only stops those
+        self._started_services: set = set()
+        self._started_at = time.monotonic()
     
     def set_window_creator(self, creator_func: Callable[[str], None]):
         """Set a callback function to create windows (called from OrchestratorApp)"""
         self._window_creator = creator_func
     
-    def launch_portal(self, server_id: str) -> str:
-        """Launch the portal on the remote server (backward compatible - launches first service)"""
+    def set_event_emitter(self, emitter_func: Callable[[str, Dict[str, Any]], None]):
+        """Set a callback function to emit events to the frontend (called from OrchestratorApp)"""
+        self._event_emitter = emitter_func
+    
+    def set_config_listener(self, listener_func: Callable[[], None]):
+        """Set a callback run after config is saved (called from OrchestratorApp)"""
+        self._config_listener = listener_func
+    
+    def _emit(self, event: str, payload: Dict[str, Any]) -> None:
+        """Emit an event to the frontend, ignoring failures (no window may be open)"""
+        if not self._event_emitter:
+            return
+        try:
+            self._event_emitter(event, payload)
+        except Exception as e:
+            logger.error("Failed to emit event %r: %s", event, e)
+    
+    def launch_portal(self, server_id: str, wait: bool = True, require_healthy: bool = False) -> str:
+        """Launch the portal on the remote server (backward compatible - launches first service); see launch_service for wait and require_healthy"""
         try:
             config = AppConfig.load()
             server = config.get_server(server_id)
             
             if not server:
-                raise Exception(f"Server not found: {server_id}")
+                raise NotFound(f"Server not found: {server_id}")
             
             # For backward compatibility, launch the first service
             if not server.services:
-                raise Exception(f"No services configured for server: {server_id}")
+                raise InvalidConfig(f"No services configured for server: {server_id}")
             
             first_service = server.services[0]
-            return self.launch_service(server_id, first_service.id)
+            return self.launch_service(server_id, first_service.id, wait, require_healthy)
                 
         except Exception as e:
-            raise Exception(f"Failed to launch portal: {e}")
+            raise command_error(e, "Failed to launch portal")
     
-    def launch_service(self, server_id: str, service_id: str) -> str:
-        """Launch a specific service on a remote server"""
+    def launch_service(self, server_id: str, service_id: str, wait: bool = True,
+                       require_healthy: bool = False) -> str:
+        """
+        Launch a specific service on a remote server.
+        
+        Raises InProgress if a launch on the same server hasn't finished yet. With wait=False
+        it returns as soon as compose up has finished, without waiting for the health check;
+        status polling reports readiness later. require_healthy (for launches something else
+        depends on) always waits, raises Timeout if the health check doesn't pass, and checks
+        an already-running service too.
+        
+        Emits 'launch-progress' events with a 'phase' of connecting, starting-containers,
+        waiting-for-health (with elapsed seconds, attempt and HTTP status code), ready,
+        started (wait=False: the containers are up but readiness wasn't checked),
+        cancelled (see cancel_launch) or failed.
+        """
+        self._check_maintenance(server_id)
+        progress = self._launch_progress(server_id, service_id)
+        cancel_event = threading.Event()
+        with self._launch_lock:
+            # A double-clicked launch button shouldn't start compose and health polling twice
+            if server_id in self._launch_cancels:
+                raise InProgress(f"A launch is already in progress on server {server_id}")
+            self._launch_cancels[server_id] = cancel_event
+            self._launch_teardowns.discard(server_id)
+        torn_down = False
+        
         try:
-            print(f"Loading config for server_id: {server_id}, service_id: {service_id}")
+            logger.debug(f"Loading config for server_id: {server_id}, service_id: {service_id}")
             config = AppConfig.load()
             result = config.get_service(server_id, service_id)
             
             if not result:
-                raise Exception(f"Service not found: {service_id} on server: {server_id}")
+                raise NotFound(f"Service not found: {service_id} on server: {server_id}")
             
             server, service = result
             
-            print(f"Connecting to SSH server: {server.host}:{server.port}")
+            logger.info(f"Connecting to SSH server: {server.host}:{server.port}")
+            progress('connecting')
             # Connect to SSH
-            with SSHClient(
-                host=server.host,
-                port=server.port,
-                username=server.username,
-                ssh_key_path=server.ssh_key_path
-            ) as ssh:
-                print(f"Checking containers at: {service.path}")
+            with self.connection_pool.connection(server) as ssh:
+                logger.debug(f"Checking containers at: {service.path}")
                 # Check current status
                 containers = ssh.check_containers_at_path(service.path)
-                print(f"Found {len(containers)} containers")
+                logger.debug(f"Found {len(containers)} containers")
                 service_running = any(
-                    c.name == service.container_name and c.state == "running" 
+                    c.matches(service.container_name) and c.state == "running" 
                     for c in containers
                 )
-                print(f"Service {service.name} running: {service_running}")
+                logger.debug(f"Service {service.name} running: {service_running}")
+                
+                service_url = server.service_url(service)
+                healthy = True
                 
                 # Start if not running
                 if not service_running:
-                    print(f"Starting service {service.name}...")
-                    # Pass pre-launch command if specified
-                    ssh.start_service(service.path, None, service.pre_launch_command)
-                    print("Service start command sent")
-                    
-                    # Wait for service to be ready (with timeout)
-                    max_wait = 120  # 2 minutes
-                    waited = 0
-                    ready = False
-                    
-                    while waited < max_wait:
-                        time.sleep(2)
-                        waited += 2
+                    try:
+                        logger.info(f"Starting service {service.name}...")
+                        progress('starting-containers')
+                        # Pass pre-launch command if specified
+                        ssh.start_service(service.path, None, service.pre_launch_command, cancel_event=cancel_event)
+                        logger.info("Service start command sent")
+                        with self._launch_lock:
+                            self._started_services.add((server_id, service_id))
                         
-                        if ssh.check_service_health(service.port, service.healthcheck_path):
-                            ready = True
-                            break
-                    
-                    if not ready:
-                        # Service started but health check didn't pass
-                        # This might be OK if the service doesn't have an HTTP endpoint
-                        print(f"Warning: Service started but health check didn't return 200 within {max_wait}s")
-                        print(f"Health check URL: http://localhost:{service.port}{service.healthcheck_path}")
-                        print("This is normal if the service doesn't have an HTTP endpoint or uses a different health check path")
-                
-                service_url = f"http://{server.host}:{service.port}"
+                        if not wait and not require_healthy:
+                            progress('started', url=service_url)
+                            return service_url
+                        healthy = self._wait_for_health(ssh, server, service, progress, cancel_event)
+                    except CommandCancelled:
+                        with self._launch_lock:
+                            teardown = server_id in self._launch_teardowns
+                        if teardown:
+                            logger.warning("Launch cancelled on server %s, tearing down %s", server_id, service.name)
+                            ssh.stop_service(service.path, None)
+                            torn_down = True
+                        raise
+                elif require_healthy:
+                    # Running isn't the same as ready, and a dependent is about to rely on it
+                    healthy = self._wait_for_health(ssh, server, service, progress, cancel_event)
+                
+                if not healthy:
+                    if require_healthy:
+                        raise Timeout(f"{service.name} didn't pass its health check within {server.portal_ready_timeout_secs}s")
+                    progress('ready', url=service_url, healthy=False)
+                    return service_url
+                
+                progress('ready', url=service_url, healthy=True)
                 return service_url
                 
+        except CommandCancelled:
+            progress('cancelled', torn_down=torn_down)
+            raise Cancelled("Launch cancelled")
+        except Exception as e:
+            progress('failed', error=str(e))
+            raise command_error(e, "Failed to launch service")
+        finally:
+            with self._launch_lock:
+                if self._launch_cancels.get(server_id) is cancel_event:
+                    del self._launch_cancels[server_id]
+                self._launch_teardowns.discard(server_id)
+    
+    def cancel_launch(self, server_id: str, teardown: bool = False) -> bool:
+        """
+        Abort the in-flight launch on a server. Returns False if nothing was launching.
+        
+        The running compose command is hung up and the health wait stops. Containers that
+        already started are left running unless teardown=True, which runs 'docker compose down'.
+        """
+        with self._launch_lock:
+            cancel_event = self._launch_cancels.get(server_id)
+            if not cancel_event:
+                return False
+            if teardown:
+                self._launch_teardowns.add(server_id)
+        cancel_event.set()
+        logger.info(f"Cancelling launch on server {server_id} (teardown={teardown})")
+        return True
+    
+    def get_portal_url(self, server_id: str) -> str:
+        """URL of the portal (the server's first service)"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            if not server.services:
+                raise InvalidConfig(f"No services configured for server: {server_id}")
+            
+            return server.portal_url()
+            
+        except Exception as e:
+            raise command_error(e, "Failed to get portal URL")
+    
+    def copy_portal_url(self, server_id: str) -> str:
+        """Copy the portal URL to the clipboard, returning it"""
+        url = self.get_portal_url(server_id)
+        try:
+            clipboard.copy_text(url)
+        except Exception as e:
+            raise command_error(e, "Failed to copy portal URL")
+        return url
+    
+    def stop_portal(self, server_id: str, remove_orphans: bool = False, remove_volumes: bool = False) -> Dict[str, Any]:
+        """
+        Stop the portal on the remote server (backward compatible - stops first service).
+        
+        remove_orphans and remove_volumes give a full cleanup rather than a normal stop.
+        """
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            if not server.services:
+                raise InvalidConfig(f"No services configured for server: {server_id}")
+            
+            return self.stop_service(server_id, server.services[0].id, remove_orphans, remove_volumes)
+                
         except Exception as e:
-            raise Exception(f"Failed to launch service: {e}")
+            raise command_error(e, "Failed to stop portal")
     
-    def stop_service(self, server_id: str, service_id: str) -> None:
-        """Stop a specific service on a remote server"""
+    def restart_portal(self, server_id: str, hard: bool = False) -> str:
+        """Restart the portal on the remote server (backward compatible - restarts first service)"""
         try:
-            print(f"Stopping service: {service_id} on server: {server_id}")
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            if not server.services:
+                raise InvalidConfig(f"No services configured for server: {server_id}")
+            
+            return self.restart_service(server_id, server.services[0].id, hard)
+                
+        except Exception as e:
+            raise command_error(e, "Failed to restart portal")
+    
+    def restart_service(self, server_id: str, service_id: str, hard: bool = False) -> str:
+        """
+        Restart a service and wait for it to become healthy again.
+        
+        A normal restart runs 'docker compose restart'; hard=True runs down + up -d so
+        containers are recreated (e.g. after editing env files). Emits the same
+        'launch-progress' events as launch_service.
+        """
+        progress = self._launch_progress(server_id, service_id)
+        
+        try:
+            self._check_maintenance(server_id)
+            logger.info(f"Restarting service: {service_id} on server: {server_id} (hard={hard})")
             config = AppConfig.load()
             result = config.get_service(server_id, service_id)
             
             if not result:
-                raise Exception(f"Service not found: {service_id} on server: {server_id}")
+                raise NotFound(f"Service not found: {service_id} on server: {server_id}")
             
             server, service = result
             
-            with SSHClient(
-                host=server.host,
-                port=server.port,
-                username=server.username,
-                ssh_key_path=server.ssh_key_path
-            ) as ssh:
-                print(f"Stopping service at: {service.path}")
-                ssh.stop_service(service.path, None)
-                print(f"Service {service.name} stopped")
+            progress('connecting')
+            with self.connection_pool.connection(server) as ssh:
+                progress('starting-containers')
+                ssh.restart_service(service.path, None, hard)
+                logger.info(f"Service {service.name} restart command sent")
+                
+                service_url = server.service_url(service)
+                healthy = self._wait_for_health(ssh, server, service, progress)
+                progress('ready', url=service_url, healthy=healthy)
+                return service_url
+                
+        except Exception as e:
+            progress('failed', error=str(e))
+            raise command_error(e, "Failed to restart service")
+    
+    def update_portal(self, server_id: str, service_id: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Pull the latest images for a service (the first one by default) and recreate its containers.
+        
+        Emits an 'update-progress' event per line of pull and up output. Returns the images that
+        were updated and those already up to date, plus 'applied' (see apply_updates) when any
+        image changed.
+        """
+        try:
+            server, service = self._find_update_target(server_id, service_id)
+            self._check_maintenance(server_id)
+            
+            with self.connection_pool.connection(server) as ssh:
+                logger.info(f"Pulling images for {service.name} at {service.path}...")
+                result = ssh.pull_images(service.path, self._update_progress(server_id, service.id))
+                logger.info(f"Updated: {result.updated or 'none'}, up to date: {result.up_to_date or 'none'}")
+                
+                applied = None
+                if result.updated:
+                    applied = ssh.apply_updates(service.path, self._update_progress(server_id, service.id))
+            
+            return {**asdict(result), 'applied': asdict(applied) if applied else None}
+            
+        except Exception as e:
+            raise command_error(e, "Failed to update portal")
+    
+    def apply_updates(self, server_id: str, service_id: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Run docker compose up -d for a service (the first one by default), recreating only what changed.
+        
+        Emits an 'update-progress' event per line of output. Returns the compose services that
+        were recreated, created, started or already up to date.
+        """
+        try:
+            server, service = self._find_update_target(server_id, service_id)
+            self._check_maintenance(server_id)
+            
+            with self.connection_pool.connection(server) as ssh:
+                logger.info(f"Applying updates for {service.name} at {service.path}...")
+                result = ssh.apply_updates(service.path, self._update_progress(server_id, service.id))
+                logger.info(f"Recreated: {result.recreated or 'none'}, up to date: {result.up_to_date or 'none'}")
+            
+            return asdict(result)
+            
+        except Exception as e:
+            raise command_error(e, "Failed to apply updates")
+    
+    @staticmethod
+    def _find_update_target(server_id: str, service_id: Optional[str]) -> Tuple[ServerConfig, ServiceConfig]:
+        """The server and service an update acts on (the server's first service by default)"""
+        config = AppConfig.load()
+        server = config.get_server(server_id)
+        
+        if not server:
+            raise NotFound(f"Server not found: {server_id}")
+        
+        if not server.services:
+            raise InvalidConfig(f"No services configured for server: {server_id}")
+        
+        if service_id:
+            service = next((s for s in server.services if s.id == service_id), None)
+        else:
+            service = server.services[0]
+        if not service:
+            raise NotFound(f"Service not found: {service_id} on server: {server_id}")
+        return server, service
+    
+    def _update_progress(self, server_id: str, service_id: str) -> Callable[[str], None]:
+        """Build a callback that emits 'update-progress' events for a service"""
+        def on_line(line: str):
+            self._emit('update-progress', {'server_id': server_id, 'service_id': service_id, 'line': line})
+        return on_line
+    
+    def _launch_progress(self, server_id: str, service_id: str) -> Callable[..., None]:
+        """Build a callback that emits 'launch-progress' events for a service"""
+        def progress(phase: str, **details):
+            self._emit('launch-progress', {'server_id': server_id, 'service_id': service_id, 'phase': phase, **details})
+        return progress
+    
+    def _wait_for_health(self, ssh: SSHClient, server: ServerConfig, service: ServiceConfig,
+                         progress: Callable[..., None], cancel_event: Optional[threading.Event] = None) -> bool:
+        """
+        Poll a service's health check until it returns an accepted status or the server's ready timeout passes.
+        
+        Raises CommandCancelled promptly if cancel_event is set.
+        """
+        max_wait = server.portal_ready_timeout_secs
+        cancel_event = cancel_event or threading.Event()
+        waited = 0
+        attempt = 0
+        
+        while waited < max_wait:
+            if cancel_event.wait(2):
+                raise CommandCancelled("waiting for health check")
+            waited += 2
+            attempt += 1
+            
+            self._check_not_crashed(ssh, service)
+            
+            health = ssh.get_service_health(service.port, service.healthcheck_path)
+            progress('waiting-for-health', elapsed_secs=waited, attempt=attempt, status_code=health.status_code)
+            if health.state == HealthState.READY:
+                return True
+        
+        # Service started but health check didn't pass
+        # This might be OK if the service doesn't have an HTTP endpoint
+        logger.warning("Service %s on server %s started but its health check at %s didn't pass within %ss "
+                       "(normal if it has no HTTP endpoint or uses a different health check path)",
+                       service.name, server.id, ssh.health_url(service.port, service.healthcheck_path), max_wait)
+        return False
+    
+    def _check_not_crashed(self, ssh: SSHClient, service: ServiceConfig) -> None:
+        """Fail fast, with the last few log lines, if a service's container exited or is restart-looping"""
+        try:
+            # ps -a, since plain ps leaves out the exited and dead containers this is looking for
+            containers = ssh.inspect_project(service.path).containers
+        except Exception as e:
+            logger.warning("Could not check containers of %s at %s on %s: %s", service.name, service.path, ssh.host, e)
+            return  # Keep waiting on the health check alone
+        # Fall back to every container in the project if none match the configured name
+        watched = [c for c in containers if c.matches(service.container_name)] or containers
+        
+        for c in watched:
+            if c.state in CRASHED_STATES:
+                try:
+                    logs = ssh.get_service_logs(service.path, c.service or None, lines=CRASH_LOG_LINES).strip()
+                except Exception as e:
+                    logs = f"(could not fetch logs: {e})"
+                raise CommandFailed(
+                    f"Container {c.name} is {c.state} ({c.status}) during startup.\n"
+                    f"Last {CRASH_LOG_LINES} log lines:\n{logs}"
+                )
+    
+    def stop_service(self, server_id: str, service_id: str,
+                     remove_orphans: bool = False, remove_volumes: bool = False) -> Dict[str, Any]:
+        """
+        Stop a specific service on a remote server with docker compose down.
+        
+        Returns the containers stopped and removed, plus removed networks and volumes.
+        remove_orphans also removes containers for services no longer in the compose file,
+        and remove_volumes deletes the project's volumes.
+        """
+        try:
+            logger.info(f"Stopping service: {service_id} on server: {server_id}")
+            config = AppConfig.load()
+            result = config.get_service(server_id, service_id)
+            
+            if not result:
+                raise NotFound(f"Service not found: {service_id} on server: {server_id}")
+            
+            server, service = result
+            
+            with self.connection_pool.connection(server) as ssh:
+                logger.info(f"Stopping service at: {service.path}")
+                down = ssh.down_services(service.path, remove_orphans, remove_volumes)
+                logger.info(f"Service {service.name} stopped (removed: {', '.join(down.removed) or 'no containers'})")
+                with self._launch_lock:
+                    self._started_services.discard((server_id, service_id))
+                return asdict(down)
                 
         except Exception as e:
-            raise Exception(f"Failed to stop service: {e}")
+            raise command_error(e, "Failed to stop service")
+    
+    def stop_started_services(self, timeout: float = QUIT_STOP_TIMEOUT_SECS) -> None:
+        """
+        Run compose down on every service this session started (not ones that were already up),
+        in parallel, giving up on any still running after timeout. Used when quitting.
+        """
+        with self._launch_lock:
+            started = sorted(self._started_services)
+        if not started:
+            return
+        
+        executor = ThreadPoolExecutor(max_workers=MAX_STATUS_CONCURRENCY)
+        futures = {executor.submit(self.stop_service, server_id, service_id): (server_id, service_id)
+                   for server_id, service_id in started}
+        try:
+            for future in as_completed(futures, timeout=timeout):
+                server_id, service_id = futures[future]
+                try:
+                    future.result()
+                except Exception as e:
+                    logger.error("Could not stop %s on server %s while quitting: %s", service_id, server_id, e)
+        except FuturesTimeoutError:
+            logger.warning("Gave up stopping services after %ss", timeout)
+        finally:
+            executor.shutdown(wait=False)
+    
+    def control_service(self, server_id: str, service_id: str, compose_service: str, action: str) -> None:
+        """Start, stop or restart a single compose service (e.g. one row in the status window)"""
+        try:
+            try:
+                service_action = ServiceAction(action.lower())
+            except ValueError:
+                raise Exception(f"Unknown action '{action}'. Expected one of: {', '.join(a.value for a in ServiceAction)}")
+            
+            config = AppConfig.load()
+            result = config.get_service(server_id, service_id)
+            
+            if not result:
+                raise NotFound(f"Service not found: {service_id} on server: {server_id}")
+            
+            server, service = result
+            self._check_maintenance(server_id)
+            
+            with self.connection_pool.connection(server) as ssh:
+                logger.info(f"Running '{service_action.value}' on compose service {compose_service} at: {service.path}")
+                ssh.control_service(service.path, compose_service, service_action)
+                
+        except Exception as e:
+            raise command_error(e, f"Failed to {action} service")
+    
+    def list_services(self, server_id: str, service_id: str) -> List[Dict[str, Any]]:
+        """Every compose service defined for a service's project, with state 'not-created' for those without a container"""
+        try:
+            config = AppConfig.load()
+            result = config.get_service(server_id, service_id)
+            
+            if not result:
+                raise NotFound(f"Service not found: {service_id} on server: {server_id}")
+            
+            server, service = result
+            
+            with self.connection_pool.connection(server) as ssh:
+                return [asdict(s) for s in ssh.list_services(service.path)]
+                
+        except Exception as e:
+            raise command_error(e, "Failed to list services")
     
     def launch_local_app(self, app_id: str) -> None:
         """Launch a local application"""
@@ -135,50 +573,215 @@ class API:
             app = config.get_local_app(app_id)
             
             if not app:
-                raise Exception(f"App not found: {app_id}")
+                raise NotFound(f"App not found: {app_id}")
             
             self.process_manager.launch_app(app)
             
         except Exception as e:
-            raise Exception(f"Failed to launch app: {e}")
+            raise command_error(e, "Failed to launch app")
+    
+    def launch_all(self, server_id: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Launch every auto_launch local app, and the portal if auto_start_portal is on.
+        
+        server_id defaults to the default server. Items start in depends_on order, each
+        dependency healthy before its dependents launch. The result maps each item (app id
+        or portal:<server_id>) to {'success': bool, 'error': str | None}, with 'order' listing them.
+        """
+        try:
+            config = AppConfig.load()
+            if server_id is None:
+                default_server = config.get_default_server()
+                server_id = default_server.id if default_server else None
+            
+            targets = [app.id for app in config.local_apps if app.auto_launch]
+            if config.preferences.auto_start_portal and server_id:
+                targets.append(f"{PORTAL_DEPENDENCY_PREFIX}{server_id}")
+            order = config.launch_order(targets)
+        except ValueError as e:
+            raise InvalidConfig(f"Failed to launch all: {e}")
+        except Exception as e:
+            raise command_error(e, "Failed to launch all")
+        
+        # Items something else waits on must be healthy, not just started
+        dependencies = {dep for app in config.local_apps for dep in app.depends_on}
+        results: Dict[str, Any] = {}
+        for item in order:
+            app = config.get_local_app(item)
+            blocked = [dep for dep in (app.depends_on if app else []) if not results[dep]['success']]
+            if blocked:
+                results[item] = {'success': False, 'error': f"Skipped: dependency {', '.join(blocked)} failed"}
+                continue
+            try:
+                if app:
+                    if not self.process_manager.is_running(app.id):
+                        self.launch_local_app(app.id)
+                    if item in dependencies and app.health_check:
+                        timeout = app.health_check.startup_timeout_secs or DEPENDENCY_HEALTH_TIMEOUT_SECS
+                        self.process_manager.wait_until_healthy(app, timeout)
+                else:
+                    # Only a portal something depends on is waited for (and must come up healthy), so the rest
+                    # don't hold up the launches after them
+                    is_dependency = item in dependencies
+                    self.launch_portal(item[len(PORTAL_DEPENDENCY_PREFIX):], wait=is_dependency, require_healthy=is_dependency)
+                results[item] = {'success': True, 'error': None}
+            except Exception as e:
+                logger.error("launch_all: %s failed: %s", item, e)
+                results[item] = {'success': False, 'error': str(e)}
+        
+        return {'order': order, 'results': results}
+    
+    def get_status(self, server_id: str, force: bool = False) -> Dict[str, Any]:
+        """
+        Get status of the remote server and all its services.
+        
+        Results are reused for the status_cache_secs preference so several open windows
+        polling at once don't each hit the server. force=True bypasses the cache.
+        
+        Each service has started_by_app, true if this session launched it (and hasn't stopped
+        it since); the server-level started_by_app is that of the portal (first service).
+        """
+        max_age = AppConfig.load().preferences.status_cache_secs
+        if not force:
+            with self._status_cache_lock:
+                cached = self._status_cache.get(server_id)
+            if cached and time.monotonic() - cached[0] < max_age:
+                return self._mark_maintenance(self._mark_started_by_app(cached[1]))
+        
+        status = self._fetch_status(server_id)
+        with self._status_cache_lock:
+            self._status_cache[server_id] = (time.monotonic(), status)
+        if status['connected']:
+            last_status.save(server_id, status)
+        return self._mark_maintenance(self._mark_started_by_app(status))
+    
+    def _mark_started_by_app(self, status: Dict[str, Any]) -> Dict[str, Any]:
+        """Copy of a status with the started_by_app flags filled in from this session's launches"""
+        with self._launch_lock:
+            started = {s_id for server_id, s_id in self._started_services if server_id == status['server_id']}
+        services = [{**service, 'started_by_app': service['id'] in started} for service in status['services']]
+        return {**status, 'services': services, 'started_by_app': bool(services) and services[0]['started_by_app']}
+    
+    @staticmethod
+    def _mark_maintenance(status: Dict[str, Any]) -> Dict[str, Any]:
+        """Copy of a status with maintenance set to the server's maintenance mode entry, or None"""
+        return {**status, 'maintenance': maintenance.get(status['server_id'])}
+    
+    @staticmethod
+    def _check_maintenance(server_id: str) -> None:
+        """Raise MaintenanceMode if a server is in maintenance mode"""
+        entry = maintenance.get(server_id)
+        if entry:
+            since = time.strftime('%Y-%m-%d %H:%M', time.localtime(entry['since']))
+            reason = f" ({entry['reason']})" if entry.get('reason') else ""
+            raise MaintenanceMode(f"Server {server_id} has been in maintenance mode since {since}{reason}. "
+                                  f"Turn maintenance mode off to launch or restart its services.")
+    
+    def set_maintenance(self, server_id: str, enabled: bool, reason: str = "") -> None:
+        """
+        Turn a server's maintenance mode on or off. While on, launches, restarts, updates and
+        control_service on it raise MaintenanceMode; stop_portal and stop_service still work.
+        
+        Emits 'maintenance-changed' with server_id and the new maintenance entry (None when off).
+        """
+        try:
+            if not AppConfig.load().get_server(server_id):
+                raise NotFound(f"Server not found: {server_id}")
+            maintenance.set_enabled(server_id, enabled, reason)
+            logger.info(f"Maintenance mode {'on' if enabled else 'off'} for server {server_id}")
+            self._emit('maintenance-changed', {'server_id': server_id, 'maintenance': maintenance.get(server_id)})
+        except Exception as e:
+            raise command_error(e, "Failed to set maintenance mode")
+    
+    def ping(self) -> Dict[str, Any]:
+        """Liveness check for the UI and scripts: versions, uptime and what the backend is holding"""
+        return {
+            'version': APP_VERSION,
+            'config_version': CONFIG_VERSION,
+            'uptime_secs': round(time.monotonic() - self._started_at),
+            'pid': os.getpid(),
+            'tracked_processes': len(self.process_manager.processes),
+            'pooled_connections': len(self.connection_pool.connections),
+            'log_streams': len(self._log_streams),
+            'shell_sessions': len(self._shells),
+        }
+    
+    def get_cached_status(self) -> List[Dict[str, Any]]:
+        """
+        Last successful status of each server, from disk, marked stale=True, so a window can
+        render before the first SSH round trip. Servers never seen connected are left out.
+        
+        Also starts a fresh fetch of every server in the background, emitted as a
+        'status-updated' event with the get_all_status result.
+        """
+        config = AppConfig.load()
+        saved = last_status.load_all()
+        statuses = [
+            self._mark_maintenance({**saved[server.id], 'server_name': server.name, 'stale': True})
+            for server in config.servers if server.id in saved
+        ]
+        
+        def refresh():
+            try:
+                self._emit('status-updated', {'statuses': self.get_all_status(True)})
+            except Exception as e:
+                logger.warning("Background status refresh failed: %s", e)
+        
+        threading.Thread(target=refresh, daemon=True).start()
+        return statuses
+    
+    def _invalidate_status(self, server_id: Optional[str] = None) -> None:
+        """Drop cached status for a server (or every server) after its state changed"""
+        with self._status_cache_lock:
+            if server_id is None:
+                self._status_cache.clear()
+            else:
+                self._status_cache.pop(server_id, None)
     
-    def get_status(self, server_id: str) -> Dict[str, Any]:
-        """Get status of the remote server and all its services"""
+    def _fetch_status(self, server_id: str) -> Dict[str, Any]:
+        """Query the remote server for the status of all its services"""
         try:
             config = AppConfig.load()
             server = config.get_server(server_id)
             
             if not server:
-                raise Exception(f"Server not found: {server_id}")
+                raise NotFound(f"Server not found: {server_id}")
             
             try:
-                with SSHClient(
-                    host=server.host,
-                    port=server.port,
-                    username=server.username,
-                    ssh_key_path=server.ssh_key_path
-                ) as ssh:
+                with self.connection_pool.connection(server) as ssh:
+                    # Tells "docker daemon down" apart from "no containers", which look the same to compose ps
+                    docker_available, docker_detail = ssh.check_docker_daemon()
+                    
                     # Get status for each service
                     services_status = []
                     for service in server.services:
                         try:
-                            containers = ssh.check_containers_at_path(service.path)
+                            project = ssh.inspect_project(service.path)
                         except Exception as e:
-                            print(f"Error checking containers at {service.path}: {e}")
+                            logger.warning("Could not check containers at %s on server %s: %s", service.path, server_id, e)
                             # Continue with empty containers list
-                            containers = []
-                        service_ready = ssh.check_service_health(service.port, service.healthcheck_path)
+                            project = ComposeProject(ProjectState.UNAVAILABLE, detail=str(e))
+                        containers = project.containers
+                        health = ssh.get_service_health(service.port, service.healthcheck_path)
+                        # Only worth probing from here if the server says the service is answering
+                        client = self._probe_from_client(server.host, service) if health.state != HealthState.UNREACHABLE else None
+                        try:
+                            compose_services = merge_service_states(ssh.list_compose_services(service.path), containers)
+                        except Exception as e:
+                            logger.warning("Could not list compose services at %s on server %s: %s", service.path, server_id, e)
+                            compose_services = []
                         
                         # Debug logging
-                        print(f"\n=== Service: {service.name} ===")
-                        print(f"Configured container name: '{service.container_name}'")
-                        print(f"Containers found: {[c.name for c in containers]}")
+                        logger.debug(f"=== Service: {service.name} ===")
+                        logger.debug(f"Configured container name: '{service.container_name}'")
+                        logger.debug(f"Containers found: {[c.name for c in containers]}")
                         
                         # Check if ANY container from this compose file is running
                         service_running = any(c.state == "running" for c in containers)
                         
                         # Flexible container name matching:
-                        # 1. Exact match: "pipeline-management-tool" == "pipeline-management-tool"
+                        # 1. Exact match on the compose service name or container name:
+                        #    "ai-portal" matches service "ai-portal" or container "portal-ai-portal-1"
                         # 2. Partial match (both directions):
                         #    - "pipeline-tool" in "data-pipeline-tool-1"
                         #    - "data-pipeline-tool-1" contains "pipeline-tool"
@@ -194,18 +797,18 @@ class API:
                             config_name_lower = service.container_name.lower()
                             container_name_lower = c.name.lower()
                             
-                            # Strategy 1: Exact match
-                            if config_name_lower == container_name_lower:
+                            # Strategy 1: Exact match on compose service or container name
+                            if c.matches(service.container_name):
                                 main_container_running = True
                                 matched_container = c.name
-                                print(f"✓ Matched (exact): {c.name}")
+                                logger.debug(f"✓ Matched (exact): {c.name}")
                                 break
                             
                             # Strategy 2: Partial match (bidirectional)
                             if config_name_lower in container_name_lower or container_name_lower in config_name_lower:
                                 main_container_running = True
                                 matched_container = c.name
-                                print(f"✓ Matched (partial): {c.name}")
+                                logger.debug(f"✓ Matched (partial): {c.name}")
                                 break
                             
                             # Strategy 3: Normalized match (remove special chars)
@@ -216,28 +819,36 @@ class API:
                             if config_normalized in container_normalized or container_normalized in config_normalized:
                                 main_container_running = True
                                 matched_container = c.name
-                                print(f"✓ Matched (normalized): {c.name}")
+                                logger.debug(f"✓ Matched (normalized): {c.name}")
                                 break
                         
                         if not main_container_running and service_running:
-                            print(f"⚠ No container matched '{service.container_name}', but containers are running")
-                            print(f"  Tip: Set container name to match one of: {[c.name for c in containers if c.state == 'running']}")
+                            logger.warning("No container on server %s matched %r, but containers are running (set the container name to one of: %s)",
+                                           server_id, service.container_name, [c.name for c in containers if c.state == 'running'])
                         
                         # If ANY container is running from this compose file, consider it running
                         # This handles cases where the name doesn't match but service is actually up
                         if not main_container_running and service_running:
                             main_container_running = True
-                            print(f"✓ Using fallback: Any container running = service running")
+                            logger.debug(f"✓ Using fallback: Any container running = service running")
                         
                         services_status.append({
                             'id': service.id,
                             'name': service.name,
-                            'url': f"http://{server.host}:{service.port}",
-                            'ready': service_ready,
+                            'url': server.service_url(service),
+                            'ready': health.state == HealthState.READY,
+                            'health': health.state.value,
+                            'status_code': health.status_code,
+                            'client_reachable': client['reachable'] if client else None,
+                            'client_detail': client['detail'] if client else None,
+                            'client_cert_issue': client['cert_issue'] if client else None,
                             'running': main_container_running,
                             'has_containers': service_running,
                             'containers': [asdict(c) for c in containers],
                             'container_count': len(containers),
+                            'project_state': project.state.value,
+                            'project_detail': project.detail,
+                            'compose_services': [asdict(s) for s in compose_services],
                             'matched_container': matched_container  # For debugging
                         })
                     
@@ -245,22 +856,152 @@ class API:
                         'server_id': server_id,
                         'server_name': server.name,
                         'connected': True,
-                        'services': services_status
+                        'docker_available': docker_available,
+                        'docker_detail': docker_detail,
+                        'services': services_status,
+                        'maintenance_commands': [asdict(c) for c in server.maintenance_commands]
                     }
             except Exception as e:
-                print(f"Error getting status for {server_id}: {e}")
+                logger.error("Error getting status for server %s: %s", server_id, e)
                 return {
                     'server_id': server_id,
                     'server_name': server.name,
                     'connected': False,
-                    'services': []
+                    'docker_available': False,
+                    'services': [],
+                    'error': str(e)
                 }
                 
         except Exception as e:
-            raise Exception(f"Failed to get status: {e}")
+            raise command_error(e, "Failed to get status")
+    
+    def check_portal_reachable(self, server_id: str) -> Dict[str, Any]:
+        """
+        Check the portal from both ends: server-side health (curl on the server) and whether
+        this machine can connect to host:port, to tell "down" from "up but firewalled".
+        """
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            if not server.services:
+                raise InvalidConfig(f"No services configured for server: {server_id}")
+            
+            portal = server.services[0]
+            with self.connection_pool.connection(server) as ssh:
+                health = ssh.get_service_health(portal.port, portal.healthcheck_path)
+            client = self._probe_from_client(server.host, portal)
+            
+            return {
+                'url': server.service_url(portal),
+                'server_health': health.state.value,
+                'status_code': health.status_code,
+                'reachable': client['reachable'],
+                'detail': client['detail'],
+                'cert_issue': client['cert_issue']
+            }
+            
+        except Exception as e:
+            raise command_error(e, "Failed to check portal reachability")
+    
+    @staticmethod
+    def _probe_from_client(host: str, service: ServiceConfig) -> Dict[str, Any]:
+        """
+        TCP connect to a service from this machine and, for https, check its certificate as a
+        browser would. cert_issue is expired, self-signed, hostname-mismatch or untrusted.
+        """
+        port = service.port
+        try:
+            with socket.create_connection((host, port), timeout=CLIENT_REACH_TIMEOUT_SECS) as sock:
+                result = {'reachable': True, 'detail': f"Connected to {host}:{port}", 'cert_issue': None}
+                if service.scheme != 'https':
+                    return result
+                try:
+                    ssl.create_default_context().wrap_socket(sock, server_hostname=host).close()
+                except ssl.SSLCertVerificationError as e:
+                    result['cert_issue'] = CERT_ISSUES.get(e.verify_code, 'untrusted')
+                    result['detail'] += f", but browsers will warn about its certificate: {e.verify_message}"
+                    if result['cert_issue'] == 'self-signed' and service.self_signed_cert:
+                        result['detail'] += " (expected, the service is marked as self-signed)"
+                except ssl.SSLError as e:
+                    result['cert_issue'] = 'untrusted'
+                    result['detail'] += f", but the TLS handshake failed (is it serving https?): {e}"
+                return result
+        except OSError as e:
+            return {'reachable': False, 'detail': f"Cannot connect to {host}:{port} from this machine: {e}", 'cert_issue': None}
     
-    def get_all_status(self) -> List[Dict[str, Any]]:
-        """Get status for all servers and their services (parallelized for speed)"""
+    def get_container_events(self, server_id: str, since_secs: int = 3600) -> List[Dict[str, Any]]:
+        """Container start/stop/health events on a server over the last since_secs seconds, oldest first"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            with self.connection_pool.connection(server) as ssh:
+                return [asdict(e) for e in ssh.get_events(since_secs)]
+                
+        except Exception as e:
+            raise command_error(e, "Failed to get container events")
+    
+    def get_container_stats(self, server_id: str) -> List[Dict[str, Any]]:
+        """Get CPU and memory usage for all running containers on a server"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            with self.connection_pool.connection(server) as ssh:
+                return [asdict(s) for s in ssh.check_container_stats()]
+                
+        except Exception as e:
+            raise command_error(e, "Failed to get container stats")
+    
+    def get_docker_disk_usage(self, server_id: str) -> Dict[str, Any]:
+        """Space docker uses on a server for images, containers, volumes and build cache, in bytes"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            with self.connection_pool.connection(server) as ssh:
+                return asdict(ssh.get_disk_usage())
+                
+        except Exception as e:
+            raise command_error(e, "Failed to get docker disk usage")
+    
+    def prune_docker(self, server_id: str, what: str) -> Dict[str, Any]:
+        """Clean up unused docker data on a server ('images', 'volumes' or 'all'; see PRUNE_COMMANDS), returning the space reclaimed"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            logger.info(f"Pruning docker {what} on {server_id}")
+            with self.connection_pool.connection(server) as ssh:
+                return asdict(ssh.prune(what))
+                
+        except Exception as e:
+            raise command_error(e, f"Failed to prune docker {what}")
+    
+    def get_all_status(self, force: bool = False) -> List[Dict[str, Any]]:
+        """
+        Get status for all servers and their services in one call.
+        
+        Servers are queried concurrently (at most MAX_STATUS_CONCURRENCY at once) and
+        returned in config order. A server that fails gets connected=False and an
+        'error' message instead of failing the whole batch. force=True bypasses the
+        status cache.
+        """
         try:
             config = AppConfig.load()
             
@@ -268,11 +1009,11 @@ class API:
                 return []
             
             # Fetch status from all servers in parallel using ThreadPoolExecutor
-            all_status = []
-            with ThreadPoolExecutor(max_workers=min(len(config.servers), 10)) as executor:
+            results: Dict[str, Dict[str, Any]] = {}
+            with ThreadPoolExecutor(max_workers=min(len(config.servers), MAX_STATUS_CONCURRENCY)) as executor:
                 # Submit all server status fetches
                 future_to_server = {
-                    executor.submit(self.get_status, server.id): server 
+                    executor.submit(self.get_status, server.id, force): server 
                     for server in config.servers
                 }
                 
@@ -280,23 +1021,23 @@ class API:
                 for future in as_completed(future_to_server):
                     server = future_to_server[future]
                     try:
-                        status = future.result()
-                        all_status.append(status)
+                        results[server.id] = future.result()
                     except Exception as e:
-                        print(f"Error fetching status for server {server.name}: {e}")
+                        logger.error("Error fetching status for server %s (%s): %s", server.id, server.name, e)
                         # Add error status for this server
-                        all_status.append({
+                        results[server.id] = {
                             'server_id': server.id,
                             'server_name': server.name,
                             'connected': False,
+                            'docker_available': False,
                             'services': [],
                             'error': str(e)
-                        })
+                        }
             
-            return all_status
+            return [results[server.id] for server in config.servers]
             
         except Exception as e:
-            raise Exception(f"Failed to get all status: {e}")
+            raise command_error(e, "Failed to get all status")
     
     def validate_server_config(self, servers: List[Dict[str, Any]]) -> Tuple[bool, str]:
         """
@@ -319,11 +1060,10 @@ class API:
                 return False, f"Duplicate server name '{name}'. Server names must be unique."
             server_names[name] = idx
         
-        # Check for duplicate server IPs (host:port combination)
+        # Servers sharing a host:port are allowed (e.g. a clone running other services), but worth a warning
         server_hosts = {}
         for idx, server in enumerate(servers):
-            host = server.get('host', '').strip()
-            port = server.get('port', 22)
+            host, port = split_host_port(server.get('host', ''), server.get('port', 22))
             
             if not host:
                 return False, f"Server '{server.get('name', f'Server {idx + 1}')}' has an empty host/IP address."
@@ -339,15 +1079,19 @@ class API:
             host_key = f"{host}:{port}"
             if host_key in server_hosts:
                 other_name = servers[server_hosts[host_key]].get('name', f'Server {server_hosts[host_key] + 1}')
-                return False, f"Duplicate server IP/Port '{host_key}'. Server '{server.get('name', f'Server {idx + 1}')}' conflicts with '{other_name}'."
-            server_hosts[host_key] = idx
+                logger.warning(f"Server '{server.get('name', f'Server {idx + 1}')}' uses the same host/port '{host_key}' as '{other_name}'")
+            server_hosts.setdefault(host_key, idx)
             
             # Validate required fields
             if not server.get('username', '').strip():
                 return False, f"Server '{server.get('name', f'Server {idx + 1}')}' is missing a username."
             
-            if not server.get('ssh_key_path', '').strip():
-                return False, f"Server '{server.get('name', f'Server {idx + 1}')}' is missing an SSH key path."
+            auth_method = server.get('auth_method') or 'key'
+            has_key = bool((server.get('ssh_key_path') or '').strip() or server.get('ssh_key_paths'))
+            if auth_method == 'key' and not has_key and not server.get('password'):
+                return False, f"Server '{server.get('name', f'Server {idx + 1}')}' is missing an SSH key path or password."
+            if auth_method == 'password' and not server.get('password'):
+                return False, f"Server '{server.get('name', f'Server {idx + 1}')}' is missing a password."
         
         return True, ""
     
@@ -362,10 +1106,12 @@ class API:
             parts = host.split('.')
             return all(0 <= int(part) <= 255 for part in parts)
         
-        # IPv6 pattern (basic check)
+        # IPv6 literal
         if ':' in host:
-            # Basic IPv6 validation
-            return len(host) <= 45  # Max IPv6 length
+            try:
+                return isinstance(ipaddress.ip_address(host), ipaddress.IPv6Address)
+            except ValueError:
+                return False
         
         # Hostname pattern (letters, numbers, dots, hyphens)
         hostname_pattern = r'^[a-zA-Z0-9]([a-zA-Z0-9\-\.]*[a-zA-Z0-9])?$'
@@ -374,112 +1120,523 @@ class API:
         
         return False
     
-    def save_config(self, config_dict: Dict[str, Any]) -> None:
-        """Save configuration with validation"""
+    def save_config(self, config_dict: Dict[str, Any]) -> Dict[str, Any]:
+        """
+        Save configuration with validation.
+        
+        Returns {'saved': bool, 'errors': [...], 'warnings': [...]} where each entry has server_id
+        (or app_id), field and message so the settings form can highlight the offending input.
+        Warnings, such as a local app that isn't installed on this machine, don't block the save.
+        """
         try:
-            servers_data = config_dict.get('servers', [])
+            # Passwords and passphrases the user didn't change come back as placeholders
+            stored = AppConfig.load()
+            servers_data = [stored.unmask_secrets(s) for s in config_dict.get('servers', [])]
             
             # Validate servers before saving
             is_valid, error_msg = self.validate_server_config(servers_data)
             if not is_valid:
-                raise Exception(f"Configuration validation failed: {error_msg}")
+                return {'saved': False, 'errors': [{'server_id': None, 'field': None, 'message': error_msg}], 'warnings': []}
             
             # Convert dictionary to AppConfig
-            servers = [ServerConfig(**s) for s in servers_data]
+            servers = [ServerConfig.from_dict(s) for s in servers_data]
             local_apps = [LocalAppConfig(**app) for app in config_dict.get('local_apps', [])]
             
+            # Keep existing preferences if the caller didn't send them
+            if 'preferences' in config_dict:
+                preferences = Preferences(**config_dict['preferences'])
+            else:
+                preferences = AppConfig.load().preferences
+            
             config = AppConfig(
                 version=config_dict.get('version', '1.0'),
                 servers=servers,
-                local_apps=local_apps
+                local_apps=local_apps,
+                preferences=preferences
             )
             
+            config.assign_missing_ids()
+            errors = config.validate()
+            if errors:
+                return {'saved': False, 'errors': [asdict(e) for e in errors], 'warnings': []}
+            
             config.save()
+            # Hosts or services may have changed under the cached status
+            self._invalidate_status()
+            
+            # Keep launch-at-login in step with the preference
+            try:
+                autostart.reconcile(config.preferences.startup_launch)
+            except Exception as e:
+                logger.warning("Failed to update launch at login: %s", e)
+            self.connection_pool.set_keep_warm(config.preferences.keep_warm)
+            get_local_api_server().apply(self, config.preferences)
+            
+            self._notify_config_changed()
+            return {'saved': True, 'errors': [], 'warnings': [asdict(w) for w in config.launch_warnings()]}
             
         except Exception as e:
-            raise Exception(f"Failed to save config: {e}")
+            raise command_error(e, "Failed to save config")
     
-    def load_config(self) -> Dict[str, Any]:
-        """Load configuration"""
+    def generate_id(self, kind: str) -> str:
+        """A new id for a server, service or app that doesn't collide with any in the saved config"""
+        if kind not in ID_KINDS:
+            raise InvalidConfig(f"Unknown id kind '{kind}' (expected one of {', '.join(ID_KINDS)})")
+        return AppConfig.load().new_id(kind)
+    
+    def clone_server(self, server_id: str) -> str:
+        """Save a copy of a server under a new id and "(copy)" name, returning the new id"""
         try:
             config = AppConfig.load()
+            clone = config.clone_server(server_id)
+            
+            if not clone:
+                raise NotFound(f"Server not found: {server_id}")
             
+            config.save()
+            self._notify_config_changed()
+            logger.info(f"Cloned server {server_id} as {clone.id} ({clone.name})")
+            return clone.id
+            
+        except Exception as e:
+            raise command_error(e, "Failed to clone server")
+    
+    def reorder_servers(self, ids: List[str]) -> None:
+        """Save a new server order (tray menu, and the first server when no default is set)"""
+        self._reorder(lambda config: config.reorder_servers(ids), "servers")
+    
+    def reorder_local_apps(self, ids: List[str]) -> None:
+        """Save a new local app order"""
+        self._reorder(lambda config: config.reorder_local_apps(ids), "local apps")
+    
+    def _reorder(self, reorder: Callable[[AppConfig], None], what: str) -> None:
+        try:
+            config = AppConfig.load()
+            try:
+                reorder(config)
+            except ValueError as e:
+                raise InvalidConfig(str(e))
+            config.save()
+            self._notify_config_changed()
+        except Exception as e:
+            raise command_error(e, f"Failed to reorder {what}")
+    
+    def refresh_tray(self) -> None:
+        """Rebuild the tray menu from the current config, e.g. after config.json was edited by hand"""
+        self._notify_config_changed()
+    
+    def _notify_config_changed(self) -> None:
+        """Run the config listener so the tray menu reflects the saved servers"""
+        if not self._config_listener:
+            return
+        try:
+            self._config_listener()
+        except Exception as e:
+            logger.error("Config listener failed: %s", e)
+    
+    def export_config(self, path: str, include_secrets: bool = False) -> None:
+        """Write the configuration to a file, e.g. to set up another machine (secrets excluded by default)"""
+        try:
+            config = AppConfig.load()
+            with open(os.path.expanduser(path), 'w', encoding='utf-8') as f:
+                json.dump(config.to_dict(include_secrets=include_secrets), f, indent=2)
+            logger.info(f"Exported configuration to {path} (secrets {'included' if include_secrets else 'excluded'})")
+        except Exception as e:
+            raise command_error(e, "Failed to export config")
+    
+    def import_config(self, path: str, merge: bool = True) -> Dict[str, Any]:
+        """
+        Load servers and local apps from an exported file.
+        
+        With merge=True they are added to the current config, replacing entries with the
+        same id; otherwise they replace the current servers and apps. Preferences are kept.
+        Returns how many servers and apps were imported, plus validation warnings
+        (e.g. passwords that weren't included in the export).
+        """
+        try:
+            with open(os.path.expanduser(path), 'r', encoding='utf-8') as f:
+                data = json.load(f)
+            if data.get('version', '1.0') == '1.0':
+                data = AppConfig._migrate_v1_to_v2(data)
+            imported = AppConfig.from_dict(data)
+            
+            config = AppConfig.load()
+            if merge:
+                imported_server_ids = {s.id for s in imported.servers}
+                imported_app_ids = {a.id for a in imported.local_apps}
+                config.servers = [s for s in config.servers if s.id not in imported_server_ids] + imported.servers
+                config.local_apps = [a for a in config.local_apps if a.id not in imported_app_ids] + imported.local_apps
+            else:
+                config.servers = imported.servers
+                config.local_apps = imported.local_apps
+            
+            config.save()
+            self._notify_config_changed()
+            logger.info(f"Imported {len(imported.servers)} servers and {len(imported.local_apps)} local apps from {path}")
+            
+            # Exports usually exclude secrets, so problems are reported for the user to fix rather than blocking
             return {
-                'version': config.version,
-                'servers': [asdict(s) for s in config.servers],
-                'local_apps': [asdict(app) for app in config.local_apps],
-                'preferences': asdict(config.preferences)
+                'servers': len(imported.servers),
+                'local_apps': len(imported.local_apps),
+                'warnings': [asdict(e) for e in config.validate() + config.launch_warnings()]
             }
             
         except Exception as e:
-            raise Exception(f"Failed to load config: {e}")
+            raise command_error(e, "Failed to import config")
+    
+    def get_config_path(self) -> str:
+        """Resolved path of config.json, so users can see where their config lives"""
+        return str(AppConfig.get_config_path().resolve())
+    
+    def get_autostart_status(self) -> Dict[str, Any]:
+        """Real launch-at-login state from the OS, alongside the stored preference"""
+        try:
+            return {
+                'enabled': autostart.is_enabled(),
+                'preference': AppConfig.load().preferences.startup_launch
+            }
+        except Exception as e:
+            raise command_error(e, "Failed to get autostart status")
     
-    def test_connection(self, server_dict: Dict[str, Any]) -> str:
-        """Test SSH connection to a server"""
+    def load_config(self) -> Dict[str, Any]:
+        """Load configuration"""
+        try:
+            # Secrets stay in the backend; save_config swaps the placeholders back
+            return AppConfig.load().to_masked_dict()
+            
+        except Exception as e:
+            raise command_error(e, "Failed to load config")
+    
+    def test_connection(self, server_dict: Dict[str, Any]) -> Dict[str, Any]:
+        """
+        Test SSH connection to a server.
+        
+        Returns {'message': ..., 'prerequisites': {...}, 'session': {...}} so the UI can tell
+        "SSH works but docker compose is missing" apart from a failed connection, and show
+        the server's version and login banner.
+        """
         try:
-            print(f"Starting SSH connection test to {server_dict['username']}@{server_dict['host']}:{server_dict['port']}")
-            print(f"Using SSH key: {server_dict['ssh_key_path']}")
+            logger.info(f"Starting SSH connection test to {server_dict['username']}@{server_dict['host']}:{server_dict['port']}")
+            logger.debug(f"Using SSH key: {server_dict['ssh_key_path']}")
+            AppConfig.load().unmask_secrets(server_dict)
             
             with SSHClient(
                 host=server_dict['host'],
                 port=server_dict['port'],
                 username=server_dict['username'],
-                ssh_key_path=server_dict['ssh_key_path']
+                ssh_key_path=server_dict.get('ssh_key_path', ''),
+                ssh_key_paths=server_dict.get('ssh_key_paths') or [],
+                password=server_dict.get('password') or None,
+                ssh_key_passphrase=server_dict.get('ssh_key_passphrase') or None,
+                known_hosts_path=server_dict.get('known_hosts_path') or None,
+                strict_host_key_checking=server_dict.get('strict_host_key_checking', True),
+                connect_timeout=server_dict.get('connect_timeout_secs', 10),
+                command_timeout=server_dict.get('command_timeout_secs', 30),
+                auth_method=server_dict.get('auth_method', 'key'),
+                compose_command=server_dict.get('compose_command') or "docker compose",
+                use_sudo=server_dict.get('use_sudo', False)
             ) as ssh:
-                print("SSH connection established successfully!")
+                logger.info("SSH connection established successfully!")
                 
                 # Test by running a simple command
                 output = ssh.execute_command("echo 'Connection successful'")
-                print(f"Command executed successfully: {output}")
+                logger.debug(f"Command executed successfully: {output}")
+                
+                prerequisites = ssh.check_prerequisites()
+                logger.info(f"Docker: {prerequisites.docker_version or 'missing'}, Compose: {prerequisites.compose_version or 'missing'}")
+                
+                session = ssh.get_session_info()
+                logger.info(f"Server version: {session.server_version}")
                 
-                return output
+                return {'message': output.strip(), 'prerequisites': asdict(prerequisites), 'session': asdict(session)}
                 
         except Exception as e:
-            print(f"Connection error: {e}")
-            raise Exception(f"Connection failed: {e}")
+            logger.warning("Connection test to %s@%s:%s failed: %s", server_dict['username'], server_dict['host'], server_dict['port'], e)
+            raise command_error(e, "Connection failed")
+    
+    def test_ssh_auth(self, host: str, port: int, username: str, key_path: str = "",
+                      password: Optional[str] = None, auth_method: str = "key",
+                      server_id: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Check SSH credentials only (no docker or service config needed), returning per-step timings in ms.
+        
+        Host keys are checked against ~/.ssh/known_hosts as for a saved server. With server_id,
+        the target is reached through that server's jump host or proxy command.
+        """
+        try:
+            ssh = SSHClient(
+                host=host,
+                port=int(port),
+                username=username,
+                ssh_key_path=key_path,
+                password=password or None,
+                auth_method=auth_method
+            )
+            if server_id:
+                server = AppConfig.load().get_server(server_id)
+                if not server:
+                    raise NotFound(f"Server not found: {server_id}")
+                routed = SSHClient.from_server(server)
+                ssh.jump_host, ssh.proxy_command = routed.jump_host, routed.proxy_command
+            timing = ssh.test_auth()
+            logger.info(f"SSH auth to {username}@{host}:{port} succeeded via {timing.auth_method}: {timing}")
+            return asdict(timing)
+            
+        except Exception as e:
+            raise command_error(e, "SSH authentication test failed")
+    
+    def diagnose_connection(self, server_id: str) -> Dict[str, Any]:
+        """
+        Run each stage of connecting to a saved server (resolve, TCP, handshake, host key, auth)
+        on a fresh connection, reporting each one's status and timing and which failed first.
+        """
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            diagnosis = SSHClient.from_server(server).diagnose()
+            logger.info(f"Connection diagnosis for {server_id}: {diagnosis.failed_stage or 'all stages passed'}")
+            return asdict(diagnosis)
+            
+        except Exception as e:
+            raise command_error(e, "Failed to diagnose connection")
+    
+    def disconnect_server(self, server_id: str) -> None:
+        """Force-drop the pooled SSH connection for a server"""
+        self.connection_pool.disconnect(server_id)
+    
+    def trust_host_key(self, server_id: str) -> str:
+        """Add a server's host key to known_hosts after the user has confirmed it"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            fingerprint = SSHClient.from_server(server).trust_host_key()
+            logger.info(f"Trusted host key for {server.host}:{server.port}: {fingerprint}")
+            return fingerprint
+            
+        except Exception as e:
+            raise command_error(e, "Failed to trust host key")
+    
+    def get_log_path(self) -> str:
+        """Path of the orchestrator's own log file, so the UI can offer to open it"""
+        return str(app_logging.get_log_path())
+    
+    def get_app_logs(self, app_id: str, lines: int = 200) -> str:
+        """Get captured stdout/stderr for a local app"""
+        try:
+            # Limit lines to reasonable range
+            lines = max(1, min(5000, int(lines)))
+            return self.process_manager.get_logs(app_id, lines)
+        except Exception as e:
+            return f"Error reading app logs: {e}"
+    
+    def clear_app_logs(self, app_id: str) -> None:
+        """Delete a local app's captured stdout/stderr"""
+        try:
+            self.process_manager.clear_logs(app_id)
+            logger.info(f"Cleared logs for app {app_id}")
+        except Exception as e:
+            raise command_error(e, "Failed to clear app logs")
     
     def is_app_running(self, app_id: str) -> bool:
         """Check if an app is currently running"""
         is_running = self.process_manager.is_running(app_id)
-        print(f"[API] Checking if app {app_id} is running: {is_running}")
+        logger.debug(f"[API] Checking if app {app_id} is running: {is_running}")
         return is_running
     
-    def terminate_app(self, app_id: str) -> None:
-        """Terminate a running app"""
+    def get_app_stats(self, app_id: str) -> Dict[str, Any]:
+        """CPU% and resident memory of a local app and its children; 'running' is False if it isn't running"""
+        try:
+            config = AppConfig.load()
+            app = config.get_local_app(app_id)
+            
+            if not app:
+                raise NotFound(f"App not found: {app_id}")
+            
+            return asdict(self.process_manager.get_stats(app_id))
+            
+        except Exception as e:
+            raise command_error(e, "Failed to get app stats")
+    
+    def app_health(self, app_id: str) -> Dict[str, Any]:
+        """
+        Check whether a local app is actually serving, not just running.
+        
+        'healthy' is None when the app has no health check configured.
+        """
+        try:
+            config = AppConfig.load()
+            app = config.get_local_app(app_id)
+            
+            if not app:
+                raise NotFound(f"App not found: {app_id}")
+            
+            running = self.process_manager.is_running(app_id)
+            if not app.health_check:
+                return {'running': running, 'healthy': None, 'detail': "No health check configured"}
+            
+            healthy, detail = self.process_manager.check_health(app.health_check)
+            return {'running': running, 'healthy': healthy, 'detail': detail}
+            
+        except Exception as e:
+            raise command_error(e, "Failed to check app health")
+    
+    def terminate_app(self, app_id: str, grace_period: float = 10) -> None:
+        """Terminate a running app, force-killing it if it hasn't exited after grace_period seconds"""
         try:
-            self.process_manager.terminate(app_id)
+            self.process_manager.terminate(app_id, grace_period)
         except Exception as e:
-            raise Exception(f"Failed to terminate app: {e}")
+            raise command_error(e, "Failed to terminate app")
+    
+    def preview_service_commands(self, server_id: str, service_id: str) -> Dict[str, List[str]]:
+        """Shell commands the app would run for each action on a service, without running them"""
+        try:
+            config = AppConfig.load()
+            result = config.get_service(server_id, service_id)
+            
+            if not result:
+                raise NotFound(f"Service not found: {service_id} on server: {server_id}")
+            
+            server, service = result
+            ssh = SSHClient.from_server(server)
+            ssh.dry_run = True
+            
+            def record(action: Callable[[], Any]) -> List[str]:
+                ssh.dry_run_commands = []
+                action()
+                return ssh.dry_run_commands
+            
+            return {
+                'start': record(lambda: ssh.start_service(service.path, None, service.pre_launch_command)),
+                'stop': record(lambda: ssh.stop_service(service.path)),
+                'restart': record(lambda: ssh.restart_service(service.path)),
+                'hard_restart': record(lambda: ssh.restart_service(service.path, hard=True)),
+                'health': record(lambda: ssh.get_service_health_code(service.port, service.healthcheck_path)),
+            }
+            
+        except Exception as e:
+            raise command_error(e, "Failed to preview commands")
+    
+    def run_maintenance(self, server_id: str, command_name: str) -> Dict[str, Any]:
+        """Run one of a server's configured maintenance commands by name, returning stdout, stderr and exit_code"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            # Only commands from config are run, never free-form text from the frontend
+            command_config = server.get_maintenance_command(command_name)
+            if not command_config:
+                raise NotFound(f"Maintenance command not found: {command_name} on server: {server_id}")
+            
+            path = quote_path(server.services[0].path) if server.services else ""
+            command = command_config.command.replace("{path}", path)
+            logger.info(f"Running maintenance command '{command_name}' on {server_id}: {command}")
+            with self.connection_pool.connection(server) as ssh:
+                return asdict(ssh.run_command(command))
+                
+        except Exception as e:
+            raise command_error(e, f"Failed to run {command_name}")
+    
+    def upload_file(self, server_id: str, local_path: str, remote_path: str) -> Dict[str, Any]:
+        """Upload a local file (e.g. an updated .env) to a server"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            with self.connection_pool.connection(server) as ssh:
+                transferred = ssh.upload_file(local_path, remote_path)
+                logger.info(f"Uploaded {local_path} to {server.host}:{remote_path} ({transferred} bytes)")
+                return {'bytes_transferred': transferred}
+                
+        except Exception as e:
+            raise command_error(e, "Failed to upload file")
+    
+    def read_remote_file(self, server_id: str, path: str) -> str:
+        """Read a small text file on a server, e.g. a service's .env, for editing in place"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            with self.connection_pool.connection(server) as ssh:
+                return ssh.read_text_file(path)
+                
+        except Exception as e:
+            raise command_error(e, f"Failed to read {path}")
+    
+    def write_remote_file(self, server_id: str, path: str, contents: str) -> Dict[str, Any]:
+        """Replace a small text file on a server atomically (temp file, then rename)"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            with self.connection_pool.connection(server) as ssh:
+                written = ssh.write_text_file(path, contents)
+                logger.info(f"Wrote {server.host}:{path} ({written} bytes)")
+                return {'bytes_written': written}
+                
+        except Exception as e:
+            raise command_error(e, f"Failed to write {path}")
+    
+    def download_file(self, server_id: str, remote_path: str, local_path: str) -> Dict[str, Any]:
+        """Download a file from a server to a local path"""
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            with self.connection_pool.connection(server) as ssh:
+                transferred = ssh.download_file(remote_path, local_path)
+                logger.info(f"Downloaded {server.host}:{remote_path} to {local_path} ({transferred} bytes)")
+                return {'bytes_transferred': transferred}
+                
+        except Exception as e:
+            raise command_error(e, "Failed to download file")
     
     def restart_container(self, server_id: str, service_id: str, container_name: str) -> None:
         """Restart a specific container within a service"""
         try:
-            print(f"Restarting container: {container_name} in service: {service_id} on server: {server_id}")
+            logger.info(f"Restarting container: {container_name} in service: {service_id} on server: {server_id}")
             config = AppConfig.load()
             result = config.get_service(server_id, service_id)
             
             if not result:
-                raise Exception(f"Service not found: {service_id} on server: {server_id}")
+                raise NotFound(f"Service not found: {service_id} on server: {server_id}")
             
             server, service = result
             
-            with SSHClient(
-                host=server.host,
-                port=server.port,
-                username=server.username,
-                ssh_key_path=server.ssh_key_path
-            ) as ssh:
-                print(f"Restarting container at: {service.path}")
+            with self.connection_pool.connection(server) as ssh:
+                logger.info(f"Restarting container at: {service.path}")
                 ssh.restart_container(service.path, container_name)
-                print(f"Container {container_name} restarted")
+                logger.info(f"Container {container_name} restarted")
                 
         except Exception as e:
-            raise Exception(f"Failed to restart container: {e}")
+            raise command_error(e, "Failed to restart container")
     
     def get_container_logs(self, server_id: str, service_id: str, container_name: str, lines: int = 200) -> str:
         """Get logs for a specific container"""
         try:
-            print(f"Fetching logs for container: {container_name} in service: {service_id} on server: {server_id}")
+            logger.info(f"Fetching logs for container: {container_name} in service: {service_id} on server: {server_id}")
             config = AppConfig.load()
             result = config.get_service(server_id, service_id)
             
@@ -492,14 +1649,9 @@ class API:
             lines = max(1, min(1000, int(lines)))
             
             try:
-                with SSHClient(
-                    host=server.host,
-                    port=server.port,
-                    username=server.username,
-                    ssh_key_path=server.ssh_key_path
-                ) as ssh:
+                with self.connection_pool.connection(server) as ssh:
                     # Use 2>&1 to capture both stdout and stderr (many apps log to stderr)
-                    cmd = f"docker logs --tail {lines} --timestamps {container_name} 2>&1"
+                    cmd = f"{ssh.docker_command} logs --tail {lines} --timestamps {shlex.quote(container_name)} 2>&1"
                     output = ssh.execute_command(cmd)
                     return output if output else "(no logs)"
             except Exception as ssh_error:
@@ -508,10 +1660,37 @@ class API:
         except Exception as e:
             return f"Error fetching logs: {e}"
     
+    def get_service_logs(self, server_id: str, service_id: str, service_name: Optional[str] = None,
+                         lines: int = 200, since: Optional[str] = None, grep: Optional[str] = None) -> Dict[str, Any]:
+        """
+        Get the last lines of a service's compose logs, optionally only those since a time
+        (e.g. "2024-05-01T10:00:00" or "15m") and matching a grep pattern.
+        
+        Returns {'output': ..., 'truncated': bool}; truncated means more lines matched than
+        `lines`, so the UI can offer to load more.
+        """
+        try:
+            config = AppConfig.load()
+            result = config.get_service(server_id, service_id)
+            
+            if not result:
+                raise NotFound(f"Service not found: {service_id} on server: {server_id}")
+            
+            server, service = result
+            
+            # Limit lines to reasonable range
+            lines = max(1, min(5000, int(lines)))
+            
+            with self.connection_pool.connection(server) as ssh:
+                return asdict(ssh.fetch_logs(service.path, service_name or None, lines, since or None, grep or None))
+                
+        except Exception as e:
+            raise command_error(e, "Failed to fetch logs")
+    
     def get_container_logs_since(self, server_id: str, service_id: str, container_name: str, since_timestamp: str) -> str:
         """Get logs for a container since a specific timestamp"""
         try:
-            print(f"Fetching incremental logs for container: {container_name} since: {since_timestamp}")
+            logger.info(f"Fetching incremental logs for container: {container_name} since: {since_timestamp}")
             config = AppConfig.load()
             result = config.get_service(server_id, service_id)
             
@@ -521,15 +1700,10 @@ class API:
             server, service = result
             
             try:
-                with SSHClient(
-                    host=server.host,
-                    port=server.port,
-                    username=server.username,
-                    ssh_key_path=server.ssh_key_path
-                ) as ssh:
+                with self.connection_pool.connection(server) as ssh:
                     # Docker accepts ISO 8601 timestamps or relative time (e.g., "2s")
                     # Use 2>&1 to capture both stdout and stderr (many apps log to stderr)
-                    cmd = f"docker logs --since {since_timestamp} --timestamps {container_name} 2>&1"
+                    cmd = f"{ssh.docker_command} logs --since {shlex.quote(since_timestamp)} --timestamps {shlex.quote(container_name)} 2>&1"
                     output = ssh.execute_command(cmd)
                     return output if output else ""
             except Exception as ssh_error:
@@ -538,6 +1712,145 @@ class API:
         except Exception as e:
             return f"Error fetching logs: {e}"
     
+    def stream_logs(self, server_id: str, service_id: str, service_name: Optional[str] = None) -> None:
+        """
+        Follow docker compose logs for a service, emitting a 'log-line' event per line.
+        
+        Any existing stream for the same server is stopped first. A 'log-stream-ended'
+        event is emitted when the stream finishes, with 'error' set if it failed.
+        """
+        config = AppConfig.load()
+        result = config.get_service(server_id, service_id)
+        
+        if not result:
+            raise NotFound(f"Service not found: {service_id} on server: {server_id}")
+        
+        server, service = result
+        
+        self.stop_log_stream(server_id)
+        stop_event = threading.Event()
+        with self._log_streams_lock:
+            self._log_streams[server_id] = stop_event
+        
+        # Lines are handed to a separate emitter thread so a slow frontend never stalls the SSH read loop
+        lines: queue.Queue = queue.Queue()
+        
+        def emit_lines():
+            while True:
+                line = lines.get()
+                if line is None:
+                    break
+                self._emit('log-line', {'server_id': server_id, 'service_id': service_id, 'line': line})
+        
+        def read_logs():
+            error = None
+            try:
+                with self.connection_pool.connection(server) as ssh:
+                    ssh.stream_logs(service.path, service_name, lines.put, stop_event)
+            except Exception as e:
+                error = str(e)
+                logger.error("Log stream for server %s failed: %s", server_id, e)
+            finally:
+                lines.put(None)
+                emitter.join()
+                with self._log_streams_lock:
+                    if self._log_streams.get(server_id) is stop_event:
+                        del self._log_streams[server_id]
+                self._emit('log-stream-ended', {'server_id': server_id, 'service_id': service_id, 'error': error})
+        
+        emitter = threading.Thread(target=emit_lines, daemon=True)
+        emitter.start()
+        threading.Thread(target=read_logs, daemon=True).start()
+        logger.info(f"Started log stream for service {service.name} on server {server_id}")
+    
+    def stop_log_stream(self, server_id: str) -> None:
+        """Stop the log stream for a server, if one is running"""
+        with self._log_streams_lock:
+            stop_event = self._log_streams.pop(server_id, None)
+        if stop_event:
+            stop_event.set()
+            logger.info(f"Stopped log stream for server {server_id}")
+    
+    def stop_all_log_streams(self) -> None:
+        """Stop every running log stream (e.g. when the status window closes)"""
+        with self._log_streams_lock:
+            server_ids = list(self._log_streams.keys())
+        for server_id in server_ids:
+            self.stop_log_stream(server_id)
+    
+    def open_shell(self, server_id: str, cols: int = 80, rows: int = 24) -> str:
+        """
+        Open an interactive shell on a server and return its session id.
+        
+        Output is pushed as 'shell-output' events ({session_id, data}); 'shell-closed'
+        is emitted when the remote shell exits or the session is closed.
+        """
+        try:
+            config = AppConfig.load()
+            server = config.get_server(server_id)
+            
+            if not server:
+                raise NotFound(f"Server not found: {server_id}")
+            
+            channel = self.connection_pool.get(server).open_shell(cols, rows)
+            session_id = uuid.uuid4().hex
+            with self._shells_lock:
+                self._shells[session_id] = channel
+            
+            def read_output():
+                # Incremental decoding so multi-byte characters split across reads survive
+                decoder = codecs.getincrementaldecoder('utf-8')(errors='replace')
+                try:
+                    while True:
+                        data = channel.recv(4096)
+                        if not data:
+                            break
+                        self._emit('shell-output', {'session_id': session_id, 'data': decoder.decode(data)})
+                except Exception as e:
+                    logger.error("Shell session %s on server %s failed: %s", session_id, server_id, e)
+                finally:
+                    with self._shells_lock:
+                        self._shells.pop(session_id, None)
+                    channel.close()
+                    self._emit('shell-closed', {'session_id': session_id})
+            
+            threading.Thread(target=read_output, daemon=True).start()
+            logger.info(f"Opened shell session {session_id} on server {server_id}")
+            return session_id
+            
+        except Exception as e:
+            raise command_error(e, "Failed to open shell")
+    
+    def _get_shell(self, session_id: str):
+        """Look up an open shell channel"""
+        with self._shells_lock:
+            channel = self._shells.get(session_id)
+        if not channel:
+            raise NotFound(f"Shell session not found: {session_id}")
+        return channel
+    
+    def write_shell(self, session_id: str, data: str) -> None:
+        """Send keystrokes to a shell session"""
+        self._get_shell(session_id).sendall(data.encode('utf-8'))
+    
+    def resize_shell(self, session_id: str, cols: int, rows: int) -> None:
+        """Resize a shell session's PTY after the terminal view changes size"""
+        self._get_shell(session_id).resize_pty(width=cols, height=rows)
+    
+    def close_shell(self, session_id: str) -> None:
+        """Close a shell session (its reader thread then emits 'shell-closed')"""
+        with self._shells_lock:
+            channel = self._shells.get(session_id)
+        if channel:
+            channel.close()
+    
+    def close_all_shells(self) -> None:
+        """Close every open shell session (e.g. on quit)"""
+        with self._shells_lock:
+            session_ids = list(self._shells.keys())
+        for session_id in session_ids:
+            self.close_shell(session_id)
+    
     def open_settings_window(self) -> None:
         """Open the Settings window"""
         if self._window_creator:
@@ -560,9 +1873,9 @@ class API:
             config = AppConfig.load()
             config.preferences.setup_completed = True
             config.save()
-            print("Setup wizard marked as completed")
+            logger.info("Setup wizard marked as completed")
         except Exception as e:
-            raise Exception(f"Failed to mark setup as completed: {e}")
+            raise command_error(e, "Failed to mark setup as completed")
     
     def get_vctt_status(self) -> Dict[str, Any]:
         """
@@ -570,7 +1883,7 @@ class API:
         Only checks local_apps config - no file system scanning.
         """
         try:
-            print("[VCTT] Checking status from config...")
+            logger.debug("[VCTT] Checking status from config...")
             config = AppConfig.load()
             
             # Look for VCTT in local_apps
@@ -613,9 +1926,7 @@ class API:
                     "error": None
                 }
         except Exception as e:
-            print(f"[VCTT] Error getting status: {e}")
-            import traceback
-            traceback.print_exc()
+            logger.exception("[VCTT] Error getting status: %s", e)
             return {
                 "installed": False,
                 "configured": False,
@@ -693,20 +2004,20 @@ class API:
             else:
                 # If check_only_provided_path is True, fail immediately without scanning
                 if check_only_provided_path:
-                    raise Exception(f"VCTT installation not found at {vctt_path}. Please ensure the installation completed successfully in the terminal window.")
+                    raise NotFound(f"VCTT installation not found at {vctt_path}. Please ensure the installation completed successfully in the terminal window.")
                 
                 # Otherwise, try to find VCTT installations (fallback for browsing existing installations)
                 found = VCTTInterface.find_vctt_installations()
                 if found:
                     vctt_app_dir = found[0]
-                    print(f"Found VCTT installation at: {vctt_app_dir}")
+                    logger.info(f"Found VCTT installation at: {vctt_app_dir}")
                 else:
-                    raise Exception(f"VCTT installation not found at {vctt_path}. Please ensure VCTT is installed.")
+                    raise NotFound(f"VCTT installation not found at {vctt_path}. Please ensure VCTT is installed.")
             
             # Validate it's a real VCTT installation
             main_py = vctt_app_dir / "main.py"
             if not main_py.exists():
-                raise Exception(f"main.py not found in {vctt_app_dir}. This doesn't appear to be a valid VCTT installation.")
+                raise NotFound(f"main.py not found in {vctt_app_dir}. This doesn't appear to be a valid VCTT installation.")
             
             # Check for launch script - prefer using launch script over python main.py
             launch_bat = vctt_app_dir / "launch_vctt.bat"
@@ -723,7 +2034,7 @@ class API:
             else:
                 # Fallback to python main.py if launch script doesn't exist
                 executable = str(main_py)
-                print(f"Warning: Launch script not found, using python main.py directly")
+                logger.warning("VCTT launch script not found, using python main.py directly")
             
             # Check if VCTT is already configured
             for app in config.local_apps:
@@ -742,7 +2053,7 @@ class API:
                         app.conda_env = conda_env
                         app.shell_command = None
                     config.save()
-                    print(f"Updated VCTT configuration: {app.id} (using {'launch script' if use_launch_script else 'python main.py'})")
+                    logger.info(f"Updated VCTT configuration: {app.id} (using {'launch script' if use_launch_script else 'python main.py'})")
                     return app.id
             
             # Create new VCTT app config
@@ -776,12 +2087,13 @@ class API:
             
             config.local_apps.append(vctt_app)
             config.save()
+            self._notify_config_changed()
             
-            print(f"VCTT configured as local app: {app_id} at {vctt_app_dir}")
+            logger.info(f"VCTT configured as local app: {app_id} at {vctt_app_dir}")
             return app_id
             
         except Exception as e:
-            raise Exception(f"Failed to configure VCTT app: {e}")
+            raise command_error(e, "Failed to configure VCTT app")
 
 
     def browse_folder(self, title: str = "Select Folder") -> Optional[str]:
@@ -815,25 +2127,34 @@ class API:
                 return folder_path
             return None
         except Exception as e:
-            print(f"Error opening folder dialog: {e}")
-            import traceback
-            traceback.print_exc()
+            logger.exception("Error opening folder dialog: %s", e)
             return None
     
-    def open_url(self, url: str) -> bool:
-        """Open a URL in the default browser"""
+    def open_url(self, url: str) -> Dict[str, Any]:
+        """
+        Open a URL in the preferred browser (falling back to the default one).
+        
+        Returns {'opened': bool, 'browser': str} naming the browser actually used.
+        """
         try:
             import webbrowser
             import platform
             
+            preferred = AppConfig.load().preferences.preferred_browser
+            if preferred:
+                if self._open_in_browser(url, preferred):
+                    logger.info(f"Opened URL in {preferred}: {url}")
+                    return {'opened': True, 'browser': preferred}
+                logger.warning(f"Preferred browser '{preferred}' not found, using the default browser")
+            
             # On macOS, PyWebView can sometimes interfere with webbrowser
             # Use subprocess as a fallback
             if platform.system() == 'Darwin':
                 import subprocess
                 try:
                     subprocess.run(['open', url], check=True)
-                    print(f"Opened URL in browser: {url}")
-                    return True
+                    logger.info(f"Opened URL in browser: {url}")
+                    return {'opened': True, 'browser': 'default'}
                 except subprocess.CalledProcessError:
                     # Fallback to webbrowser
                     pass
@@ -841,22 +2162,33 @@ class API:
             # Standard approach for other platforms or as fallback
             success = webbrowser.open(url)
             if success:
-                print(f"Opened URL in browser: {url}")
+                logger.info(f"Opened URL in browser: {url}")
             else:
-                print(f"Failed to open URL: {url}")
-            return success
+                logger.warning(f"Failed to open URL: {url}")
+            return {'opened': success, 'browser': 'default'}
         except Exception as e:
-            print(f"Error opening URL: {e}")
-            import traceback
-            traceback.print_exc()
+            logger.exception("Error opening URL %s: %s", url, e)
+            return {'opened': False, 'browser': 'default'}
+    
+    def _open_in_browser(self, url: str, browser: str) -> bool:
+        """Open a URL in a named browser, returning False if it can't be found"""
+        import platform
+        import shutil
+        import subprocess
+        import webbrowser
+        
+        # macOS takes application names, e.g. "Google Chrome"
+        if platform.system() == 'Darwin':
+            return subprocess.run(['open', '-a', browser, url]).returncode == 0
+        
+        try:
+            return webbrowser.get(browser).open(url)
+        except webbrowser.Error:
+            pass
+        
+        # Not registered with webbrowser - try it as an executable on PATH
+        executable = shutil.which(browser)
+        if not executable:
             return False
-
-
-# Global API instance
-_api = API()
-
-
-def get_api() -> API:
-    """Get the global API instance"""
-    return _api
-
+        webbrowser.register(browser, None, webbrowser.BackgroundBrowser(executable))
+        return webbrowser.get(browser).open(url)
@@ -0,0 +1,38 @@
+"""Application log file setup (rotating file in the config directory plus the console)"""
+import logging
+import logging.handlers
+from pathlib import Path
+from config import AppConfig
+
+LOG_FILE_NAME = "orchestrator.log"
+LOG_MAX_BYTES = 5 * 1024 * 1024
+LOG_BACKUP_COUNT = 3
+LOG_FORMAT = "%(asctime)s %(levelname)-7s %(name)s: %(message)s"
+
+
+def get_log_path() -> Path:
+    """File the orchestrator's own log is written to"""
+    return AppConfig.get_config_path().parent / LOG_FILE_NAME
+
+
+def setup_logging(level: str = "INFO") -> None:
+    """Send log records at the given level to a rotating log file and the console"""
+    numeric_level = getattr(logging, str(level).upper(), None)
+    unknown_level = not isinstance(numeric_level, int)
+    if unknown_level:
+        numeric_level = logging.INFO
+    
+    formatter = logging.Formatter(LOG_FORMAT)
+    file_handler = logging.handlers.RotatingFileHandler(
+        get_log_path(), maxBytes=LOG_MAX_BYTES, backupCount=LOG_BACKUP_COUNT, encoding='utf-8'
+    )
+    file_handler.setFormatter(formatter)
+    console_handler = logging.StreamHandler()
+    console_handler.setFormatter(formatter)
+    
+    root = logging.getLogger()
+    root.setLevel(numeric_level)
+    root.handlers = [file_handler, console_handler]
+    # Logged once the handlers are in place, so it reaches the file too
+    if unknown_level:
+        logging.getLogger(__name__).warning("Unknown log level %r, using INFO", level)
@@ -0,0 +1,115 @@
+"""Launch-at-login registration for Windows, macOS and Linux"""
+import logging
+import os
+import subprocess
+import sys
+from pathlib import Path
+from typing import List
+
+logger = logging.getLogger(__name__)
+
+APP_NAME = "Platonic"
+LAUNCH_AGENT_LABEL = "com.platonic.orchestrator"
+WINDOWS_RUN_KEY = r"Software\Microsoft\Windows\CurrentVersion\Run"
+
+
+def get_launch_command() -> List[str]:
+    """Command that starts this app (the bundled executable, or python + main.py in development)"""
+    if getattr(sys, 'frozen', False):
+        return [sys.executable]
+    return [sys.executable, str(Path(__file__).parent / 'main.py')]
+
+
+def _mac_plist_path() -> Path:
+    return Path.home() / 'Library' / 'LaunchAgents' / f'{LAUNCH_AGENT_LABEL}.plist'
+
+
+def _linux_desktop_path() -> Path:
+    config_home = os.environ.get('XDG_CONFIG_HOME') or str(Path.home() / '.config')
+    return Path(config_home) / 'autostart' / 'orchestrator-app.desktop'
+
+
+def is_enabled() -> bool:
+    """Whether the OS is currently set to launch the app at login"""
+    if sys.platform == 'win32':
+        import winreg
+        try:
+            with winreg.OpenKey(winreg.HKEY_CURRENT_USER, WINDOWS_RUN_KEY) as key:
+                winreg.QueryValueEx(key, APP_NAME)
+            return True
+        except FileNotFoundError:
+            return False
+    elif sys.platform == 'darwin':
+        return _mac_plist_path().exists()
+    else:
+        return _linux_desktop_path().exists()
+
+
+def enable() -> None:
+    """Register the app to launch at login"""
+    command = get_launch_command()
+    
+    if sys.platform == 'win32':
+        import winreg
+        with winreg.CreateKey(winreg.HKEY_CURRENT_USER, WINDOWS_RUN_KEY) as key:
+            winreg.SetValueEx(key, APP_NAME, 0, winreg.REG_SZ, subprocess.list2cmdline(command))
+    elif sys.platform == 'darwin':
+        arguments = '\n'.join(f'        <string>{arg}</string>' for arg in command)
+        plist = f"""<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{LAUNCH_AGENT_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"""
+        path = _mac_plist_path()
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(plist)
+    else:
+        import shlex
+        desktop = f"""[Desktop Entry]
+Type=Application
+Name={APP_NAME}
+Exec={shlex.join(command)}
+X-GNOME-Autostart-enabled=true
+"""
+        path = _linux_desktop_path()
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(desktop)
+    
+    logger.info(f"Enabled launch at login: {command}")
+
+
+def disable() -> None:
+    """Remove the app's launch-at-login registration"""
+    if sys.platform == 'win32':
+        import winreg
+        try:
+            with winreg.OpenKey(winreg.HKEY_CURRENT_USER, WINDOWS_RUN_KEY, 0, winreg.KEY_SET_VALUE) as key:
+                winreg.DeleteValue(key, APP_NAME)
+        except FileNotFoundError:
+            pass
+    elif sys.platform == 'darwin':
+        _mac_plist_path().unlink(missing_ok=True)
+    else:
+        _linux_desktop_path().unlink(missing_ok=True)
+    
+    logger.info("Disabled launch at login")
+
+
+def reconcile(enabled: bool) -> None:
+    """Make the OS autostart state match the startup_launch preference"""
+    if enabled == is_enabled():
+        return
+    if enabled:
+        enable()
+    else:
+        disable()
@@ -0,0 +1,31 @@
+"""Copying text to the system clipboard using each platform's clipboard tool"""
+import shutil
+import subprocess
+import sys
+from typing import List
+
+# Linux clipboard tools, tried in order (Wayland first)
+LINUX_CLIPBOARD_COMMANDS = [
+    ['wl-copy'],
+    ['xclip', '-selection', 'clipboard'],
+    ['xsel', '--clipboard', '--input'],
+]
+
+
+def _clipboard_command() -> List[str]:
+    """Command that reads the text to copy from stdin"""
+    if sys.platform == 'win32':
+        return ['clip']
+    if sys.platform == 'darwin':
+        return ['pbcopy']
+    for command in LINUX_CLIPBOARD_COMMANDS:
+        if shutil.which(command[0]):
+            return command
+    raise Exception("No clipboard tool found (install wl-clipboard, xclip or xsel)")
+
+
+def copy_text(text: str) -> None:
+    """Put text on the system clipboard"""
+    # clip.exe expects UTF-16 to handle non-ASCII text
+    encoding = 'utf-16-le' if sys.platform == 'win32' else 'utf-8'
+    subprocess.run(_clipboard_command(), input=text.encode(encoding), check=True, timeout=5)
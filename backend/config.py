@@ -1,9 +1,31 @@
 """Configuration management for Orchestrator App"""
 import json
+import logging
 import os
+import re
+import shutil
+import uuid
+from copy import deepcopy
 from dataclasses import dataclass, field, asdict
-from typing import Optional, List, Dict, Any, Tuple
+from enum import Enum
+from typing import Optional, List, Dict, Any, Iterable, Set, Tuple
 from pathlib import Path
+import paramiko
+from keychain import KEYCHAIN_PLACEHOLDER, store_secret, load_secret, delete_secret
+
+logger = logging.getLogger(__name__)
+
+# Environment variable that points the app at a custom config directory
+CONFIG_DIR_ENV_VAR = 'ORCHESTRATOR_CONFIG_DIR'
+
+# Current config.json schema version; older files are migrated up to it on load
+CONFIG_VERSION = '2.0'
+
+# Sensitive fields kept in the OS keychain (keyed by server id) rather than config.json
+SECRET_FIELDS = ('password', 'ssh_key_passphrase')
+
+# depends_on entries starting with this name a server's portal (portal:<server_id>) rather than an app
+PORTAL_DEPENDENCY_PREFIX = 'portal:'
 
 
 @dataclass
@@ -16,6 +38,107 @@ class ServiceConfig:
     path: str
     healthcheck_path: str = "/"
     pre_launch_command: Optional[str] = None  # Optional command to run before starting service
+    scheme: str = "http"  # http or https, for the URL the service is opened at
+    self_signed_cert: bool = False  # The https certificate is self-signed, so browser warnings are expected
+
+
+@dataclass
+class JumpHostConfig:
+    """Bastion host used to reach a server that isn't directly reachable"""
+    host: str
+    port: int = 22
+    username: str = ""
+    ssh_key_path: str = ""
+    password: Optional[str] = None
+    ssh_key_passphrase: Optional[str] = None
+
+
+@dataclass
+class MaintenanceCommand:
+    """A canned command run on a server from the status window, e.g. pruning images"""
+    name: str
+    command: str  # {path} is replaced with the first service's (quoted) path
+    description: str = ""
+
+
+def generate_id(prefix: str, taken: Iterable[str] = ()) -> str:
+    """A random id like server-1a2b3c4d5e6f, retried until it isn't one of taken"""
+    taken = set(taken)
+    while True:
+        new_id = f"{prefix}-{uuid.uuid4().hex[:12]}"
+        if new_id not in taken:
+            return new_id
+
+
+def _reordered(items: List[Any], ids: List[str], kind: str) -> List[Any]:
+    """items (anything with an id) sorted to match ids, rejecting unknown, missing or repeated ids"""
+    by_id = {item.id: item for item in items}
+    unknown = [i for i in ids if i not in by_id]
+    if unknown:
+        raise ValueError(f"Unknown {kind} ids: {', '.join(unknown)}")
+    missing = [i for i in by_id if i not in ids]
+    if missing:
+        raise ValueError(f"Missing {kind} ids: {', '.join(missing)}")
+    if len(set(ids)) != len(ids):
+        raise ValueError(f"Repeated {kind} ids in the new order")
+    return [by_id[i] for i in ids]
+
+
+def unique_key_paths(paths: List[str]) -> List[str]:
+    """Strip, drop blanks and de-duplicate key paths, keeping their order"""
+    unique = []
+    for path in paths:
+        path = (path or '').strip()
+        if path and path not in unique:
+            unique.append(path)
+    return unique
+
+
+def split_host_port(host: str, port: int) -> Tuple[str, int]:
+    """
+    Split a host entered as "host:port", "[v6]:port" or "[v6]" into a bare host and a port.
+    
+    Anything else, including a bare IPv6 literal such as "::1", keeps the given port.
+    """
+    host = (host or '').strip()
+    if host.startswith('[') and ']' in host:
+        address, _, rest = host[1:].partition(']')
+        if not rest:
+            return address, port
+        if rest.startswith(':') and rest[1:].isdigit():
+            return address, int(rest[1:])
+    elif host.count(':') == 1:
+        name, _, port_text = host.partition(':')
+        if name and port_text.isdigit():
+            return name, int(port_text)
+    return host, port
+
+
+# ServerConfig fields overriding paramiko's algorithm preferences for legacy servers
+ALGORITHM_FIELDS = ('kex_algorithms', 'host_key_algorithms', 'ciphers')
+
+
+def supported_algorithms(field_name: str) -> List[str]:
+    """Algorithm names paramiko implements for one of ALGORITHM_FIELDS"""
+    # paramiko has no public list; these are the tables its SecurityOptions validate against
+    tables = {
+        'kex_algorithms': paramiko.Transport._kex_info,
+        'host_key_algorithms': paramiko.Transport._key_info,
+        'ciphers': paramiko.Transport._cipher_info,
+    }
+    return list(tables[field_name])
+
+
+def url_host(host: str) -> str:
+    """A host as written in a URL, with IPv6 literals in brackets"""
+    return f"[{host}]" if ':' in host and not host.startswith('[') else host
+
+
+class AuthMethod(str, Enum):
+    """How to authenticate to a server"""
+    KEY = "key"  # ssh_key_path, falling back to password if one is set
+    PASSWORD = "password"
+    AGENT = "agent"  # Identities from the running ssh-agent (e.g. hardware-backed keys)
 
 
 @dataclass
@@ -28,6 +151,88 @@ class ServerConfig:
     username: str
     ssh_key_path: str
     services: List[ServiceConfig] = field(default_factory=list)
+    password: Optional[str] = None  # Used when the key is missing or rejected
+    ssh_key_passphrase: Optional[str] = None  # None means the key is not encrypted
+    known_hosts_path: Optional[str] = None  # Defaults to ~/.ssh/known_hosts
+    strict_host_key_checking: bool = True  # Reject unknown or mismatched host keys
+    connect_timeout_secs: int = 10  # TCP connect, handshake and auth
+    command_timeout_secs: int = 30  # Per remote command
+    portal_ready_timeout_secs: int = 120  # How long launch waits for the health check
+    jump_host: Optional[JumpHostConfig] = None  # Connect through this bastion if set
+    # Command whose stdin/stdout carry the SSH connection instead of TCP, e.g. "cloudflared access ssh --hostname %h".
+    # %h, %p and %r expand to host, port and username as in OpenSSH. See docs/README-PYTHON.md for limitations.
+    proxy_command: Optional[str] = None
+    compose_file: Optional[str] = None  # Passed as -f; None uses docker-compose.yml in the service path
+    compose_profiles: List[str] = field(default_factory=list)  # Each passed as --profile
+    # Prefix for every compose invocation, run as written, e.g. "docker-compose" (v1) or "/usr/local/bin/docker compose"
+    compose_command: str = "docker compose"
+    use_sudo: bool = False  # Run docker commands with sudo -n, for SSH users outside the docker group
+    maintenance_commands: List[MaintenanceCommand] = field(default_factory=list)  # The only commands run_maintenance runs
+    # Replace paramiko's algorithm preferences, in order, for old servers paramiko's defaults can't negotiate with.
+    # Empty keeps the defaults. Legacy algorithms weaken the connection's security; see docs/README-PYTHON.md.
+    kex_algorithms: List[str] = field(default_factory=list)
+    host_key_algorithms: List[str] = field(default_factory=list)
+    ciphers: List[str] = field(default_factory=list)
+    auth_method: AuthMethod = AuthMethod.KEY
+    ssh_key_paths: List[str] = field(default_factory=list)  # More keys to try, in order, after ssh_key_path
+    health_scheme: str = "http"  # http or https (certificates aren't verified)
+    health_host: str = "localhost"  # Host the health check curls, as seen from the server
+    health_path: Optional[str] = None  # Overrides every service's healthcheck_path when set
+    # Status codes counted as healthy; "2xx" accepts a whole class
+    health_accepted_statuses: List[str] = field(default_factory=lambda: ["200"])
+    
+    def __post_init__(self):
+        # Stored as a plain string / dictionaries in config.json
+        self.auth_method = AuthMethod(self.auth_method)
+        # Accept "host:port" and bracketed IPv6 in the host field
+        self.host, self.port = split_host_port(self.host, self.port)
+        self.maintenance_commands = [
+            MaintenanceCommand(**c) if isinstance(c, dict) else c for c in self.maintenance_commands
+        ]
+    
+    def get_maintenance_command(self, name: str) -> Optional[MaintenanceCommand]:
+        """Get a maintenance command by name"""
+        for command in self.maintenance_commands:
+            if command.name == name:
+                return command
+        return None
+    
+    def service_url(self, service: ServiceConfig) -> str:
+        """URL a service is reached at from this machine"""
+        return f"{service.scheme}://{url_host(self.host)}:{service.port}"
+    
+    def portal_url(self) -> Optional[str]:
+        """URL of the portal (the first service), or None if no services are configured"""
+        return self.service_url(self.services[0]) if self.services else None
+    
+    def key_paths(self) -> List[str]:
+        """Every configured key, ssh_key_path first, without blanks or duplicates"""
+        return unique_key_paths([self.ssh_key_path] + list(self.ssh_key_paths))
+    
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> 'ServerConfig':
+        """Create a ServerConfig from its JSON dictionary form"""
+        server_dict = {k: v for k, v in data.items() if k not in ('services', 'jump_host')}
+        services = [ServiceConfig(**s) for s in data.get('services', [])]
+        jump_host = JumpHostConfig(**data['jump_host']) if data.get('jump_host') else None
+        return cls(**server_dict, services=services, jump_host=jump_host)
+
+
+class RestartPolicy(str, Enum):
+    """When a local app should be relaunched after it exits"""
+    NEVER = "never"
+    ON_FAILURE = "on_failure"  # Only after a non-zero exit code
+    ALWAYS = "always"
+
+
+@dataclass
+class HealthCheck:
+    """How to tell a local app is ready: a TCP port accepting connections or an HTTP URL returning 200"""
+    port: Optional[int] = None
+    url: Optional[str] = None
+    host: str = "127.0.0.1"  # Used with port
+    timeout_secs: float = 2.0
+    startup_timeout_secs: Optional[float] = None  # If set, launching waits up to this long for the check to pass
 
 
 @dataclass
@@ -42,6 +247,64 @@ class LocalAppConfig:
     shell_command: Optional[str] = None
     install_dependencies: bool = False  # Install dependencies before launch
     requirements_file: Optional[str] = None  # Path to requirements.txt
+    args: List[str] = field(default_factory=list)  # Extra command-line arguments
+    env: Dict[str, str] = field(default_factory=dict)  # Extra environment variables
+    restart_policy: RestartPolicy = RestartPolicy.NEVER
+    max_restarts: int = 5  # Give up after this many consecutive crash restarts
+    health_check: Optional[HealthCheck] = None
+    create_working_dir: bool = False  # Create working_directory at launch if it doesn't exist
+    # Process name or command-line substring identifying an already-running copy started outside the app
+    singleton_match: Optional[str] = None
+    auto_launch: bool = False  # Launched by launch_all along with the portal
+    depends_on: List[str] = field(default_factory=list)  # App ids or portal:<server_id>, launched and healthy first
+    hide_window: bool = False  # Windows: start without a console window (CREATE_NO_WINDOW), for GUI apps
+    # Run in its own session (DETACHED_PROCESS on Windows) and keep running when the orchestrator quits
+    detached: bool = False
+    
+    def __post_init__(self):
+        # Stored as a plain string / dictionary in config.json
+        self.restart_policy = RestartPolicy(self.restart_policy)
+        if isinstance(self.health_check, dict):
+            self.health_check = HealthCheck(**self.health_check)
+    
+    def launch_problems(self) -> List[Tuple[str, str]]:
+        """(field, message) pairs for settings that would make a launch fail before the process starts"""
+        problems = []
+        working_dir = self.working_directory
+        if working_dir and not self.create_working_dir:
+            if not os.path.exists(working_dir):
+                problems.append(('working_directory', f"Working directory not found: {working_dir}"))
+            elif not os.path.isdir(working_dir):
+                problems.append(('working_directory', f"Working directory is not a directory: {working_dir}"))
+        
+        # Shell launches may name a script or a command line, so only direct execution is checked
+        if not self.use_shell:
+            executable = self.resolve_executable()
+            if not self.executable_path:
+                problems.append(('executable_path', "Executable path is required"))
+            elif executable is None:
+                problems.append(('executable_path', f"Executable '{self.executable_path}' not found on PATH"))
+            elif not os.path.isfile(executable):
+                problems.append(('executable_path', f"Executable not found: {executable}"))
+            elif not os.access(executable, os.X_OK):
+                problems.append(('executable_path', f"Executable is not executable (check its permissions): {executable}"))
+        return problems
+    
+    def resolve_executable(self) -> Optional[str]:
+        """
+        Path executable_path refers to, checked in this order:
+        
+        1. An absolute path is used as is.
+        2. A relative path (one containing a directory separator, e.g. ./vctt.exe or bin/app)
+           is resolved against working_directory, or the orchestrator's directory if unset.
+        3. A bare name (e.g. python) is looked up on PATH; None if it isn't found.
+        """
+        executable = self.executable_path
+        if not executable or os.path.isabs(executable):
+            return executable
+        if os.path.dirname(executable):
+            return os.path.abspath(os.path.join(self.working_directory or '', executable))
+        return shutil.which(executable)
 
 
 @dataclass
@@ -51,6 +314,30 @@ class Preferences:
     minimize_to_tray: bool = True
     startup_launch: bool = False
     setup_completed: bool = False  # Flag to track if first-run setup wizard has been completed
+    enable_notifications: bool = True  # Desktop notifications for portal/app state changes
+    preferred_browser: Optional[str] = None  # e.g. "chrome" or "Google Chrome" on macOS; None uses the OS default
+    connect_retry_attempts: int = 3  # Tries per connection for transient network/handshake failures
+    connect_retry_backoff_secs: float = 1.0  # Delay before the first retry, doubled after each one
+    log_level: str = "INFO"  # DEBUG, INFO, WARNING or ERROR for the orchestrator's log file
+    status_cache_secs: float = 3.0  # Repeated get_status calls within this window reuse the last result
+    default_server_id: Optional[str] = None  # Server the tray's top-level portal item acts on; None uses the first
+    keep_warm: bool = False  # Hold a connection to the default server open so launches skip the SSH handshake
+    enable_local_api: bool = False  # Serve the loopback HTTP control API (see local_api.py)
+    local_api_port: int = 0  # 0 picks a free port; clients read it from local_api.json
+    stop_portal_on_quit: bool = False  # Quitting runs compose down on services this session started
+    health_monitor_interval_secs: int = 15  # Background polls behind the tray and health alerts; 0 turns them off
+    health_alert_cooldown_secs: int = 300  # Minimum gap between repeat alerts for the same container or portal
+    max_log_size_mb: int = 10  # Captured app logs over this are rotated to <app>.log.1; 0 for no limit
+    max_log_age_days: int = 30  # Captured app logs untouched this long are deleted; 0 keeps them forever
+
+
+@dataclass
+class ValidationError:
+    """A single problem found when validating configuration"""
+    server_id: Optional[str]
+    field: Optional[str]  # ServerConfig (or LocalAppConfig) field name, for highlighting in the settings form
+    message: str
+    app_id: Optional[str] = None  # Set for local app problems
 
 
 @dataclass
@@ -63,8 +350,11 @@ class AppConfig:
 
     @staticmethod
     def get_config_path() -> Path:
-        """Get the config file path"""
-        if os.name == 'nt':  # Windows
+        """Get the config file path (ORCHESTRATOR_CONFIG_DIR overrides the directory, e.g. for portable installs)"""
+        override = os.getenv(CONFIG_DIR_ENV_VAR)
+        if override:
+            config_dir = Path(override).expanduser()
+        elif os.name == 'nt':  # Windows
             config_dir = Path(os.getenv('APPDATA')) / 'orchestrator-app'
         else:  # Mac/Linux
             config_dir = Path.home() / '.config' / 'orchestrator-app'
@@ -81,51 +371,109 @@ class AppConfig:
             return cls(version="2.0")
         
         try:
-            with open(config_path, 'r') as f:
-                data = json.load(f)
-            
+            data = cls._read_json(config_path)
+        except ValueError as e:
+            # Truncated or otherwise unparseable, e.g. the app was killed mid-write before saves were atomic
+            backup_path = cls.get_backup_path()
+            logger.warning("Config file %s is corrupt (%s), trying backup %s", config_path, e, backup_path)
+            try:
+                data = cls._read_json(backup_path)
+                logger.info(f"Recovered configuration from backup {backup_path}")
+            except Exception as backup_error:
+                logger.error("Could not recover configuration from backup: %s", backup_error)
+                return cls(version="2.0")
+        
+        try:
             version = data.get('version', '1.0')
             
             # Migrate from v1.0 to v2.0
             if version == '1.0':
-                print("Migrating config from v1.0 to v2.0...")
+                logger.info("Migrating config from v1.0 to v2.0...")
                 data = cls._migrate_v1_to_v2(data)
-                version = '2.0'
+                version = CONFIG_VERSION
             
-            # Convert dictionaries to dataclass instances
-            servers = []
+            # Pull secrets back out of the OS keychain
             for server_data in data.get('servers', []):
-                services_data = server_data.get('services', [])
-                services = [ServiceConfig(**s) for s in services_data]
-                
-                # Create ServerConfig without 'services' key first
-                server_dict = {k: v for k, v in server_data.items() if k != 'services'}
-                server = ServerConfig(**server_dict, services=services)
-                servers.append(server)
-            
-            local_apps = [LocalAppConfig(**app) for app in data.get('local_apps', [])]
-            preferences = Preferences(**data.get('preferences', {}))
+                for prefix, secret_holder in cls._secret_holders(server_data):
+                    for secret_field in SECRET_FIELDS:
+                        if secret_holder.get(secret_field) == KEYCHAIN_PLACEHOLDER:
+                            # One that can't be read keeps its placeholder, so the next save leaves it stored
+                            secret = load_secret(f"{prefix}.{secret_field}")
+                            if secret is not None:
+                                secret_holder[secret_field] = secret
             
-            config = cls(
-                version=version,
-                servers=servers,
-                local_apps=local_apps,
-                preferences=preferences
-            )
+            # Convert dictionaries to dataclass instances
+            data['version'] = version
+            config = cls.from_dict(data)
             
             # Auto-save migrated config
             if data.get('_migrated', False):
-                print("Saving migrated config...")
+                logger.info("Saving migrated config...")
                 config.save()
             
             return config
             
         except Exception as e:
-            print(f"Error loading config: {e}")
-            import traceback
-            traceback.print_exc()
+            logger.exception("Error loading config: %s", e)
             return cls(version="2.0")
     
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> 'AppConfig':
+        """Create an AppConfig from its JSON dictionary form (already migrated, secrets resolved)"""
+        return cls(
+            version=data.get('version', CONFIG_VERSION),
+            servers=[ServerConfig.from_dict(s) for s in data.get('servers', [])],
+            local_apps=[LocalAppConfig(**app) for app in data.get('local_apps', [])],
+            preferences=Preferences(**data.get('preferences', {}))
+        )
+    
+    def to_dict(self, include_secrets: bool = True) -> Dict[str, Any]:
+        """JSON dictionary form of the config, optionally with passwords and passphrases blanked"""
+        data = {
+            'version': self.version,
+            'servers': [asdict(s) for s in self.servers],
+            'local_apps': [asdict(app) for app in self.local_apps],
+            'preferences': asdict(self.preferences)
+        }
+        if not include_secrets:
+            for server_data in data['servers']:
+                for _, secret_holder in self._secret_holders(server_data):
+                    for secret_field in SECRET_FIELDS:
+                        secret_holder[secret_field] = None
+        return data
+    
+    def to_masked_dict(self) -> Dict[str, Any]:
+        """JSON dictionary form for the webview, with any set password or passphrase replaced by KEYCHAIN_PLACEHOLDER"""
+        data = self.to_dict()
+        for server_data in data['servers']:
+            for _, secret_holder in self._secret_holders(server_data):
+                for secret_field in SECRET_FIELDS:
+                    if secret_holder.get(secret_field):
+                        secret_holder[secret_field] = KEYCHAIN_PLACEHOLDER
+        return data
+    
+    def unmask_secrets(self, server_data: Dict[str, Any]) -> Dict[str, Any]:
+        """Swap KEYCHAIN_PLACEHOLDER values in a server dictionary from the webview back to this config's stored secrets"""
+        server = self.get_server(server_data.get('id')) if server_data.get('id') else None
+        stored_holders = dict(self._secret_holders(asdict(server))) if server else {}
+        for prefix, secret_holder in self._secret_holders(server_data):
+            stored = stored_holders.get(prefix) or {}
+            for secret_field in SECRET_FIELDS:
+                if secret_holder.get(secret_field) == KEYCHAIN_PLACEHOLDER:
+                    secret_holder[secret_field] = stored.get(secret_field)
+        return server_data
+    
+    @staticmethod
+    def get_backup_path() -> Path:
+        """Copy of the previous config.json, kept by save() for recovery"""
+        return AppConfig.get_config_path().with_suffix('.json.bak')
+    
+    @staticmethod
+    def _read_json(path: Path) -> Dict[str, Any]:
+        """Read a JSON file, raising ValueError if it can't be parsed"""
+        with open(path, 'r', encoding='utf-8') as f:
+            return json.load(f)
+    
     @staticmethod
     def _migrate_v1_to_v2(data: Dict[str, Any]) -> Dict[str, Any]:
         """Migrate config from v1.0 to v2.0 format"""
@@ -160,7 +508,7 @@ class AppConfig:
                 migrated_servers.append(server)
         
         data['servers'] = migrated_servers
-        data['version'] = '2.0'
+        data['version'] = CONFIG_VERSION
         data['_migrated'] = True
         
         return data
@@ -171,18 +519,148 @@ class AppConfig:
         
         try:
             # Convert dataclasses to dictionaries
-            data = {
-                'version': self.version,
-                'servers': [asdict(s) for s in self.servers],
-                'local_apps': [asdict(app) for app in self.local_apps],
-                'preferences': asdict(self.preferences)
-            }
-            
-            with open(config_path, 'w') as f:
+            data = self.to_dict()
+            
+            # Move secrets into the OS keychain, leaving placeholders in the JSON
+            # (falls back to plaintext if no keychain is available)
+            kept_keys = set()
+            for server_data in data['servers']:
+                for prefix, secret_holder in self._secret_holders(server_data):
+                    for secret_field in SECRET_FIELDS:
+                        key = f"{prefix}.{secret_field}"
+                        value = secret_holder.get(secret_field)
+                        if value == KEYCHAIN_PLACEHOLDER:
+                            # Couldn't be read at load time; the keychain still has it
+                            kept_keys.add(key)
+                        elif value and store_secret(key, value):
+                            secret_holder[secret_field] = KEYCHAIN_PLACEHOLDER
+                            kept_keys.add(key)
+            
+            # Drop keychain entries for fields that were cleared and servers or jump hosts that were removed
+            for key in self._keychain_keys(config_path) - kept_keys:
+                delete_secret(key)
+            
+            # Keep the previous version, unless it's corrupt and would clobber a good backup
+            if config_path.exists():
+                try:
+                    self._read_json(config_path)
+                    shutil.copy2(config_path, self.get_backup_path())
+                except ValueError:
+                    logger.warning("Not backing up corrupt config file %s", config_path)
+            
+            # Write a temp file in the same directory and rename it over the target, so an
+            # interrupted save never leaves a truncated config.json behind
+            tmp_path = config_path.with_suffix('.json.tmp')
+            with open(tmp_path, 'w', encoding='utf-8') as f:
                 json.dump(data, f, indent=2)
+                f.flush()
+                os.fsync(f.fileno())
+            os.replace(tmp_path, config_path)
         except Exception as e:
             raise Exception(f"Failed to save config: {e}")
 
+    def validate(self) -> List[ValidationError]:
+        """Check every server for missing or invalid connection settings, and every local app for a broken depends_on"""
+        errors = []
+        seen_ids = set()
+        
+        for idx, server in enumerate(self.servers):
+            label = server.name or f"Server {idx + 1}"
+            
+            def error(field_name: Optional[str], message: str):
+                errors.append(ValidationError(server_id=server.id or None, field=field_name, message=f"{label}: {message}"))
+            
+            if not server.id:
+                error('id', "Server id is missing")
+            elif server.id in seen_ids:
+                error('id', f"Duplicate server id '{server.id}'")
+            seen_ids.add(server.id)
+            
+            if not (server.host or '').strip():
+                error('host', "Host is required")
+            if not (server.username or '').strip():
+                error('username', "Username is required")
+            if not isinstance(server.port, int) or not (1 <= server.port <= 65535):
+                error('port', f"Invalid port {server.port}. Port must be between 1 and 65535.")
+            if not (server.compose_command or "").strip():
+                error('compose_command', "Compose command is required (e.g. docker compose)")
+            names = [c.name for c in server.maintenance_commands]
+            for command in server.maintenance_commands:
+                if not command.name.strip() or not command.command.strip():
+                    error('maintenance_commands', "Maintenance commands need a name and a command")
+                elif names.count(command.name) > 1:
+                    error('maintenance_commands', f"Duplicate maintenance command '{command.name}'")
+            for field_name in ALGORITHM_FIELDS:
+                supported = supported_algorithms(field_name)
+                unsupported = [a for a in getattr(server, field_name) if a not in supported]
+                if unsupported:
+                    error(field_name, f"Unsupported {field_name}: {', '.join(unsupported)}. Supported: {', '.join(supported)}")
+            if server.proxy_command and server.jump_host:
+                error('proxy_command', "Use either a jump host or a proxy command, not both")
+            for service in server.services:
+                if service.scheme not in ('http', 'https'):
+                    error('services', f"Invalid scheme '{service.scheme}' for service {service.name}. Use http or https.")
+            
+            key_paths = server.key_paths()
+            if server.auth_method == AuthMethod.PASSWORD:
+                if not server.password:
+                    error('password', "A password is required for password authentication")
+            elif server.auth_method == AuthMethod.KEY:
+                if key_paths and not any(os.path.isfile(os.path.expanduser(p)) for p in key_paths):
+                    error('ssh_key_path', f"SSH key not found at {', '.join(key_paths)}")
+                elif not key_paths and not server.password:
+                    error('ssh_key_path', "An SSH key path or password is required")
+            
+            if server.health_scheme not in ('http', 'https'):
+                error('health_scheme', f"Invalid health check scheme '{server.health_scheme}'. Use http or https.")
+            for status in server.health_accepted_statuses:
+                if not re.fullmatch(r'[1-5](\d\d|xx)', str(status).lower()):
+                    error('health_accepted_statuses', f"Invalid accepted status '{status}'. Use a code like 200 or a class like 2xx.")
+        
+        for idx, app in enumerate(self.local_apps):
+            label = app.name or f"App {idx + 1}"
+            try:
+                self.launch_order([app.id])
+            except ValueError as e:
+                errors.append(ValidationError(server_id=None, app_id=app.id, field='depends_on', message=f"{label}: {e}"))
+        
+        return errors
+    
+    def launch_warnings(self) -> List[ValidationError]:
+        """
+        Local apps whose executable or working directory is missing on this machine. They don't
+        stop a save (the app may not be installed yet, or live on an unmounted drive); launching
+        one still fails with the same message.
+        """
+        return [
+            ValidationError(server_id=None, app_id=app.id, field=field_name, message=f"{app.name or f'App {idx + 1}'}: {message}")
+            for idx, app in enumerate(self.local_apps)
+            for field_name, message in app.launch_problems()
+        ]
+
+    @classmethod
+    def _keychain_keys(cls, config_path: Path) -> Set[str]:
+        """Keychain keys the config file on disk has placeholders for"""
+        try:
+            data = cls._read_json(config_path)
+        except (OSError, ValueError):
+            return set()
+        return {
+            f"{prefix}.{secret_field}"
+            for server_data in data.get('servers', [])
+            for prefix, secret_holder in cls._secret_holders(server_data)
+            for secret_field in SECRET_FIELDS
+            if secret_holder.get(secret_field) == KEYCHAIN_PLACEHOLDER
+        }
+    
+    @staticmethod
+    def _secret_holders(server_data: Dict[str, Any]) -> List[Tuple[str, Dict[str, Any]]]:
+        """(keychain key prefix, dictionary) pairs that may carry secret fields for a server"""
+        holders = [(server_data['id'], server_data)]
+        if server_data.get('jump_host'):
+            holders.append((f"{server_data['id']}.jump_host", server_data['jump_host']))
+        return holders
+
     def get_server(self, server_id: str) -> Optional[ServerConfig]:
         """Get server by ID"""
         for server in self.servers:
@@ -190,6 +668,11 @@ class AppConfig:
                 return server
         return None
     
+    def get_default_server(self) -> Optional[ServerConfig]:
+        """The preferred default server, falling back to the first if unset or deleted"""
+        default = self.get_server(self.preferences.default_server_id) if self.preferences.default_server_id else None
+        return default or (self.servers[0] if self.servers else None)
+    
     def get_service(self, server_id: str, service_id: str) -> Optional[Tuple[ServerConfig, ServiceConfig]]:
         """Get service by server ID and service ID"""
         server = self.get_server(server_id)
@@ -201,6 +684,50 @@ class AppConfig:
                 return (server, service)
         return None
     
+    def new_id(self, prefix: str) -> str:
+        """A fresh id for a server, service or app, unused by anything in this config"""
+        taken = {server.id for server in self.servers} | {app.id for app in self.local_apps}
+        taken |= {service.id for server in self.servers for service in server.services}
+        return generate_id(prefix, taken)
+    
+    def assign_missing_ids(self) -> None:
+        """Give servers, services and apps saved with an empty id a generated one"""
+        for server in self.servers:
+            server.id = server.id or self.new_id('server')
+            for service in server.services:
+                service.id = service.id or self.new_id('service')
+        for app in self.local_apps:
+            app.id = app.id or self.new_id('app')
+    
+    def clone_server(self, server_id: str) -> Optional[ServerConfig]:
+        """
+        Append a copy of a server named "<name> (copy)" with a new id, returning it.
+        
+        Service ids are kept since they're only unique within a server.
+        """
+        server = self.get_server(server_id)
+        if not server:
+            return None
+        
+        clone = deepcopy(server)
+        clone.id = self.new_id('server')
+        names = {s.name for s in self.servers}
+        clone.name = f"{server.name} (copy)"
+        counter = 2
+        while clone.name in names:
+            clone.name = f"{server.name} (copy {counter})"
+            counter += 1
+        self.servers.append(clone)
+        return clone
+    
+    def reorder_servers(self, ids: List[str]) -> None:
+        """Put servers in the order of ids, which must name each server exactly once (ValueError otherwise)"""
+        self.servers = _reordered(self.servers, ids, "server")
+    
+    def reorder_local_apps(self, ids: List[str]) -> None:
+        """Put local apps in the order of ids, which must name each app exactly once (ValueError otherwise)"""
+        self.local_apps = _reordered(self.local_apps, ids, "app")
+    
     def add_service(self, server_id: str, service: ServiceConfig) -> bool:
         """Add a service to a server"""
         server = self.get_server(server_id)
@@ -226,6 +753,40 @@ class AppConfig:
                 return app
         return None
     
+    def launch_order(self, targets: List[str]) -> List[str]:
+        """
+        Targets (app ids or portal:<server_id>) plus everything they depend on, ordered so
+        each item comes after its dependencies. Raises ValueError on unknown ids or a cycle.
+        """
+        order: List[str] = []
+        path: List[str] = []  # Apps currently being visited, for reporting cycles
+        
+        def visit(item: str) -> None:
+            if item in order:
+                return
+            if item in path:
+                cycle = path[path.index(item):] + [item]
+                raise ValueError(f"Dependency cycle: {' -> '.join(cycle)}")
+            if item.startswith(PORTAL_DEPENDENCY_PREFIX):
+                server_id = item[len(PORTAL_DEPENDENCY_PREFIX):]
+                if not self.get_server(server_id):
+                    raise ValueError(f"Unknown server in dependency: {item}")
+                order.append(item)
+                return
+            
+            app = self.get_local_app(item)
+            if not app:
+                raise ValueError(f"Unknown app in dependency: {item}")
+            path.append(item)
+            for dependency in app.depends_on:
+                visit(dependency)
+            path.pop()
+            order.append(item)
+        
+        for target in targets:
+            visit(target)
+        return order
+    
     def is_first_run(self) -> bool:
         """Check if this is a first-run (setup wizard not completed)"""
         return not self.preferences.setup_completed and len(self.servers) == 0
@@ -0,0 +1,132 @@
+"""Connection pool for reusing SSH sessions across commands"""
+import json
+import logging
+import threading
+from contextlib import contextmanager
+from dataclasses import asdict
+from typing import Dict, Iterator, Optional, Tuple
+from config import AppConfig, ServerConfig
+from ssh_client import SSHClient
+
+logger = logging.getLogger(__name__)
+
+# Seconds between keepalive packets on idle pooled connections
+KEEPALIVE_INTERVAL = 30
+# Seconds between keep-warm pings of the default server's connection
+KEEP_WARM_INTERVAL = 20
+# Longest wait between keep-warm reconnect attempts while the default server is unreachable
+KEEP_WARM_MAX_BACKOFF = 300
+
+
+class ConnectionPool:
+    """Caches one SSH connection per server and reconnects when it has died"""
+    
+    def __init__(self):
+        # server_id -> (client, connection settings it was opened with)
+        self.connections: Dict[str, Tuple[SSHClient, str]] = {}
+        self._lock = threading.Lock()  # Guards _server_locks and _keep_warm_stop
+        self._server_locks: Dict[str, threading.Lock] = {}
+        self._keep_warm_stop: Optional[threading.Event] = None  # Set while the keep-warm task runs
+    
+    def _server_lock(self, server_id: str) -> threading.Lock:
+        """Per-server lock so connecting to one server doesn't block the others"""
+        with self._lock:
+            return self._server_locks.setdefault(server_id, threading.Lock())
+    
+    @staticmethod
+    def _settings_key(server: ServerConfig) -> str:
+        """Connection-relevant settings, so edited servers get a fresh connection"""
+        settings = {k: v for k, v in asdict(server).items() if k not in ('services', 'name')}
+        return json.dumps(settings, sort_keys=True, default=str)
+    
+    @staticmethod
+    def _is_alive(client: SSHClient) -> bool:
+        """Check whether a pooled client still has an active transport"""
+        transport = client.client.get_transport() if client.client else None
+        return transport is not None and transport.is_active()
+    
+    def get(self, server: ServerConfig) -> SSHClient:
+        """Get a connected client for a server, reconnecting if needed"""
+        settings_key = self._settings_key(server)
+        
+        with self._server_lock(server.id):
+            cached = self.connections.get(server.id)
+            if cached:
+                client, cached_key = cached
+                if cached_key == settings_key and self._is_alive(client):
+                    return client
+                logger.info("Pooled connection for server %s is stale, reconnecting...", server.id)
+                client.disconnect()
+                del self.connections[server.id]
+            
+            preferences = AppConfig.load().preferences
+            client = SSHClient.from_server(server)
+            client.connect_with_retry(preferences.connect_retry_attempts, preferences.connect_retry_backoff_secs)
+            client.client.get_transport().set_keepalive(KEEPALIVE_INTERVAL)
+            self.connections[server.id] = (client, settings_key)
+            return client
+    
+    @contextmanager
+    def connection(self, server: ServerConfig) -> Iterator[SSHClient]:
+        """Context manager handing out a pooled client (the connection stays open afterwards)"""
+        client = self.get(server)
+        try:
+            yield client
+        except Exception:
+            # Don't hand a broken session to the next caller
+            if not self._is_alive(client):
+                self.disconnect(server.id)
+            raise
+    
+    def disconnect(self, server_id: str) -> None:
+        """Drop the pooled connection for a server"""
+        with self._server_lock(server_id):
+            cached = self.connections.pop(server_id, None)
+        if cached:
+            cached[0].disconnect()
+            logger.info(f"Closed pooled connection for {server_id}")
+    
+    def set_keep_warm(self, enabled: bool) -> None:
+        """Start or stop the background task holding the default server's connection open"""
+        with self._lock:
+            if enabled == (self._keep_warm_stop is not None):
+                return
+            if enabled:
+                self._keep_warm_stop = threading.Event()
+                threading.Thread(target=self._keep_warm, args=(self._keep_warm_stop,), daemon=True).start()
+            else:
+                self._keep_warm_stop.set()
+                self._keep_warm_stop = None
+    
+    def _keep_warm(self, stop: threading.Event) -> None:
+        """Ping the default server every KEEP_WARM_INTERVAL, reconnecting with backoff when it drops"""
+        delay = KEEP_WARM_INTERVAL
+        while not stop.is_set():
+            server = AppConfig.load().get_default_server()
+            if server:
+                try:
+                    client = self.get(server)  # Reconnects if the connection was lost
+                    # Same request OpenSSH's ServerAliveInterval sends; a dead link fails the next check
+                    client.client.get_transport().global_request('keepalive@openssh.com', wait=False)
+                    delay = KEEP_WARM_INTERVAL
+                except Exception as e:
+                    delay = min(delay * 2, KEEP_WARM_MAX_BACKOFF)
+                    logger.warning("Keep-warm connection to server %s failed (%s), retrying in %ss", server.id, e, delay)
+            stop.wait(delay)
+    
+    def disconnect_all(self) -> None:
+        """Close all pooled connections"""
+        for server_id in list(self.connections.keys()):
+            try:
+                self.disconnect(server_id)
+            except:
+                pass
+
+
+# Global connection pool instance
+_connection_pool = ConnectionPool()
+
+
+def get_connection_pool() -> ConnectionPool:
+    """Get the global connection pool instance"""
+    return _connection_pool
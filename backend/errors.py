@@ -0,0 +1,121 @@
+"""Categorised errors raised by API commands, so the frontend can tell failures apart"""
+import socket
+from enum import Enum
+from typing import Dict, Type
+
+
+class ErrorKind(str, Enum):
+    """Broad failure categories; also the class name the frontend sees as error.name"""
+    CONNECTION_FAILED = "ConnectionFailed"
+    AUTH_FAILED = "AuthFailed"
+    HOST_KEY_REJECTED = "HostKeyRejected"
+    COMMAND_FAILED = "CommandFailed"
+    PORT_IN_USE = "PortInUse"
+    SUDO_PASSWORD_REQUIRED = "SudoPasswordRequired"
+    TIMEOUT = "Timeout"
+    CANCELLED = "Cancelled"
+    IN_PROGRESS = "InProgress"
+    MAINTENANCE_MODE = "MaintenanceMode"
+    NOT_FOUND = "NotFound"
+    DOCKER_MISSING = "DockerMissing"
+    INVALID_CONFIG = "InvalidConfig"
+    UNKNOWN = "CommandError"
+
+
+class CommandError(Exception):
+    """
+    An API failure with a human-readable message and a kind.
+
+    pywebview only passes an exception's message and class name to JavaScript, so each
+    kind is its own subclass named after the kind.
+    """
+    kind = ErrorKind.UNKNOWN
+
+
+class ConnectionFailed(CommandError):
+    """The server couldn't be reached, or the SSH handshake failed"""
+    kind = ErrorKind.CONNECTION_FAILED
+
+
+class AuthFailed(CommandError):
+    """The server rejected every configured SSH credential"""
+    kind = ErrorKind.AUTH_FAILED
+
+
+class HostKeyRejected(CommandError):
+    """The server's host key is unknown or doesn't match known_hosts"""
+    kind = ErrorKind.HOST_KEY_REJECTED
+
+
+class CommandFailed(CommandError):
+    """A remote command exited non-zero"""
+    kind = ErrorKind.COMMAND_FAILED
+
+
+class PortInUse(CommandFailed):
+    """docker compose up failed because a host port is already taken on the server"""
+    kind = ErrorKind.PORT_IN_USE
+
+
+class SudoPasswordRequired(CommandFailed):
+    """A docker command run with sudo -n failed because sudo wanted a password"""
+    kind = ErrorKind.SUDO_PASSWORD_REQUIRED
+
+
+class Timeout(CommandError):
+    """A command or health check didn't finish in time"""
+    kind = ErrorKind.TIMEOUT
+
+
+class Cancelled(CommandError):
+    """The user cancelled the operation"""
+    kind = ErrorKind.CANCELLED
+
+
+class InProgress(CommandError):
+    """The same operation is already running, e.g. a second launch of one server's portal"""
+    kind = ErrorKind.IN_PROGRESS
+
+
+class MaintenanceMode(CommandError):
+    """The server is in maintenance mode, so launches, restarts and updates are refused"""
+    kind = ErrorKind.MAINTENANCE_MODE
+
+
+class NotFound(CommandError):
+    """A server, service, app or file doesn't exist"""
+    kind = ErrorKind.NOT_FOUND
+
+
+class DockerMissing(CommandError):
+    """docker or docker compose isn't installed on the server"""
+    kind = ErrorKind.DOCKER_MISSING
+
+
+class InvalidConfig(CommandError):
+    """The configuration is incomplete or invalid for the requested operation"""
+    kind = ErrorKind.INVALID_CONFIG
+
+
+_KIND_CLASSES: Dict[ErrorKind, Type[CommandError]] = {
+    cls.kind: cls for cls in (CommandError, ConnectionFailed, AuthFailed, HostKeyRejected, CommandFailed, PortInUse,
+                              SudoPasswordRequired, Timeout, Cancelled, InProgress, MaintenanceMode, NotFound, DockerMissing, InvalidConfig)
+}
+
+
+def error_kind(e: Exception) -> ErrorKind:
+    """Kind of an exception, guessing from built-in types for errors that aren't CommandErrors"""
+    if isinstance(e, CommandError):
+        return e.kind
+    if isinstance(e, (socket.timeout, TimeoutError)):
+        return ErrorKind.TIMEOUT
+    if isinstance(e, FileNotFoundError):
+        return ErrorKind.NOT_FOUND
+    if isinstance(e, (ConnectionError, socket.gaierror, EOFError)):
+        return ErrorKind.CONNECTION_FAILED
+    return ErrorKind.UNKNOWN
+
+
+def command_error(e: Exception, context: str) -> CommandError:
+    """Wrap an error as '<context>: <message>', keeping its kind"""
+    return _KIND_CLASSES[error_kind(e)](f"{context}: {e}")
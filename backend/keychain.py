@@ -0,0 +1,54 @@
+"""OS keychain storage for sensitive config values (passwords, passphrases)"""
+import logging
+from typing import Optional
+
+logger = logging.getLogger(__name__)
+
+SERVICE_NAME = "orchestrator-app"
+
+# Written to config.json in place of a value that lives in the keychain
+KEYCHAIN_PLACEHOLDER = "__keychain__"
+
+try:
+    import keyring
+except ImportError:
+    keyring = None
+
+
+def store_secret(key: str, value: str) -> bool:
+    """Store a secret in the OS keychain. Returns False if no keychain is available."""
+    if keyring is None:
+        logger.warning("keyring is not installed, storing %r in plaintext config", key)
+        return False
+    try:
+        keyring.set_password(SERVICE_NAME, key, value)
+        return True
+    except Exception as e:
+        logger.warning("OS keychain unavailable (%s), storing %r in plaintext config", e, key)
+        return False
+
+
+def load_secret(key: str) -> Optional[str]:
+    """Load a secret from the OS keychain, or None if it can't be found"""
+    if keyring is None:
+        logger.warning("keyring is not installed, cannot load %r from the keychain", key)
+        return None
+    try:
+        value = keyring.get_password(SERVICE_NAME, key)
+        if value is None:
+            logger.warning("%r is missing from the OS keychain", key)
+        return value
+    except Exception as e:
+        logger.warning("Could not read %r from the OS keychain: %s", key, e)
+        return None
+
+
+def delete_secret(key: str) -> None:
+    """Remove a secret from the OS keychain if present"""
+    if keyring is None:
+        return
+    try:
+        keyring.delete_password(SERVICE_NAME, key)
+    except Exception:
+        # Not present or no keychain - nothing to clean up
+        pass
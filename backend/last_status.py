@@ -0,0 +1,43 @@
+"""Last successful status of each server, saved so the status window has something to show at once"""
+import json
+import logging
+import threading
+import time
+from pathlib import Path
+from typing import Any, Dict
+from config import AppConfig
+
+logger = logging.getLogger(__name__)
+
+# get_all_status saves several servers at once from worker threads
+_lock = threading.Lock()
+
+
+def get_store_path() -> Path:
+    """File holding each server's last successful status"""
+    return AppConfig.get_config_path().parent / 'last_status.json'
+
+
+def load_all() -> Dict[str, Dict[str, Any]]:
+    """Saved status of every server, keyed by server id, each with a saved_at Unix time"""
+    path = get_store_path()
+    if not path.exists():
+        return {}
+    try:
+        with open(path, 'r') as f:
+            return json.load(f)
+    except Exception as e:
+        logger.warning("Could not read last known status: %s", e)
+        return {}
+
+
+def save(server_id: str, status: Dict[str, Any]) -> None:
+    """Remember a server's status as the last known one"""
+    with _lock:
+        state = load_all()
+        state[server_id] = {**status, 'saved_at': time.time()}
+        try:
+            with open(get_store_path(), 'w') as f:
+                json.dump(state, f, indent=2)
+        except Exception as e:
+            logger.warning("Could not save last known status: %s", e)
@@ -0,0 +1,145 @@
+"""Loopback-only HTTP control API so scripts and other tools can drive the orchestrator"""
+import hmac
+import http.server
+import json
+import logging
+import os
+import secrets
+import threading
+from pathlib import Path
+from typing import Any, Dict, Optional
+from config import AppConfig, Preferences
+from errors import ErrorKind, error_kind
+
+logger = logging.getLogger(__name__)
+
+# API methods callable over HTTP; windows, shells and config editing stay UI-only
+ALLOWED_METHODS = (
+    'launch_portal', 'launch_service', 'cancel_launch', 'stop_portal', 'stop_service',
+    'restart_portal', 'restart_service', 'update_portal', 'apply_updates',
+    'get_status', 'get_all_status', 'get_cached_status', 'diagnose_connection', 'get_portal_url', 'check_portal_reachable',
+    'list_services', 'control_service', 'get_container_stats', 'get_docker_disk_usage', 'prune_docker', 'get_service_logs',
+    'run_maintenance', 'set_maintenance', 'ping', 'refresh_tray', 'launch_all', 'launch_local_app', 'terminate_app', 'is_app_running', 'get_app_stats', 'app_health',
+)
+
+
+def get_info_path() -> Path:
+    """File telling clients the port and token of the running server"""
+    return AppConfig.get_config_path().parent / 'local_api.json'
+
+
+class LocalAPIServer:
+    """
+    JSON-RPC style server on 127.0.0.1.
+    
+    Clients POST {"method": "get_status", "params": {"server_id": "..."}} to /rpc with an
+    "Authorization: Bearer <token>" header, taking the port and token from local_api.json.
+    Responses are {"result": ...} or {"error": {"kind": ..., "message": ...}}.
+    """
+    
+    def __init__(self):
+        self._lock = threading.Lock()
+        self._httpd: Optional[http.server.ThreadingHTTPServer] = None
+        self._port_setting: Optional[int] = None  # local_api_port the running server was started with
+    
+    @property
+    def port(self) -> Optional[int]:
+        """Port the server is listening on, or None if it isn't running"""
+        return self._httpd.server_address[1] if self._httpd else None
+    
+    def apply(self, api: Any, preferences: Preferences) -> None:
+        """Start, stop or restart the server to match the enable_local_api and local_api_port preferences"""
+        with self._lock:
+            if self._httpd and (not preferences.enable_local_api or preferences.local_api_port != self._port_setting):
+                self._stop()
+            if preferences.enable_local_api and not self._httpd:
+                self._start(api, preferences.local_api_port)
+    
+    def stop(self) -> None:
+        """Shut the server down if it's running"""
+        with self._lock:
+            self._stop()
+    
+    def _start(self, api: Any, port: int) -> None:
+        token = secrets.token_urlsafe(32)
+        handler = type('Handler', (_RequestHandler,), {'api': api, 'token': token})
+        try:
+            httpd = http.server.ThreadingHTTPServer(('127.0.0.1', port), handler)
+        except OSError as e:
+            logger.error("Could not start local API on port %s: %s", port, e)
+            return
+        httpd.daemon_threads = True
+        self._httpd = httpd
+        self._port_setting = port
+        threading.Thread(target=httpd.serve_forever, daemon=True).start()
+        
+        info_path = get_info_path()
+        try:
+            # Created owner-only, since the token grants control of the app
+            fd = os.open(info_path, os.O_WRONLY | os.O_CREAT | os.O_TRUNC, 0o600)
+            with os.fdopen(fd, 'w') as f:
+                json.dump({'port': self.port, 'token': token, 'pid': os.getpid()}, f, indent=2)
+        except Exception as e:
+            logger.warning("Could not write local API info to %s: %s", info_path, e)
+        logger.info(f"Local API listening on 127.0.0.1:{self.port}")
+    
+    def _stop(self) -> None:
+        if not self._httpd:
+            return
+        self._httpd.shutdown()
+        self._httpd.server_close()
+        self._httpd = None
+        self._port_setting = None
+        get_info_path().unlink(missing_ok=True)
+        logger.info("Local API stopped")
+
+
+class _RequestHandler(http.server.BaseHTTPRequestHandler):
+    """Maps POST /rpc requests onto API methods; api and token are set per server"""
+    api: Any = None
+    token: str = ""
+    
+    def do_POST(self):
+        if self.path != '/rpc':
+            return self._reply(404, {'error': {'kind': ErrorKind.NOT_FOUND.value, 'message': f"No such endpoint: {self.path}"}})
+        auth = self.headers.get('Authorization', '')
+        if not hmac.compare_digest(auth.encode(), f"Bearer {self.token}".encode()):
+            return self._reply(401, {'error': {'kind': ErrorKind.AUTH_FAILED.value, 'message': "Missing or invalid token"}})
+        
+        try:
+            length = int(self.headers.get('Content-Length') or 0)
+            request = json.loads(self.rfile.read(length) or b'{}')
+            method = request['method']
+            params = request.get('params') or {}
+        except (ValueError, KeyError, TypeError) as e:
+            return self._reply(400, {'error': {'kind': 'InvalidRequest', 'message': f"Malformed request: {e}"}})
+        
+        if method not in ALLOWED_METHODS:
+            return self._reply(404, {'error': {'kind': ErrorKind.NOT_FOUND.value, 'message': f"Unknown method: {method}"}})
+        
+        try:
+            function = getattr(self.api, method)
+            result = function(*params) if isinstance(params, list) else function(**params)
+        except Exception as e:
+            return self._reply(200, {'error': {'kind': error_kind(e).value, 'message': str(e)}})
+        self._reply(200, {'result': result})
+    
+    def _reply(self, status: int, body: Dict[str, Any]) -> None:
+        data = json.dumps(body, default=str).encode()
+        self.send_response(status)
+        self.send_header('Content-Type', 'application/json')
+        self.send_header('Content-Length', str(len(data)))
+        self.end_headers()
+        self.wfile.write(data)
+    
+    def log_message(self, format, *args):
+        logger.debug("Local API: %s", format % args)
+
+
+# Global local API server instance
+_local_api_server = LocalAPIServer()
+
+
+def get_local_api_server() -> LocalAPIServer:
+    """Get the global local API server instance"""
+    return _local_api_server
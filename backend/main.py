@@ -7,23 +7,54 @@ from PIL import Image
 import pystray
 from pystray import MenuItem as Item, Menu
 import threading
+import json
+import logging
+import time
 import http.server
 import socketserver
-from api import get_api
-from process_manager import get_process_manager
+from api import API
+from config import AppConfig
+from process_manager import ProcessManager
+from app_logging import setup_logging
+from local_api import get_local_api_server
+import window_state
+
+logger = logging.getLogger(__name__)
+
+# Seconds between checks of whether a turned-off health monitor has been turned back on
+MONITOR_DISABLED_RECHECK_SECS = 5
+# Seconds between passes rotating and expiring captured app logs (the first runs at startup)
+LOG_CLEANUP_INTERVAL_SECS = 3600
 
 
 class OrchestratorApp:
     """Main orchestrator application"""
     
     def __init__(self):
-        self.api = get_api()
-        self.process_manager = get_process_manager()
+        # Process state lives as long as the app, shared with the API rather than held globally
+        self.process_manager = ProcessManager()
+        self.api = API(self.process_manager)
+        # Pick back up any apps launched before the orchestrator last restarted
+        self.process_manager.restore_state()
+        preferences = AppConfig.load().preferences
+        self.api.connection_pool.set_keep_warm(preferences.keep_warm)
+        get_local_api_server().apply(self.api, preferences)
         self.windows = {}
         self.tray_icon = None
         self.running = True
         self.http_server = None
         self.http_port = 8765
+        # server_id -> {'running': bool, 'launching': bool}, shown in the tray menu
+        self.portal_state = {}
+        self.portal_state_lock = threading.Lock()
+        self.tray_servers = []  # (server_id, display name) pairs shown as tray submenus
+        self.default_server_id = None  # Target of the top-level portal item
+        self.unhealthy_containers = set()  # (server_id, container) pairs already notified about
+        self.ready_portals = set()  # server_ids whose portal answered its last health check
+        self.unresponsive_portals = set()  # server_ids whose running portal stopped answering its health check
+        self.last_alerts = {}  # alert key -> monotonic time it was last notified, for the cooldown
+        self.launch_failures_notified = set()  # server_ids whose failed launch already raised a notification
+        self.awaiting_ready = set()  # (server_id, service_id) pairs launched without waiting, notified once ready
         
         # Get project root (parent of backend directory)
         self.project_root = Path(__file__).parent.parent
@@ -36,6 +67,56 @@ class OrchestratorApp:
         
         # Register window creator callback with API so it can open windows
         self.api.set_window_creator(self.create_window)
+        # Register event emitter so the API can push events to open windows
+        self.api.set_event_emitter(self.emit_event)
+        self.process_manager.set_event_emitter(self.emit_event)
+        # Rebuild the tray's server submenus whenever settings are saved
+        self.api.set_config_listener(self.reload_tray_menu)
+    
+    def emit_event(self, event, payload):
+        """Dispatch a DOM CustomEvent with the given payload to every open window"""
+        self.notify_for_event(event, payload)
+        script = f"window.dispatchEvent(new CustomEvent({json.dumps(event)}, {{ detail: {json.dumps(payload)} }}))"
+        for window in list(self.windows.values()):
+            if window:
+                try:
+                    window.evaluate_js(script)
+                except Exception:
+                    # Window may be closing or not loaded yet
+                    pass
+    
+    def notify(self, title, message):
+        """Show a desktop notification if the user has them enabled"""
+        if not self.tray_icon:
+            return
+        try:
+            if not AppConfig.load().preferences.enable_notifications:
+                return
+            self.tray_icon.notify(message, title)
+        except Exception as e:
+            # Not every tray backend supports notifications
+            logger.warning("Failed to show notification %r: %s", title, e)
+    
+    def notify_for_event(self, event, payload):
+        """Turn backend events worth the user's attention into notifications"""
+        if event == 'launch-progress':
+            if payload.get('phase') == 'ready':
+                self.notify('Portal ready', payload.get('url', ''))
+            elif payload.get('phase') == 'started':
+                self.awaiting_ready.add((payload.get('server_id'), payload.get('service_id')))
+            elif payload.get('phase') == 'failed':
+                self.notify('Portal failed to start', payload.get('error', 'Unknown error'))
+                self.launch_failures_notified.add(payload.get('server_id'))
+        elif event == 'app-crashed':
+            self.notify(f"{payload['name']} crashed", f"Exited with code {payload['exit_code']}")
+        elif event == 'app-restart-failed':
+            self.notify(f"Could not restart {payload['name']}", payload['error'])
+        elif event == 'tray-action-failed':
+            # A launch that got as far as launch_service has already been notified about
+            already_notified = payload['server_id'] in self.launch_failures_notified
+            self.launch_failures_notified.discard(payload['server_id'])
+            if not (payload['action'] == 'launch' and already_notified):
+                self.notify(f"Could not {payload['action']} portal", payload['error'])
     
     def start_local_server(self, directory: Path, port: int = 8765):
         """Start a local HTTP server to serve static files"""
@@ -55,10 +136,10 @@ class OrchestratorApp:
             self.http_server = httpd
             server_thread = threading.Thread(target=httpd.serve_forever, daemon=True)
             server_thread.start()
-            print(f"Started local HTTP server on port {port}")
+            logger.info(f"Started local HTTP server on port {port}")
             return True
         except OSError as e:
-            print(f"Could not start HTTP server on port {port}: {e}")
+            logger.error(f"Could not start HTTP server on port {port}: {e}")
             return False
     
     def get_icon_path(self):
@@ -75,14 +156,9 @@ class OrchestratorApp:
             icon_path = self.get_icon_path()
             image = Image.open(icon_path)
             
-            menu = Menu(
-                Item('Open Dashboard', self.on_open_dashboard),
-                Menu.SEPARATOR,
-                Item('Settings', self.on_settings),
-                Item('Status', self.on_status),
-                Menu.SEPARATOR,
-                Item('Quit', self.on_quit)
-            )
+            # Generated dynamically so update_menu() picks up config changes
+            self.reload_tray_menu()
+            menu = Menu(self.build_tray_items)
             
             self.tray_icon = pystray.Icon(
                 "orchestrator",
@@ -93,9 +169,292 @@ class OrchestratorApp:
             
             # Run tray icon in separate thread
             threading.Thread(target=self.tray_icon.run, daemon=True).start()
+            # Keep the portal item in sync with the real server state
+            threading.Thread(target=self.poll_tray_status, daemon=True).start()
             
         except Exception as e:
-            print(f"Failed to create tray icon: {e}")
+            logger.exception("Failed to create tray icon: %s", e)
+    
+    def build_tray_items(self):
+        """Tray menu items, with one submenu per configured server"""
+        items = [
+            Item('Open Dashboard', self.on_open_dashboard),
+            Item('Launch All', self.on_launch_all),
+            Menu.SEPARATOR,
+        ]
+        
+        if self.default_server_id:
+            # Quick access to the default server's portal without opening its submenu
+            default_id = self.default_server_id
+            default_name = dict(self.tray_servers).get(default_id, default_id)
+            items.append(Item(
+                lambda item: f"{self.portal_item_title(default_id)} ({default_name})",
+                self.tray_action(f'toggle_portal:{default_id}'),
+                enabled=lambda item: self.portal_item_enabled(default_id)
+            ))
+            items.append(Menu.SEPARATOR)
+        
+        if self.tray_servers:
+            for server_id, server_name in self.tray_servers:
+                items.append(Item(server_name, Menu(*self.build_server_items(server_id))))
+        else:
+            items.append(Item('No servers configured', None, enabled=False))
+        
+        items.extend([
+            Menu.SEPARATOR,
+            Item('Settings', self.on_settings),
+            Item('Status', self.on_status),
+            Menu.SEPARATOR,
+            Item('Quit', self.on_quit)
+        ])
+        return items
+    
+    def build_server_items(self, server_id):
+        """Launch/stop/status items for one server's submenu"""
+        return [
+            Item(
+                lambda item: self.portal_item_title(server_id),
+                self.tray_action(f'toggle_portal:{server_id}'),
+                enabled=lambda item: self.portal_item_enabled(server_id)
+            ),
+            Item(
+                lambda item: 'Status: ' + ('Running' if self.get_portal_state(server_id)['running'] else 'Stopped'),
+                self.tray_action(f'refresh_status:{server_id}')
+            ),
+            Item('Copy Portal URL', self.tray_action(f'copy_url:{server_id}')),
+        ]
+    
+    def tray_action(self, action_id):
+        """Menu callback dispatching an '<action>:<server_id>' id to the right handler"""
+        return lambda icon, item: self.on_tray_action(action_id)
+    
+    def on_tray_action(self, action_id):
+        """Parse a tray action id and run it against the encoded server"""
+        action, _, server_id = action_id.partition(':')
+        if action == 'toggle_portal':
+            self.on_toggle_portal(server_id)
+        elif action == 'refresh_status':
+            threading.Thread(target=lambda: self.refresh_tray_status([server_id]), daemon=True).start()
+        elif action == 'copy_url':
+            self.on_copy_portal_url(server_id)
+        else:
+            logger.warning("Unknown tray action: %s", action_id)
+    
+    def reload_tray_menu(self):
+        """Re-read the server list from config and rebuild the tray menu"""
+        previous_ids = {server_id for server_id, _ in self.tray_servers}
+        try:
+            config = AppConfig.load()
+            self.tray_servers = [(s.id, s.name or s.host) for s in config.servers]
+            default_server = config.get_default_server()
+            self.default_server_id = default_server.id if default_server else None
+        except Exception as e:
+            logger.error("Failed to load servers for tray menu: %s", e)
+            self.tray_servers = []
+            self.default_server_id = None
+        
+        # Forget removed servers and poll added ones so their items show the right state
+        current_ids = {server_id for server_id, _ in self.tray_servers}
+        with self.portal_state_lock:
+            for server_id in set(self.portal_state) - current_ids:
+                del self.portal_state[server_id]
+        added_ids = list(current_ids - previous_ids)
+        if added_ids and self.tray_icon:
+            threading.Thread(target=lambda: self.refresh_tray_status(added_ids), daemon=True).start()
+        
+        if self.tray_icon:
+            self.tray_icon.update_menu()
+    
+    def get_portal_state(self, server_id):
+        """Tray-visible state of a server's portal"""
+        with self.portal_state_lock:
+            return dict(self.portal_state.get(server_id, {'running': False, 'launching': False}))
+    
+    def set_portal_state(self, server_id, **changes):
+        """Update a server's tray-visible state and redraw the menu"""
+        with self.portal_state_lock:
+            state = self.portal_state.setdefault(server_id, {'running': False, 'launching': False})
+            state.update(changes)
+        if self.tray_icon:
+            self.tray_icon.update_menu()
+    
+    def portal_item_title(self, server_id):
+        """Label for a portal menu item, reflecting its live state"""
+        state = self.get_portal_state(server_id)
+        if state['launching']:
+            return 'Launching Portal...'
+        if state['running'] and state.get('unhealthy'):
+            return '⚠ Stop Portal (unhealthy)'
+        return '● Stop Portal' if state['running'] else 'Launch Portal'
+    
+    def portal_item_enabled(self, server_id):
+        """Disable the portal item while a launch is in flight to prevent double-clicks"""
+        return not self.get_portal_state(server_id)['launching']
+    
+    def on_toggle_portal(self, server_id):
+        """Launch the portal if it's down, stop it if it's up"""
+        if self.get_portal_state(server_id)['launching']:
+            return
+        
+        running = self.get_portal_state(server_id)['running']
+        self.set_portal_state(server_id, launching=True)
+        
+        def toggle():
+            try:
+                if running:
+                    self.api.stop_portal(server_id)
+                    self.set_portal_state(server_id, running=False)
+                else:
+                    self.launch_failures_notified.discard(server_id)
+                    url = self.api.launch_portal(server_id)
+                    self.set_portal_state(server_id, running=True)
+                    opened = self.api.open_url(url)
+                    logger.info("Opened %s for server %s in %s browser", url, server_id, opened['browser'])
+            except Exception as e:
+                action = 'stop' if running else 'launch'
+                logger.error("Tray portal action (%s) failed for server %s: %s", action, server_id, e)
+                # Tray actions have no window to report to, so tell the user and any open status window
+                self.emit_event('tray-action-failed', {'server_id': server_id, 'action': action, 'error': str(e)})
+            finally:
+                self.set_portal_state(server_id, launching=False)
+        
+        threading.Thread(target=toggle, daemon=True).start()
+    
+    def on_launch_all(self, icon=None, item=None):
+        """Launch the auto_launch apps and the default portal in the background"""
+        threading.Thread(target=self.launch_all, daemon=True).start()
+    
+    def launch_all(self):
+        """Run API.launch_all against the default server, keeping the tray in sync and reporting failures"""
+        server_id = self.default_server_id
+        if server_id:
+            if self.get_portal_state(server_id)['launching']:
+                return
+            self.set_portal_state(server_id, launching=True)
+        try:
+            results = self.api.launch_all(server_id)
+        except Exception as e:
+            logger.error("Launch all failed: %s", e)
+            self.notify('Launch all failed', str(e))
+            return
+        finally:
+            if server_id:
+                self.set_portal_state(server_id, launching=False)
+        
+        portal = f"portal:{server_id}"
+        if results['results'].get(portal, {}).get('success'):
+            self.set_portal_state(server_id, running=True)
+        failed = [item for item in results['order'] if not results['results'][item]['success']]
+        if failed:
+            logger.warning("Launch all: %s failed", ', '.join(failed))
+            self.notify('Some launches failed', ', '.join(failed))
+        else:
+            logger.info("Launch all finished: %s", ', '.join(results['order']))
+    
+    def on_copy_portal_url(self, server_id):
+        """Copy a server's portal URL to the clipboard"""
+        try:
+            url = self.api.copy_portal_url(server_id)
+            logger.info("Copied portal URL for server %s: %s", server_id, url)
+            self.notify('Portal URL copied', url)
+        except Exception as e:
+            logger.error("Could not copy portal URL for server %s: %s", server_id, e)
+            self.notify('Could not copy portal URL', str(e))
+    
+    def refresh_tray_status(self, server_ids=None):
+        """Poll servers once (all of them by default) and update the tray menu"""
+        if server_ids is None:
+            server_ids = [server_id for server_id, _ in self.tray_servers]
+        for server_id in server_ids:
+            if self.get_portal_state(server_id)['launching']:
+                continue  # Let the in-flight action decide the final state
+            try:
+                status = self.api.get_status(server_id)
+                first_service = status['services'][0] if status['services'] else None
+                running = bool(first_service and first_service['running'])
+                self.check_container_health(server_id, status)
+                self.check_portal_responding(server_id, status)
+                self.check_started_ready(server_id, status)
+            except Exception as e:
+                logger.warning("Tray status poll failed for server %s: %s", server_id, e)
+                running = False
+            unhealthy = server_id in self.unresponsive_portals or any(key[0] == server_id for key in self.unhealthy_containers)
+            self.set_portal_state(server_id, running=running, unhealthy=unhealthy)
+    
+    def check_container_health(self, server_id, status):
+        """Alert when a container transitions to unhealthy"""
+        unhealthy = {
+            (server_id, c['name'])
+            for service in status['services']
+            for c in service['containers']
+            if c.get('health') == 'unhealthy'
+        }
+        previous = {key for key in self.unhealthy_containers if key[0] == server_id}
+        for _, container in unhealthy - previous:
+            self.alert(('container', server_id, container), 'Container unhealthy',
+                       f"{container} on {status.get('server_name', server_id)}")
+        self.unhealthy_containers = (self.unhealthy_containers - previous) | unhealthy
+    
+    def check_portal_responding(self, server_id, status):
+        """Alert when a portal that was ready stops answering while its containers keep running"""
+        first_service = status['services'][0] if status['services'] else None
+        if first_service and first_service['ready']:
+            self.unresponsive_portals.discard(server_id)
+            self.ready_portals.add(server_id)
+        elif first_service and first_service['running']:
+            if server_id in self.ready_portals:
+                self.ready_portals.discard(server_id)
+                self.unresponsive_portals.add(server_id)
+                self.alert(('portal', server_id), 'Portal not responding',
+                           f"{status.get('server_name', server_id)}: health check returned {first_service['status_code'] or 'no response'}")
+        else:
+            # Stopped (or the server is unreachable), which isn't the portal hanging
+            self.ready_portals.discard(server_id)
+            self.unresponsive_portals.discard(server_id)
+    
+    def check_started_ready(self, server_id, status):
+        """Notify once a service launched without waiting answers its health check"""
+        for service in status['services']:
+            key = (server_id, service['id'])
+            if key in self.awaiting_ready and service['ready']:
+                self.awaiting_ready.discard(key)
+                self.notify('Portal ready', service['url'])
+            elif key in self.awaiting_ready and not service['running']:
+                # Stopped, or it exited before becoming ready; nothing to wait for any more
+                self.awaiting_ready.discard(key)
+    
+    def alert(self, key, title, message):
+        """Notify about a problem unless the same one was notified within the cooldown"""
+        cooldown = AppConfig.load().preferences.health_alert_cooldown_secs
+        now = time.monotonic()
+        last = self.last_alerts.get(key)
+        if last is not None and now - last < cooldown:
+            logger.info("Suppressed repeat alert (cooldown): %s - %s", title, message)
+            return
+        self.last_alerts[key] = now
+        logger.warning("%s: %s", title, message)
+        self.notify(title, message)
+    
+    def poll_tray_status(self):
+        """
+        Background health monitor keeping tray state in sync with the servers and alerting
+        on problems, whether or not any window is open.
+        """
+        while self.running:
+            interval = AppConfig.load().preferences.health_monitor_interval_secs
+            if interval > 0:
+                self.refresh_tray_status()
+            time.sleep(interval if interval > 0 else MONITOR_DISABLED_RECHECK_SECS)
+    
+    def clean_app_logs(self):
+        """Background pass keeping captured app logs within the size and age preferences"""
+        while self.running:
+            preferences = AppConfig.load().preferences
+            try:
+                self.process_manager.rotate_logs(preferences.max_log_size_mb, preferences.max_log_age_days)
+            except Exception as e:
+                logger.warning("Could not clean up app logs: %s", e)
+            time.sleep(LOG_CLEANUP_INTERVAL_SECS)
     
     def on_open_dashboard(self, icon=None, item=None):
         """Open/show the dashboard window"""
@@ -136,6 +495,13 @@ class OrchestratorApp:
         """Quit application"""
         self.running = False
         self.process_manager.cleanup_all()
+        self.api.close_all_shells()
+        if AppConfig.load().preferences.stop_portal_on_quit:
+            logger.info("Stopping portals started this session...")
+            self.api.stop_started_services()
+        self.api.connection_pool.set_keep_warm(False)
+        get_local_api_server().stop()
+        self.api.connection_pool.disconnect_all()
         
         if self.tray_icon:
             self.tray_icon.stop()
@@ -164,11 +530,11 @@ class OrchestratorApp:
                 # Use restore() to bring back minimized windows, then show() to focus
                 existing_window.restore()
                 existing_window.show()
-                print(f"Restored and focused existing {window_type} window")
+                logger.info(f"Restored and focused existing {window_type} window")
                 return existing_window
             except:
                 # Window was closed or is invalid, remove from dict
-                print(f"Existing {window_type} window was closed, creating new one")
+                logger.info(f"Existing {window_type} window was closed, creating new one")
                 self.windows[window_type] = None
         
         # Determine window properties based on type
@@ -207,7 +573,7 @@ class OrchestratorApp:
             width, height = 400, 300
         
         if not html_file.exists():
-            print(f"HTML file not found: {html_file}")
+            logger.error(f"HTML file not found: {html_file}")
             return None
         
         # If using HTTP server (for built versions), convert file path to URL
@@ -216,7 +582,7 @@ class OrchestratorApp:
             try:
                 relative_path = html_file.relative_to(self.project_root / 'dist')
                 url = f"http://localhost:{self.http_port}/{relative_path}"
-                print(f"Loading {window_type} from HTTP server: {url}")
+                logger.info(f"Loading {window_type} from HTTP server: {url}")
                 html_source = url
             except ValueError:
                 # Fallback to file path if not in dist
@@ -229,24 +595,58 @@ class OrchestratorApp:
         window = webview.create_window(
             title,
             html_source,
-            width=width,
-            height=height,
             resizable=True,
             hidden=False,
-            js_api=self.api  # Expose API to this window as window.pywebview.api
+            js_api=self.api,  # Expose API to this window as window.pywebview.api
+            **self.restored_geometry(window_type, width, height)
         )
         
         # Set up event handler to clear window from dict when closed
         def on_closing():
-            print(f"{window_type} window closing, removing from registry")
+            logger.info(f"{window_type} window closing, removing from registry")
+            self.remember_geometry(window_type, window)
             self.windows[window_type] = None
+            if window_type == 'status':
+                # Log streams are only consumed by the status window
+                self.api.stop_all_log_streams()
         
         window.events.closing += on_closing
         
         self.windows[window_type] = window
-        print(f"Created {window_type} window")
+        logger.info(f"Created {window_type} window")
         return window
     
+    def restored_geometry(self, window_type, width, height):
+        """create_window size/position kwargs: the window's last geometry (kept on screen), else the default size"""
+        saved = window_state.load(window_type)
+        if not saved:
+            return {'width': width, 'height': height}
+        geometry = window_state.clamp_to_screens(saved, self.screen_areas())
+        return {'x': geometry.x, 'y': geometry.y, 'width': geometry.width, 'height': geometry.height}
+    
+    @staticmethod
+    def screen_areas():
+        """(x, y, width, height) of each connected screen, primary first"""
+        try:
+            screens = webview.screens
+        except Exception as e:
+            logger.warning("Could not list screens: %s", e)
+            return []
+        # pywebview before 5.0 doesn't report screen offsets, so they can only be trusted for a single screen
+        if len(screens) > 1 and not all(hasattr(s, 'x') for s in screens):
+            return []
+        return [(getattr(s, 'x', 0), getattr(s, 'y', 0), s.width, s.height) for s in screens]
+    
+    @staticmethod
+    def remember_geometry(window_type, window):
+        """Save a closing window's position and size for next time"""
+        try:
+            window_state.save(window_type, window_state.WindowGeometry(
+                x=window.x, y=window.y, width=window.width, height=window.height
+            ))
+        except Exception as e:
+            logger.warning("Could not save %s window geometry: %s", window_type, e)
+    
     def run(self):
         """Run the application"""
         import platform
@@ -258,10 +658,17 @@ class OrchestratorApp:
             try:
                 self.create_tray_icon()
             except Exception as e:
-                print(f"Warning: Could not create tray icon: {e}")
-                print("Application will continue without tray icon.")
+                logger.warning(f"Could not create tray icon: {e}")
+                logger.info("Application will continue without tray icon.")
         else:
-            print("Note: Tray icon disabled on macOS (conflicts with pywebview event loop)")
+            logger.info("Tray icon disabled on macOS (conflicts with pywebview event loop)")
+        
+        threading.Thread(target=self.clean_app_logs, daemon=True).start()
+        
+        # Launched at login: bring everything up without waiting for the user
+        if AppConfig.load().preferences.startup_launch:
+            self.reload_tray_menu()
+            threading.Thread(target=self.launch_all, daemon=True).start()
         
         # Find the home HTML file - check multiple options in order:
         # 1. Built version (dist/home.html) - preferred
@@ -274,11 +681,11 @@ class OrchestratorApp:
         html_path = None
         
         if using_built:
-            print("Found built version in dist/home.html")
+            logger.info("Found built version in dist/home.html")
             # Start local HTTP server for built assets
             if self.start_local_server(self.project_root / 'dist', self.http_port):
                 html_path = f"http://localhost:{self.http_port}/home.html"
-                print(f"Using HTTP server: {html_path}")
+                logger.info(f"Using HTTP server: {html_path}")
             else:
                 html_path = str(home_html.resolve())
         else:
@@ -289,8 +696,8 @@ class OrchestratorApp:
                 response.close()
                 html_path = 'http://localhost:1420/home.html'
                 using_vite_dev = True
-                print("Found Vite dev server running on port 1420")
-                print(f"Using Vite dev server: {html_path}")
+                logger.info("Found Vite dev server running on port 1420")
+                logger.info(f"Using Vite dev server: {html_path}")
             except:
                 # Vite dev server not running
                 pass
@@ -316,7 +723,7 @@ Then restart this application.
 Searched locations:
   - {self.project_root / 'dist' / 'home.html'}
   - Vite dev server: http://localhost:1420/home.html"""
-            print(error_msg)
+            logger.error(error_msg)
             # On Mac, show a simple error window if possible
             try:
                 error_window = webview.create_window(
@@ -349,11 +756,11 @@ Searched locations:
                 webview.start(debug=False)
             except:
                 # If even error window fails, print and exit
-                print("Could not create error window. Exiting.")
+                logger.error("Could not create error window. Exiting.")
                 sys.exit(1)
             return
         
-        print(f"Loading: {html_path}")
+        logger.info(f"Loading: {html_path}")
         
         # On macOS, PyWebView works better when window is created before start()
         # but we need to ensure it's properly shown
@@ -362,20 +769,20 @@ Searched locations:
             main_window = webview.create_window(
                 'PLATONIC - Pantheon Lab Tools Orchestration for Integration and Control',
                 html_path,  # Can be http:// URL or file:// path
-                width=1280,
-                height=740,
                 resizable=True,
                 hidden=False,  # Show the home dashboard window
                 js_api=self.api,  # Expose API to JavaScript as window.pywebview.api
                 text_select=False,  # Prevent text selection issues
-                shadow=True  # Enable window shadow on Mac
+                shadow=True,  # Enable window shadow on Mac
+                **self.restored_geometry('home', 1280, 740)
             )
             self.windows['home'] = main_window
+            main_window.events.closing += lambda: self.remember_geometry('home', main_window)
             
             # Set up error handler to prevent window from closing on JS errors
             def on_loaded():
                 """Called when window finishes loading"""
-                print("Window loaded successfully")
+                logger.info("Window loaded successfully")
                 try:
                     # Inject error handler to catch JS errors and prevent window closure
                     main_window.evaluate_js("""
@@ -390,21 +797,20 @@ Searched locations:
                         });
                     """)
                 except Exception as e:
-                    print(f"Could not inject error handler: {e}")
+                    logger.error(f"Could not inject error handler: {e}")
             
             main_window.events.loaded += on_loaded
             
-            print(f"Window created successfully. Platform: {platform.system()}")
-            print(f"Loading: {html_path}")
-            print("Starting webview...")
+            logger.info(f"Window created successfully. Platform: {platform.system()}")
+            logger.info(f"Loading: {html_path}")
+            logger.info("Starting webview...")
             # Start webview (this blocks)
             # Enable debug mode on Mac to see console errors
             webview.start(debug=platform.system() == 'Darwin')
         except Exception as e:
             error_msg = f"Failed to start application: {e}"
-            print(error_msg)
+            logger.exception(error_msg)
             import traceback
-            traceback.print_exc()
             
             # Try to show error in a window
             try:
@@ -417,15 +823,14 @@ Searched locations:
                 )
                 webview.start(debug=False)
             except:
-                print("Could not create error window. Exiting.")
+                logger.error("Could not create error window. Exiting.")
                 sys.exit(1)
 
 
 def main():
     """Main entry point"""
     # Set up logging
-    import logging
-    logging.basicConfig(level=logging.INFO)
+    setup_logging(AppConfig.load().preferences.log_level)
     
     # Create and run app
     app = OrchestratorApp()
@@ -0,0 +1,55 @@
+"""Per-server maintenance mode, which blocks launches and restarts while someone works on a server"""
+import json
+import logging
+import os
+import threading
+import time
+from pathlib import Path
+from typing import Any, Dict, Optional
+from config import AppConfig
+
+logger = logging.getLogger(__name__)
+
+# The status window and local API scripts may toggle servers at the same time
+_lock = threading.Lock()
+
+
+def get_store_path() -> Path:
+    """File holding the servers currently in maintenance mode"""
+    return AppConfig.get_config_path().parent / 'maintenance.json'
+
+
+def load_all() -> Dict[str, Dict[str, Any]]:
+    """Servers in maintenance mode, keyed by server id, each with a since Unix time and a reason"""
+    path = get_store_path()
+    if not path.exists():
+        return {}
+    try:
+        with open(path, 'r') as f:
+            return json.load(f)
+    except Exception as e:
+        logger.warning("Could not read maintenance mode state: %s", e)
+        return {}
+
+
+def get(server_id: str) -> Optional[Dict[str, Any]]:
+    """A server's maintenance mode entry, or None if it isn't in maintenance mode"""
+    return load_all().get(server_id)
+
+
+def set_enabled(server_id: str, enabled: bool, reason: str = "") -> None:
+    """Put a server into maintenance mode, or take it out; survives restarts of the app"""
+    with _lock:
+        state = load_all()
+        if enabled:
+            state[server_id] = {'since': time.time(), 'reason': reason}
+        else:
+            state.pop(server_id, None)
+        # Written to a temp file and renamed over the store, so a crash mid-write can't lift maintenance mode
+        path = get_store_path()
+        tmp_path = path.with_suffix('.json.tmp')
+        with open(tmp_path, 'w') as f:
+            json.dump(state, f, indent=2)
+            f.flush()
+            os.fsync(f.fileno())
+        os.replace(tmp_path, path)
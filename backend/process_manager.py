@@ -1,36 +1,335 @@
 """Process manager for launching and managing local applications"""
 import subprocess
+import json
+import logging
 import os
 import sys
-from typing import Dict, Optional
-from config import LocalAppConfig
+import time
+import shlex
+import shutil
+import socket
+import threading
+import urllib.error
+import urllib.request
+import psutil
+from collections import deque
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Any, Callable, Dict, List, NamedTuple, Optional, Tuple, Union
+from config import AppConfig, HealthCheck, LocalAppConfig, RestartPolicy
+from errors import InProgress
+
+logger = logging.getLogger(__name__)
+
+# Seconds between crash-watcher checks
+WATCH_INTERVAL = 2
+# Maximum delay between restart attempts, in seconds
+MAX_RESTART_BACKOFF = 60
+# Seconds an app must stay up before its restart count is reset
+STABLE_UPTIME = 60
+# Seconds to wait after asking an app to exit before killing it
+DEFAULT_GRACE_PERIOD = 10
+# Seconds after spawning before checking the app didn't exit straight away
+POST_SPAWN_CHECK_SECS = 0.5
+# Output lines included when an app fails to start
+STARTUP_FAILURE_LOG_LINES = 20
+# Seconds over which CPU usage is measured for get_stats
+CPU_SAMPLE_SECS = 0.2
+# ProcessKey kind of a local app; the only kind launched so far
+APP_PROCESS = 'app'
+# Kinds ProcessKey.parse recognises in the state file
+PROCESS_KINDS = (APP_PROCESS,)
+
+
+@dataclass
+class AppStats:
+    """CPU and memory usage of a local app, including its child processes"""
+    running: bool
+    pid: Optional[int] = None
+    cpu_percent: float = 0.0  # Summed across processes, so can exceed 100 on multi-core machines
+    memory_rss_bytes: int = 0
+    process_count: int = 0
+
+
+class ProcessKey(NamedTuple):
+    """
+    What a tracked process belongs to. Server and app ids are both user-supplied, so the kind
+    keeps an app from being mistaken for a server (or anything else tracked later) with the same id.
+    """
+    kind: str
+    id: str
+    
+    @classmethod
+    def app(cls, app_id: str) -> 'ProcessKey':
+        """Key of a local app's process"""
+        return cls(APP_PROCESS, app_id)
+    
+    def __str__(self) -> str:
+        return f"{self.kind}:{self.id}"
+    
+    @classmethod
+    def parse(cls, value: str) -> 'ProcessKey':
+        """Inverse of str(); bare ids from state files written before keys had a kind are apps"""
+        kind, sep, item_id = value.partition(':')
+        return cls(kind, item_id) if sep and kind in PROCESS_KINDS else cls.app(value)
+
+
+class AdoptedProcess:
+    """
+    A process launched by a previous orchestrator run, re-adopted from the state file.
+    
+    Implements the subset of subprocess.Popen used by ProcessManager.
+    """
+    
+    def __init__(self, process: psutil.Process):
+        self._process = process
+        self.pid = process.pid
+    
+    def poll(self) -> Optional[int]:
+        """Return None while running (exit codes of non-children are unknown, so 0 once gone)"""
+        try:
+            if self._process.is_running() and self._process.status() != psutil.STATUS_ZOMBIE:
+                return None
+        except psutil.NoSuchProcess:
+            pass
+        return 0
+    
+    def terminate(self) -> None:
+        try:
+            self._process.terminate()
+        except psutil.NoSuchProcess:
+            pass
+    
+    def kill(self) -> None:
+        try:
+            self._process.kill()
+        except psutil.NoSuchProcess:
+            pass
+    
+    def wait(self, timeout: Optional[float] = None) -> Optional[int]:
+        try:
+            return self._process.wait(timeout=timeout)
+        except psutil.TimeoutExpired:
+            raise subprocess.TimeoutExpired(str(self.pid), timeout)
+        except psutil.NoSuchProcess:
+            return 0
 
 
 class ProcessManager:
-    """Manages local application processes"""
+    """
+    Manages local application processes.
+    
+    Each instance tracks its own processes: the app creates one and hands it to the API, and
+    tests can create independent ones. Public methods take app ids; state is keyed by ProcessKey.
+    """
     
     def __init__(self):
-        self.processes: Dict[str, subprocess.Popen] = {}
+        self.processes: Dict[ProcessKey, Union[subprocess.Popen, AdoptedProcess]] = {}
+        self._lock = threading.RLock()
+        self._event_emitter: Optional[Callable[[str, Dict[str, Any]], None]] = None
+        
+        # Auto-restart state for apps whose restart policy isn't 'never'
+        self._monitored: Dict[ProcessKey, LocalAppConfig] = {}
+        self._started_at: Dict[ProcessKey, float] = {}
+        self._restart_counts: Dict[ProcessKey, int] = {}
+        self._restart_at: Dict[ProcessKey, float] = {}  # Time of the pending relaunch
+        self._watcher: Optional[threading.Thread] = None
+        
+        # Lifecycle event state
+        self._app_names: Dict[ProcessKey, str] = {}  # For event payloads
+        self._terminating: set = set()  # Keys being stopped on purpose (reported as app-terminated)
+        self._detached: set = set()  # Keys launched detached, left running by cleanup_all
+    
+    def set_event_emitter(self, emitter_func: Callable[[str, Dict[str, Any]], None]) -> None:
+        """Set a callback function to emit events to the frontend (called from OrchestratorApp)"""
+        self._event_emitter = emitter_func
+    
+    def _emit(self, event: str, payload: Dict[str, Any]) -> None:
+        """Emit an event to the frontend, ignoring failures (no window may be open)"""
+        if not self._event_emitter:
+            return
+        try:
+            self._event_emitter(event, payload)
+        except Exception as e:
+            logger.error("Failed to emit event %r: %s", event, e)
+    
+    @staticmethod
+    def get_state_path() -> Path:
+        """File recording launched PIDs so they can be re-adopted after a restart"""
+        return AppConfig.get_config_path().parent / 'processes.json'
+    
+    def _save_state(self) -> None:
+        """Persist PID and start time of every tracked process"""
+        state = {}
+        for key, process in self.processes.items():
+            try:
+                ps_process = psutil.Process(process.pid)
+                state[str(key)] = {
+                    'pid': process.pid,
+                    'create_time': ps_process.create_time(),
+                    'name': ps_process.name(),
+                    'detached': key in self._detached
+                }
+            except psutil.NoSuchProcess:
+                continue
+        
+        try:
+            with open(self.get_state_path(), 'w') as f:
+                json.dump(state, f, indent=2)
+        except Exception as e:
+            logger.warning("Could not save process state: %s", e)
+    
+    def restore_state(self) -> None:
+        """Re-adopt processes from a previous run that are still alive"""
+        state_path = self.get_state_path()
+        if not state_path.exists():
+            return
+        
+        try:
+            with open(state_path, 'r') as f:
+                state = json.load(f)
+        except Exception as e:
+            logger.warning("Could not read process state: %s", e)
+            return
+        
+        for key_text, entry in state.items():
+            key = ProcessKey.parse(key_text)
+            try:
+                ps_process = psutil.Process(entry['pid'])
+                # A matching start time and name guards against the PID having been reused
+                if (abs(ps_process.create_time() - entry['create_time']) < 1
+                        and ps_process.name() == entry['name']):
+                    self.processes[key] = AdoptedProcess(ps_process)
+                    if entry.get('detached'):
+                        self._detached.add(key)
+                    logger.info(f"Re-adopted {key} (PID: {entry['pid']})")
+            except (psutil.NoSuchProcess, psutil.AccessDenied, KeyError):
+                continue
+        
+        self._save_state()
+        if self.processes:
+            self._ensure_watcher()
+    
+    def launch_app(self, config: LocalAppConfig, verify: bool = True) -> None:
+        """
+        Launch a local application, monitoring it for crashes if it has a restart policy.
+        
+        With verify, raises if the app exits with an error within POST_SPAWN_CHECK_SECS, or
+        if its health check has a startup_timeout_secs and doesn't pass in time.
+        """
+        key = ProcessKey.app(config.id)
+        with self._lock:
+            self._launch(config)
+            process = self.processes[key]
+            
+            # A manual launch starts the restart budget afresh
+            self._restart_counts.pop(key, None)
+            self._restart_at.pop(key, None)
+            if config.restart_policy != RestartPolicy.NEVER:
+                self._monitored[key] = config
+                self._ensure_watcher()
+            else:
+                self._monitored.pop(key, None)
+        
+        # Checked without the lock so the crash watcher isn't held up
+        if verify:
+            try:
+                self._verify_started(config, process)
+            except Exception:
+                # The caller is told the launch failed, so the watcher mustn't keep relaunching it
+                with self._lock:
+                    self._monitored.pop(key, None)
+                    self._restart_at.pop(key, None)
+                    self._restart_counts.pop(key, None)
+                raise
+    
+    def _verify_started(self, config: LocalAppConfig, process: subprocess.Popen) -> None:
+        """Raise if a freshly spawned app failed straight away or never became healthy"""
+        time.sleep(POST_SPAWN_CHECK_SECS)
+        exit_code = process.poll()
+        if exit_code is not None:
+            if exit_code != 0:
+                raise Exception(self._startup_failure(config, f"exited immediately with code {exit_code}"))
+            # Launchers that hand off to another process legitimately exit 0 right away
+            logger.warning("App %s (%s) exited with code 0 right after launch", config.id, config.name)
+            return
+        
+        health_check = config.health_check
+        if health_check and health_check.startup_timeout_secs:
+            self._wait_healthy(config, process, health_check.startup_timeout_secs)
+    
+    def wait_until_healthy(self, config: LocalAppConfig, timeout: float) -> None:
+        """Block until a running app's health check passes (immediately if it has none)"""
+        with self._lock:
+            process = self.processes.get(ProcessKey.app(config.id))
+        if process is None:
+            raise Exception(f"App '{config.name}' is not running")
+        self._wait_healthy(config, process, timeout)
+    
+    def _wait_healthy(self, config: LocalAppConfig, process: subprocess.Popen, timeout: float) -> None:
+        """Poll an app's health check, raising if it exits first or doesn't pass within timeout"""
+        health_check = config.health_check
+        if not health_check:
+            return
+        
+        deadline = time.monotonic() + timeout
+        while True:
+            healthy, detail = self.check_health(health_check)
+            if healthy:
+                logger.info(f"App '{config.name}' is healthy: {detail}")
+                return
+            exit_code = process.poll()
+            if exit_code is not None:
+                raise Exception(self._startup_failure(config, f"exited with code {exit_code} before becoming healthy"))
+            if time.monotonic() > deadline:
+                raise Exception(f"App '{config.name}' started but its health check didn't pass "
+                                f"within {timeout}s: {detail}")
+            time.sleep(POST_SPAWN_CHECK_SECS)
+    
+    def _startup_failure(self, config: LocalAppConfig, reason: str) -> str:
+        """Error message for an app that failed to start, with its last lines of output"""
+        output = self.get_logs(config.id, STARTUP_FAILURE_LOG_LINES).strip()
+        message = f"App '{config.name}' {reason}"
+        return f"{message}. Last output:\n{output}" if output else message
     
-    def launch_app(self, config: LocalAppConfig) -> None:
-        """Launch a local application"""
+    def _launch(self, config: LocalAppConfig) -> None:
+        """Spawn a local application process"""
+        key = ProcessKey.app(config.id)
         # Check if already running
-        if config.id in self.processes:
-            process = self.processes[config.id]
+        if key in self.processes:
+            process = self.processes[key]
             if process.poll() is None:  # Still running
-                print(f"App '{config.name}' is already running (PID: {process.pid})")
+                logger.info(f"App '{config.name}' is already running (PID: {process.pid})")
                 raise Exception(f"App '{config.name}' is already running")
             else:
                 # Process has exited, remove it
-                print(f"Previous process for '{config.name}' has exited, cleaning up...")
-                del self.processes[config.id]
+                logger.info(f"Previous process for '{config.name}' has exited, cleaning up...")
+                del self.processes[key]
+                self._save_state()
+        
+        if config.singleton_match:
+            existing = self.find_singleton(config.singleton_match)
+            if existing:
+                # Track the other copy so stop/status work on it, but don't start a second one
+                self.processes[key] = AdoptedProcess(existing)
+                self._started_at[key] = time.time()
+                self._save_state()
+                logger.info(f"Adopted running process for '{config.name}' (PID: {existing.pid})")
+                raise Exception(f"App '{config.name}' is already running (PID: {existing.pid}, started outside the orchestrator)")
+        
+        if config.working_directory and config.create_working_dir and not os.path.isdir(config.working_directory):
+            os.makedirs(config.working_directory)
+            logger.info(f"Created working directory for '{config.name}': {config.working_directory}")
+        problems = config.launch_problems()
+        if problems:
+            raise Exception(f"Cannot launch app '{config.name}': {'; '.join(message for _, message in problems)}")
         
         try:
             if config.use_shell:
                 # Build shell command
                 if config.shell_command:
                     # Use custom shell command
-                    shell_cmd = config.shell_command
+                    shell_cmd = config.shell_command + self._shell_args(config)
                 elif config.conda_env:
                     # Build conda activation command
                     conda_base = "conda" if sys.platform == 'win32' else "source $(conda info --base)/etc/profile.d/conda.sh && conda"
@@ -57,85 +356,396 @@ class ProcessManager:
                     exec_path = config.executable_path
                     if config.working_directory and not os.path.isabs(exec_path):
                         exec_path = os.path.join(config.working_directory, exec_path)
-                    cmd_parts.append(f"python {exec_path}")
+                    cmd_parts.append(f"python {exec_path}{self._shell_args(config)}")
                     
                     shell_cmd = " && ".join(cmd_parts)
                 else:
                     # Just run the executable path as a command
-                    shell_cmd = config.executable_path
+                    shell_cmd = config.executable_path + self._shell_args(config)
                 
                 # Execute via shell
                 if sys.platform == 'win32':
                     # Windows: use cmd.exe
-                    # Don't pipe stdout/stderr to allow subprocesses and interactive tools to work properly
-                    process = subprocess.Popen(
-                        shell_cmd,
-                        shell=True,
-                        cwd=config.working_directory,
-                        creationflags=subprocess.CREATE_NEW_CONSOLE if sys.platform == 'win32' else 0
-                    )
+                    # Output goes to a log file rather than a pipe so subprocesses never block on a full pipe
+                    process = self._spawn(config, shell_cmd, shell=True, **self._window_kwargs(config, new_console=True))
                 else:
                     # Mac/Linux: use sh
-                    # Don't pipe stdout/stderr to allow subprocesses and interactive tools to work properly
-                    process = subprocess.Popen(
-                        ['sh', '-c', shell_cmd],
-                        cwd=config.working_directory
-                    )
+                    process = self._spawn(config, ['sh', '-c', shell_cmd], **self._window_kwargs(config))
             else:
-                # Direct execution
-                # Don't pipe stdout/stderr to allow subprocesses and interactive tools to work properly
-                process = subprocess.Popen(
-                    [config.executable_path],
-                    cwd=config.working_directory
-                )
+                # Direct execution (launch_problems has checked the executable resolves)
+                process = self._spawn(config, [config.resolve_executable()] + list(config.args), **self._window_kwargs(config))
             
-            self.processes[config.id] = process
-            print(f"Launched app: {config.name} (PID: {process.pid})")
+            self.processes[key] = process
+            if config.detached:
+                self._detached.add(key)
+            else:
+                self._detached.discard(key)
+            self._started_at[key] = time.time()
+            self._app_names[key] = config.name
+            self._save_state()
+            logger.info(f"Launched app: {config.name} (PID: {process.pid})")
+            self._emit('app-started', {'app_id': config.id, 'name': config.name, 'pid': process.pid})
+            self._ensure_watcher()
             
         except Exception as e:
             raise Exception(f"Failed to launch app '{config.name}': {e}")
     
+    @staticmethod
+    def matches_singleton(name: str, cmdline: List[str], pattern: str) -> bool:
+        """Whether a process's name equals pattern, or its command line contains it (case-insensitive)"""
+        pattern = pattern.lower()
+        return (name or '').lower() == pattern or pattern in ' '.join(cmdline or []).lower()
+    
+    def find_singleton(self, pattern: str) -> Optional[psutil.Process]:
+        """A running process, other than this one or ones we already track, matching singleton_match"""
+        own_pids = {os.getpid()} | {process.pid for process in self.processes.values()}
+        for ps_process in psutil.process_iter(['pid', 'name', 'cmdline']):
+            try:
+                info = ps_process.info
+                if info['pid'] in own_pids:
+                    continue
+                if self.matches_singleton(info['name'], info['cmdline'], pattern):
+                    return ps_process
+            except (psutil.NoSuchProcess, psutil.AccessDenied):
+                continue
+        return None
+    
+    @staticmethod
+    def get_log_dir() -> Path:
+        """Directory holding captured app logs"""
+        log_dir = AppConfig.get_config_path().parent / 'logs'
+        log_dir.mkdir(parents=True, exist_ok=True)
+        return log_dir
+    
+    @classmethod
+    def get_log_path(cls, app_id: str) -> Path:
+        """Log file capturing an app's stdout/stderr"""
+        return cls.get_log_dir() / f"{app_id}.log"
+    
+    @classmethod
+    def get_log_backup_path(cls, app_id: str) -> Path:
+        """Log rotated out of the way by rotate_logs, kept until the next rotation"""
+        return cls.get_log_dir() / f"{app_id}.log.1"
+    
+    def rotate_logs(self, max_size_mb: int, max_age_days: int) -> None:
+        """
+        Keep captured logs bounded (0 turns either limit off): a log over max_size_mb is copied
+        to <app>.log.1, replacing the last copy, and emptied; logs untouched for max_age_days
+        are deleted.
+        
+        Logs are copied and truncated rather than renamed because running apps hold them open.
+        They append, so on POSIX they carry on at the start of the emptied file; a Windows handle
+        keeps its old offset, so there a running app's log waits until it has exited.
+        """
+        cutoff = time.time() - max_age_days * 86400
+        for path in self.get_log_dir().iterdir():
+            is_backup = path.name.endswith('.log.1')
+            if not is_backup and path.suffix != '.log':
+                continue
+            app_id = path.name[:-len('.log.1')] if is_backup else path.stem
+            try:
+                stat = path.stat()
+                if max_age_days > 0 and stat.st_mtime < cutoff and (is_backup or not self.is_running(app_id)):
+                    path.unlink()
+                    logger.info(f"Deleted expired log {path.name}")
+                elif (max_size_mb > 0 and not is_backup and stat.st_size > max_size_mb * 1024 * 1024
+                      and not (sys.platform == 'win32' and self.is_running(app_id))):
+                    shutil.copyfile(path, self.get_log_backup_path(app_id))
+                    os.truncate(path, 0)
+                    logger.info(f"Rotated log {path.name} ({stat.st_size} bytes)")
+            except OSError as e:
+                logger.warning("Could not clean up log %s: %s", path.name, e)
+    
+    def clear_logs(self, app_id: str) -> None:
+        """Delete an app's captured output, including the rotated copy"""
+        if sys.platform == 'win32' and self.is_running(app_id):
+            # Truncating under a running app's handle would leave a gap of NUL bytes (see rotate_logs)
+            raise InProgress(f"Stop app {app_id} before clearing its logs")
+        self.get_log_backup_path(app_id).unlink(missing_ok=True)
+        log_path = self.get_log_path(app_id)
+        if log_path.exists():
+            os.truncate(log_path, 0)
+    
+    @staticmethod
+    def _shell_args(config: LocalAppConfig) -> str:
+        """Configured arguments quoted for appending to a shell command line"""
+        if not config.args:
+            return ""
+        if sys.platform == 'win32':
+            return " " + subprocess.list2cmdline(config.args)
+        return " " + shlex.join(config.args)
+    
+    @staticmethod
+    def _window_kwargs(config: LocalAppConfig, new_console: bool = False) -> Dict[str, Any]:
+        """
+        Popen arguments for hide_window and detached. new_console gives a Windows app
+        its own console when neither is set, as shell launches always have.
+        """
+        if sys.platform == 'win32':
+            if config.detached:
+                # A detached process has no console at all, so nothing flashes up either
+                flags = subprocess.DETACHED_PROCESS | subprocess.CREATE_NEW_PROCESS_GROUP
+            elif config.hide_window:
+                flags = subprocess.CREATE_NO_WINDOW
+            else:
+                flags = subprocess.CREATE_NEW_CONSOLE if new_console else 0
+            return {'creationflags': flags}
+        # Its own session keeps it out of the orchestrator's terminal and process group signals
+        return {'start_new_session': True} if config.detached else {}
+    
+    def _spawn(self, config: LocalAppConfig, args, **popen_kwargs) -> subprocess.Popen:
+        """Start a process with stdout/stderr appended to the app's log file"""
+        with open(self.get_log_path(config.id), 'ab') as log_file:
+            log_file.write(f"\n=== Launching {config.name} at {time.strftime('%Y-%m-%d %H:%M:%S')} ===\n".encode())
+            log_file.flush()
+            # The child inherits its own copy of the handle, so ours can be closed right away
+            env = {**os.environ, **config.env} if config.env else None
+            return subprocess.Popen(
+                args,
+                cwd=config.working_directory,
+                env=env,
+                stdout=log_file,
+                stderr=subprocess.STDOUT,
+                **popen_kwargs
+            )
+    
+    def get_logs(self, app_id: str, lines: int = 200) -> str:
+        """Get the last lines of an app's captured output"""
+        log_path = self.get_log_path(app_id)
+        if not log_path.exists():
+            return ""
+        with open(log_path, 'r', encoding='utf-8', errors='replace') as f:
+            return ''.join(deque(f, maxlen=lines))
+    
+    @staticmethod
+    def check_health(health_check: HealthCheck) -> Tuple[bool, str]:
+        """Run a health check, returning (healthy, detail)"""
+        if health_check.url:
+            try:
+                with urllib.request.urlopen(health_check.url, timeout=health_check.timeout_secs) as response:
+                    return response.status == 200, f"HTTP {response.status}"
+            except urllib.error.HTTPError as e:
+                return False, f"HTTP {e.code}"
+            except Exception as e:
+                return False, f"Unreachable: {e}"
+        
+        if health_check.port:
+            try:
+                with socket.create_connection((health_check.host, health_check.port), timeout=health_check.timeout_secs):
+                    return True, f"Port {health_check.port} is accepting connections"
+            except OSError as e:
+                return False, f"Port {health_check.port} is not accepting connections: {e}"
+        
+        return False, "Health check has neither a port nor a URL"
+    
+    def get_stats(self, app_id: str) -> AppStats:
+        """Sample an app's CPU and memory usage (running=False if it isn't, or has just exited)"""
+        with self._lock:
+            process = self.processes.get(ProcessKey.app(app_id))
+        if process is None or process.poll() is not None:
+            return AppStats(running=False)
+        
+        try:
+            parent = psutil.Process(process.pid)
+            procs = [parent] + parent.children(recursive=True)
+        except psutil.NoSuchProcess:
+            return AppStats(running=False)
+        
+        # The first cpu_percent call only starts the measurement
+        for proc in procs:
+            try:
+                proc.cpu_percent(None)
+            except (psutil.NoSuchProcess, psutil.AccessDenied):
+                pass
+        time.sleep(CPU_SAMPLE_SECS)
+        
+        stats = AppStats(running=True, pid=process.pid)
+        for proc in procs:
+            try:
+                stats.cpu_percent += proc.cpu_percent(None)
+                stats.memory_rss_bytes += proc.memory_info().rss
+                stats.process_count += 1
+            except (psutil.NoSuchProcess, psutil.AccessDenied):
+                continue
+        
+        # The app itself may have died while we were sampling
+        if not parent.is_running():
+            return AppStats(running=False)
+        stats.cpu_percent = round(stats.cpu_percent, 1)
+        return stats
+    
     def is_running(self, app_id: str) -> bool:
         """Check if an app is currently running"""
-        if app_id not in self.processes:
-            return False
-        
-        process = self.processes[app_id]
-        if process.poll() is None:
-            return True
-        else:
-            # Process has exited
-            del self.processes[app_id]
+        key = ProcessKey.app(app_id)
+        with self._lock:
+            if key not in self.processes:
+                return False
+            
+            process = self.processes[key]
+            if process.poll() is None:
+                return True
+            
+            # Monitored apps are cleaned up (or relaunched) by the watcher, stopping ones by terminate
+            if key not in self._monitored and key not in self._terminating:
+                self._reap(key, process.poll())
             return False
     
-    def terminate(self, app_id: str) -> None:
-        """Terminate a running app"""
-        if app_id not in self.processes:
+    def terminate(self, app_id: str, grace_period: float = DEFAULT_GRACE_PERIOD) -> None:
+        """
+        Terminate a running app (and stop auto-restarting it).
+        
+        The app and its children are asked to exit (SIGTERM on Unix, a close request on
+        Windows) and only force-killed if still alive after grace_period seconds.
+        """
+        key = ProcessKey.app(app_id)
+        with self._lock:
+            # Intentional stops must not be undone by the watcher
+            self._monitored.pop(key, None)
+            self._restart_at.pop(key, None)
+            self._restart_counts.pop(key, None)
+            process = self.processes.get(key)
+            if process is not None:
+                self._terminating.add(key)
+        
+        if process is None:
             return
         
-        process = self.processes[app_id]
         try:
-            process.terminate()
-            process.wait(timeout=5)
-        except subprocess.TimeoutExpired:
-            process.kill()
+            self._stop_process_tree(process.pid, grace_period)
         finally:
-            del self.processes[app_id]
+            with self._lock:
+                self._terminating.discard(key)
+                if self.processes.get(key) is process:
+                    del self.processes[key]
+                self._save_state()
+            self._emit('app-terminated', {'app_id': app_id, 'name': self._app_names.get(key, app_id), 'pid': process.pid})
+    
+    @staticmethod
+    def _stop_process_tree(pid: int, grace_period: float) -> None:
+        """Gracefully stop a process and its children, escalating to a kill after the grace period"""
+        try:
+            parent = psutil.Process(pid)
+            procs = [parent] + parent.children(recursive=True)
+        except psutil.NoSuchProcess:
+            return
+        
+        if sys.platform == 'win32':
+            # taskkill without /F posts WM_CLOSE so GUI apps can save state
+            subprocess.run(['taskkill', '/PID', str(pid), '/T'], capture_output=True)
+        else:
+            for proc in procs:
+                try:
+                    proc.terminate()  # SIGTERM
+                except psutil.NoSuchProcess:
+                    pass
+        
+        _, alive = psutil.wait_procs(procs, timeout=grace_period)
+        for proc in alive:
+            logger.warning("Process %s did not exit within %ss, killing it", proc.pid, grace_period)
+            try:
+                proc.kill()
+            except psutil.NoSuchProcess:
+                pass
     
     def cleanup_all(self) -> None:
-        """Terminate all running processes"""
-        for app_id in list(self.processes.keys()):
+        """Terminate all running processes except detached ones, which are re-adopted on next start"""
+        for key in list(self.processes.keys()):
+            if key in self._detached:
+                continue
             try:
-                self.terminate(app_id)
+                self.terminate(key.id)
             except:
                 pass
-
-
-# Global process manager instance
-_process_manager = ProcessManager()
-
-
-def get_process_manager() -> ProcessManager:
-    """Get the global process manager instance"""
-    return _process_manager
-
+    
+    def _ensure_watcher(self) -> None:
+        """Start the background watcher (exit reaping and crash restarts) if it isn't running"""
+        if self._watcher and self._watcher.is_alive():
+            return
+        self._watcher = threading.Thread(target=self._watch, daemon=True)
+        self._watcher.start()
+    
+    def _watch(self) -> None:
+        """Emit app-exited for apps that exit, relaunching monitored ones with exponential backoff"""
+        while True:
+            time.sleep(WATCH_INTERVAL)
+            with self._lock:
+                for key, config in list(self._monitored.items()):
+                    try:
+                        self._check_monitored(key, config)
+                    except Exception as e:
+                        logger.error("Error watching app %s (%s): %s", key.id, config.name, e)
+                
+                for key, process in list(self.processes.items()):
+                    if key in self._monitored or key in self._terminating:
+                        continue
+                    exit_code = process.poll()
+                    if exit_code is not None:
+                        self._reap(key, exit_code)
+    
+    def _reap(self, key: ProcessKey, exit_code: int) -> None:
+        """Forget an unmonitored app that exited and report it (caller holds the lock)"""
+        process = self.processes.pop(key, None)
+        self._save_state()
+        logger.warning("App %s (%s) exited with code %s", key.id, self._app_names.get(key, key.id), exit_code)
+        self._emit_exited(key, exit_code, process.pid if process else None)
+    
+    def _emit_exited(self, key: ProcessKey, exit_code: int, pid: Optional[int]) -> None:
+        self._emit('app-exited', {'app_id': key.id, 'name': self._app_names.get(key, key.id),
+                                  'exit_code': exit_code, 'pid': pid})
+    
+    def _check_monitored(self, key: ProcessKey, config: LocalAppConfig) -> None:
+        """Handle one watcher tick for a monitored app (caller holds the lock)"""
+        now = time.time()
+        process = self.processes.get(key)
+        
+        if key in self._restart_at:
+            if now < self._restart_at[key]:
+                return
+            del self._restart_at[key]
+            attempt = self._restart_counts.get(key, 0) + 1
+            self._restart_counts[key] = attempt
+            logger.info(f"Restarting app '{config.name}' (attempt {attempt}/{config.max_restarts})...")
+            self.processes.pop(key, None)
+            try:
+                self._launch(config)
+            except Exception as e:
+                # A relaunch that can't even start counts against the budget like a crash
+                if attempt < config.max_restarts:
+                    delay = min(2 ** attempt, MAX_RESTART_BACKOFF)
+                    logger.warning("Failed to restart app %s (%s): %s; retrying in %ss", key.id, config.name, e, delay)
+                    self._restart_at[key] = now + delay
+                else:
+                    logger.error("Failed to restart app %s (%s): %s; giving up after %s attempts", key.id, config.name, e, attempt)
+                    del self._monitored[key]
+                    self._save_state()
+                    self._emit('app-restart-failed', {'app_id': key.id, 'name': config.name, 'error': str(e)})
+                return
+            self._emit('app-restarted', {'app_id': key.id, 'attempt': attempt, 'pid': self.processes[key].pid})
+            return
+        
+        if process is None or process.poll() is None:
+            return
+        
+        exit_code = process.poll()
+        self._emit_exited(key, exit_code, process.pid)
+        if exit_code != 0:
+            self._emit('app-crashed', {'app_id': key.id, 'name': config.name, 'exit_code': exit_code})
+        should_restart = (config.restart_policy == RestartPolicy.ALWAYS
+                          or (config.restart_policy == RestartPolicy.ON_FAILURE and exit_code != 0))
+        
+        # An app that stayed up for a while gets its restart budget back
+        if now - self._started_at.get(key, now) > STABLE_UPTIME:
+            self._restart_counts.pop(key, None)
+        restarts = self._restart_counts.get(key, 0)
+        
+        if not should_restart or restarts >= config.max_restarts:
+            if should_restart:
+                logger.error("App %s (%s) exited with code %s; giving up after %s restarts", key.id, config.name, exit_code, restarts)
+            del self._monitored[key]
+            del self.processes[key]
+            self._save_state()
+            return
+        
+        delay = min(2 ** restarts, MAX_RESTART_BACKOFF)
+        logger.warning("App %s (%s) exited with code %s; restarting in %ss", key.id, config.name, exit_code, delay)
+        self._restart_at[key] = now + delay
@@ -1,89 +1,1291 @@
 """SSH client for remote Docker operations using paramiko"""
 import json
+import os
+import re
+import shlex
+import socket
+import threading
+import time
 import paramiko
+from contextlib import contextmanager
 import logging
-from typing import List, Dict, Optional
-from dataclasses import dataclass
+from typing import Any, List, Dict, Iterator, Optional, Callable, Tuple, Union
+from dataclasses import dataclass, field
+from enum import Enum
+from config import AuthMethod, ServerConfig, split_host_port, unique_key_paths, url_host
+from keychain import KEYCHAIN_PLACEHOLDER
+from errors import (AuthFailed, Cancelled, CommandFailed, ConnectionFailed, DockerMissing, HostKeyRejected,
+                    InvalidConfig, NotFound, PortInUse, SudoPasswordRequired, Timeout,
+                    command_error)
 
+logger = logging.getLogger(__name__)
 logging.getLogger('paramiko').setLevel(logging.WARNING)
 
+def quote_path(path: str) -> str:
+    """Shell-quote a remote path, leaving a leading ~/ unquoted so it still expands to the home directory"""
+    if path == "~":
+        return path
+    if path.startswith("~/"):
+        return "~/" + shlex.quote(path[2:])
+    return shlex.quote(path)
+
+
 @dataclass
 class ContainerStatus:
     """Docker container status"""
     name: str
     status: str
     state: str
+    service: str = ""  # Compose service name (container names are prefixed with the project)
+    health: str = ""  # healthy/unhealthy/starting, empty if the container has no healthcheck
+    
+    def matches(self, name: str) -> bool:
+        """Whether this is the container for a compose service or container called name"""
+        if name in (self.service, self.name):
+            return True
+        # Compose names containers <project>-<service>-<index> (underscores on v1)
+        return re.fullmatch(rf".+[-_]{re.escape(name)}[-_]\d+", self.name) is not None
+
+
+# State reported for a compose service that has no container yet
+NOT_CREATED = "not-created"
+
+
+@dataclass
+class ComposeServiceState:
+    """A service defined in a compose file and the state of its container"""
+    name: str
+    state: str  # Container state (running, exited, ...) or NOT_CREATED
+    container: str = ""  # Container name, empty if not created
+
+
+class ProjectState(str, Enum):
+    """What docker compose ps says about a service's compose project as a whole"""
+    RUNNING = "running"  # At least one container is running
+    STOPPED = "stopped"  # Containers exist, none running
+    NOT_CREATED = NOT_CREATED  # ps succeeded with no containers: compose up has never run (or down removed them)
+    MISSING = "missing"  # The path or its compose file doesn't exist
+    UNAVAILABLE = "unavailable"  # docker compose ps itself failed, e.g. the daemon isn't running
+
+
+@dataclass
+class ComposeProject:
+    """A compose project's state, its containers (stopped ones included) and why, if it couldn't be checked"""
+    state: ProjectState
+    containers: List[ContainerStatus] = field(default_factory=list)
+    detail: str = ""
+
+
+def project_state(exit_code: int, containers: List[ContainerStatus]) -> ProjectState:
+    """State of a project from the exit code and parsed output of docker compose ps -a"""
+    if exit_code != 0:
+        return ProjectState.UNAVAILABLE
+    if not containers:
+        return ProjectState.NOT_CREATED
+    return ProjectState.RUNNING if any(c.state == "running" for c in containers) else ProjectState.STOPPED
+
+
+def merge_service_states(service_names: List[str], containers: List[ContainerStatus]) -> List[ComposeServiceState]:
+    """Pair every defined service with its container, preferring a running replica"""
+    states = []
+    for name in service_names:
+        matching = sorted((c for c in containers if c.service == name), key=lambda c: c.state != "running")
+        if matching:
+            states.append(ComposeServiceState(name, matching[0].state, matching[0].name))
+        else:
+            states.append(ComposeServiceState(name, NOT_CREATED))
+    return states
+
+
+def parse_compose_ps(output: str) -> List[ContainerStatus]:
+    """
+    Parse 'docker compose ps --format json' output.
+    
+    Compose v2.20+ prints a single JSON array, older versions print one JSON object
+    per line. Non-JSON lines (warnings, errors merged from stderr) are skipped.
+    """
+    entries = []
+    try:
+        parsed = json.loads(output)
+        entries = parsed if isinstance(parsed, list) else [parsed]
+    except json.JSONDecodeError:
+        for line in output.strip().split('\n'):
+            line = line.strip()
+            if not line:
+                continue
+            try:
+                parsed = json.loads(line)
+            except json.JSONDecodeError:
+                # Skip non-JSON lines (like error messages)
+                continue
+            if isinstance(parsed, list):
+                entries.extend(parsed)
+            else:
+                entries.append(parsed)
+    
+    return [
+        ContainerStatus(
+            name=entry.get('Name', ''),
+            status=entry.get('Status', ''),
+            state=entry.get('State', ''),
+            service=entry.get('Service', ''),
+            health=entry.get('Health', '')
+        )
+        for entry in entries
+        if isinstance(entry, dict)
+    ]
+
+
+@dataclass
+class ContainerStats:
+    """Docker container resource usage"""
+    name: str
+    cpu_percent: float
+    memory_usage_bytes: int
+    memory_limit_bytes: int
+    memory_percent: float
+
+
+_SIZE_UNITS = {
+    'b': 1,
+    'kb': 1000, 'mb': 1000 ** 2, 'gb': 1000 ** 3, 'tb': 1000 ** 4,
+    'kib': 1024, 'mib': 1024 ** 2, 'gib': 1024 ** 3, 'tib': 1024 ** 4,
+}
+
+
+def parse_size(value: str) -> int:
+    """Parse a docker human-readable size like '1.5GiB' or '512kB' into bytes"""
+    match = re.match(r'^\s*([\d.]+)\s*([a-zA-Z]*)\s*$', value or '')
+    if not match:
+        return 0
+    number, unit = match.groups()
+    return int(float(number) * _SIZE_UNITS.get(unit.lower() or 'b', 1))
+
+
+def parse_percent(value: str) -> float:
+    """Parse a docker percentage like '12.5%' into a float"""
+    try:
+        return float((value or '').strip().rstrip('%'))
+    except ValueError:
+        return 0.0
+
+
+@dataclass
+class DiskUsageEntry:
+    """One row of docker system df: how many objects of a type there are and the space they take"""
+    total_count: int = 0
+    active: int = 0  # In use by a container
+    size_bytes: int = 0
+    reclaimable_bytes: int = 0  # Freed by pruning what isn't in use
+
+
+@dataclass
+class DockerDiskUsage:
+    """Space used by docker on a server, from docker system df"""
+    images: DiskUsageEntry = field(default_factory=DiskUsageEntry)
+    containers: DiskUsageEntry = field(default_factory=DiskUsageEntry)
+    volumes: DiskUsageEntry = field(default_factory=DiskUsageEntry)
+    build_cache: DiskUsageEntry = field(default_factory=DiskUsageEntry)
+
+
+# docker system df row Type -> DockerDiskUsage field
+DISK_USAGE_TYPES = {
+    'Images': 'images',
+    'Containers': 'containers',
+    'Local Volumes': 'volumes',
+    'Build Cache': 'build_cache',
+}
+
+
+def _parse_count(value: Any) -> int:
+    try:
+        return int(value)
+    except (TypeError, ValueError):
+        return 0
+
+
+def parse_disk_usage(output: str) -> DockerDiskUsage:
+    """Parse 'docker system df' JSON lines; unknown rows are skipped and missing ones stay zero"""
+    usage = DockerDiskUsage()
+    for line in output.splitlines():
+        try:
+            entry = json.loads(line)
+        except json.JSONDecodeError:
+            continue
+        if not isinstance(entry, dict) or entry.get('Type') not in DISK_USAGE_TYPES:
+            continue
+        # Reclaimable looks like "1.2GB (45%)"
+        reclaimable = (entry.get('Reclaimable') or '').split(' ')[0]
+        setattr(usage, DISK_USAGE_TYPES[entry['Type']], DiskUsageEntry(
+            total_count=_parse_count(entry.get('TotalCount')),
+            active=_parse_count(entry.get('Active')),
+            size_bytes=parse_size(entry.get('Size', '')),
+            reclaimable_bytes=parse_size(reclaimable)
+        ))
+    return usage
+
+
+# What prune_docker removes for each choice. None of them touch a volume a container still
+# uses, and "all" leaves volumes alone so a stopped stack's data survives a cleanup.
+PRUNE_COMMANDS = {
+    'images': "image prune -f",  # Dangling images, e.g. the ones replaced by a pull
+    'volumes': "volume prune -f",  # Volumes no container uses
+    'all': "system prune -f",  # Stopped containers, unused networks, dangling images and build cache
+}
+
+
+@dataclass
+class PruneResult:
+    """What a docker prune freed"""
+    what: str  # A PRUNE_COMMANDS key
+    reclaimed_bytes: int
+    output: str
+
+
+def parse_reclaimed_space(output: str) -> int:
+    """Bytes freed according to a prune's "Total reclaimed space: 1.2GB" line (0 if it has none)"""
+    match = re.search(r'Total reclaimed space:\s*(\S+)', output)
+    return parse_size(match.group(1)) if match else 0
+
+
+@dataclass
+class PullResult:
+    """Images updated vs already current after a docker compose pull"""
+    updated: List[str] = field(default_factory=list)
+    up_to_date: List[str] = field(default_factory=list)
+
+
+# Compose up progress words mapped to the action taken, v2 ("Container x  Recreated") and v1
+# ("Recreating x ... done", "x is up-to-date"). Later actions in this order win for a container.
+UP_ACTIONS = {
+    'Running': 'up_to_date', 'up-to-date': 'up_to_date',
+    'Starting': 'started', 'Started': 'started', 'Start': 'started',
+    'Creating': 'created', 'Created': 'created', 'Create': 'created',
+    'Recreating': 'recreated', 'Recreated': 'recreated', 'Recreate': 'recreated',
+}
+UP_ACTION_PRIORITY = ['up_to_date', 'started', 'created', 'recreated']
+
+
+@dataclass
+class UpResult:
+    """What docker compose up -d did to each container"""
+    recreated: List[str] = field(default_factory=list)
+    created: List[str] = field(default_factory=list)
+    started: List[str] = field(default_factory=list)
+    up_to_date: List[str] = field(default_factory=list)
+
+
+def parse_compose_up(output: str) -> Dict[str, str]:
+    """Map each container named in compose up output to the most significant action taken on it"""
+    actions: Dict[str, str] = {}
+    patterns = [
+        r'\bContainer\s+(?P<name>\S+)\s+(?P<word>\w+)\b',  # v2
+        r'^\s*(?P<word>Recreating|Creating|Starting)\s+(?P<name>\S+)',  # v1
+        r'^\s*(?P<name>\S+)\s+is\s+(?P<word>up-to-date)',  # v1
+    ]
+    for line in output.splitlines():
+        for pattern in patterns:
+            match = re.search(pattern, line)
+            if not match or match.group('word') not in UP_ACTIONS:
+                continue
+            action = UP_ACTIONS[match.group('word')]
+            name = match.group('name')
+            previous = actions.get(name)
+            if previous is None or UP_ACTION_PRIORITY.index(action) > UP_ACTION_PRIORITY.index(previous):
+                actions[name] = action
+            break
+    return actions
+
+
+def parse_port_conflict(output: str) -> Optional[int]:
+    """
+    The host port from docker's "port is already allocated" or "address already in use"
+    error in compose up output, or None if that's not why it failed
+    """
+    match = re.search(r':(\d+) failed: port is already allocated|:(\d+): bind: address already in use', output)
+    if not match:
+        return None
+    return int(match.group(1) or match.group(2))
+
+
+def parse_image_ids(output: str) -> Dict[str, str]:
+    """Parse '<image> <id>' lines into a mapping (id is '' for images not present locally)"""
+    images = {}
+    for line in output.splitlines():
+        parts = line.split()
+        if parts:
+            images[parts[0]] = parts[1] if len(parts) > 1 else ""
+    return images
+
+
+@dataclass
+class DownResult:
+    """What "docker compose down" stopped and removed"""
+    stopped: List[str] = field(default_factory=list)  # Containers
+    removed: List[str] = field(default_factory=list)  # Containers
+    networks: List[str] = field(default_factory=list)  # Removed networks
+    volumes: List[str] = field(default_factory=list)  # Removed volumes
+
+
+def parse_compose_down(output: str) -> DownResult:
+    """Parse progress lines like " ✔ Container portal-web-1  Removed  0.3s" from compose down"""
+    result = DownResult()
+    targets = {
+        ('Container', 'Stopped'): result.stopped,
+        ('Container', 'Removed'): result.removed,
+        ('Network', 'Removed'): result.networks,
+        ('Volume', 'Removed'): result.volumes,
+    }
+    for match in re.finditer(r'\b(Container|Network|Volume)\s+(\S+)\s+(Stopped|Removed)\b', output):
+        names = targets.get((match.group(1), match.group(3)))
+        if names is not None and match.group(2) not in names:
+            names.append(match.group(2))
+    return result
+
+
+@dataclass
+class LogOutput:
+    """Log lines fetched from a server, newest last"""
+    output: str
+    truncated: bool  # More lines matched than the tail limit allowed; fetch more to see them
+
+
+def trim_log_tail(output: str, lines: int) -> LogOutput:
+    """Keep the last `lines` lines of output fetched with one extra line, flagging whether any were cut"""
+    all_lines = output.splitlines()
+    return LogOutput(output="\n".join(all_lines[-lines:]), truncated=len(all_lines) > lines)
+
+
+@dataclass
+class Prerequisites:
+    """Whether docker and docker compose are installed on a server, with their versions"""
+    docker_installed: bool
+    docker_version: str
+    compose_installed: bool
+    compose_version: str
+    compose_command: str = "docker compose"  # What was checked for compose_installed
+
+
+@dataclass
+class SessionInfo:
+    """What the SSH server said about itself while connecting"""
+    server_version: Optional[str] = None  # Remote identification string, e.g. SSH-2.0-OpenSSH_9.6
+    auth_banner: Optional[str] = None  # Pre-login message (sshd's Banner), if the server sent one
+
+
+@dataclass
+class ContainerEvent:
+    """A container lifecycle or health transition reported by docker events"""
+    time: int  # Unix timestamp
+    container: str
+    service: str  # Compose service name, empty for non-compose containers
+    action: str  # e.g. start, die, restart, health_status
+    health: str = ""  # healthy/unhealthy/starting for health_status events
+
+
+def parse_container_events(output: str) -> List[ContainerEvent]:
+    """Parse "docker events --format '{{json .}}'" output (one JSON object per line)"""
+    events = []
+    for line in output.splitlines():
+        line = line.strip()
+        if not line.startswith('{'):
+            continue
+        try:
+            data = json.loads(line)
+        except json.JSONDecodeError:
+            continue
+        
+        attributes = data.get('Actor', {}).get('Attributes', {})
+        action = data.get('Action') or data.get('status', '')
+        health = ""
+        # Health transitions arrive as "health_status: healthy"
+        if action.startswith('health_status'):
+            action, _, health = action.partition(':')
+            health = health.strip()
+        events.append(ContainerEvent(
+            time=int(data.get('time', 0)),
+            container=attributes.get('name', data.get('id', '')[:12]),
+            service=attributes.get('com.docker.compose.service', ''),
+            action=action,
+            health=health
+        ))
+    return events
+
+
+@dataclass
+class AuthTiming:
+    """How long each step of an SSH login took, to diagnose slow servers"""
+    auth_method: str  # key, password or agent - whichever succeeded
+    connect_ms: int  # TCP connect
+    handshake_ms: int  # SSH key exchange
+    auth_ms: int
+    command_ms: int  # Opening a channel and running echo
+
+
+@dataclass
+class DiagnosticStage:
+    """Outcome of one step of a connection diagnosis"""
+    name: str  # resolve, tcp, handshake, host_key or auth
+    status: str  # ok, failed or skipped
+    duration_ms: int = 0
+    detail: str = ""
+
+
+@dataclass
+class ConnectionDiagnosis:
+    """Every stage of connecting to a server, and the first that failed, if any"""
+    stages: List[DiagnosticStage] = field(default_factory=list)
+    failed_stage: Optional[str] = None
+    server_version: Optional[str] = None  # The SSH banner, e.g. SSH-2.0-OpenSSH_9.6
+    auth_banner: Optional[str] = None  # Pre-login message sent during authentication
+
+
+class ServiceAction(str, Enum):
+    """Actions that can be applied to a single compose service"""
+    START = "start"
+    STOP = "stop"
+    RESTART = "restart"
+
+
+def is_accepted_status(status_code: int, accepted: List[str]) -> bool:
+    """Whether a status code matches one of the accepted codes ("200") or classes ("2xx")"""
+    code = str(status_code)
+    for pattern in accepted:
+        pattern = str(pattern).lower()
+        if pattern == code or (pattern.endswith('xx') and code.startswith(pattern[0]) and len(code) == 3):
+            return True
+    return False
+
+
+class HealthState(str, Enum):
+    """How a service's HTTP health endpoint is responding"""
+    READY = "ready"  # Returned an accepted status (200 by default)
+    STARTING = "starting"  # Answering, but not with an accepted status yet (e.g. 503 while warming up)
+    UNREACHABLE = "unreachable"  # Nothing answering (connection refused or timed out)
+
+
+@dataclass
+class ServiceHealth:
+    """Health state of a service along with the HTTP status code it returned (0 if unreachable)"""
+    state: HealthState
+    status_code: int = 0
+    
+    @classmethod
+    def from_status_code(cls, status_code: int, accepted: Optional[List[str]] = None) -> 'ServiceHealth':
+        if is_accepted_status(status_code, accepted or ["200"]):
+            return cls(HealthState.READY, status_code)
+        if status_code == 0:
+            return cls(HealthState.UNREACHABLE, status_code)
+        return cls(HealthState.STARTING, status_code)
+
+
+class UnknownHostKeyError(HostKeyRejected):
+    """Raised when the server's host key is not listed in known_hosts"""
+    
+    def __init__(self, hostname: str, key: paramiko.PKey, known_hosts_path: str):
+        self.hostname = hostname
+        self.key = key
+        super().__init__(
+            f"Unknown host key for {hostname} ({key.get_name()} {key.fingerprint}).\n"
+            f"The host is not listed in {known_hosts_path}. Verify the fingerprint and "
+            f"trust the host key before connecting."
+        )
+
+
+class _RejectUnknownHostKeyPolicy(paramiko.MissingHostKeyPolicy):
+    """Reject hosts missing from known_hosts with an error the UI can act on"""
+    
+    def __init__(self, known_hosts_path: str):
+        self.known_hosts_path = known_hosts_path
+    
+    def missing_host_key(self, client, hostname, key):
+        raise UnknownHostKeyError(hostname, key, self.known_hosts_path)
+
+
+@dataclass
+class CommandResult:
+    """Output and exit code of a remote command"""
+    stdout: str
+    stderr: str
+    exit_code: int
+    
+    def check(self, cmd: str) -> str:
+        """Return stdout, raising if the command exited non-zero"""
+        if self.exit_code != 0:
+            message = f"Failed to execute command '{cmd}': Command failed with exit status {self.exit_code}: {self.stderr or self.stdout}"
+            sudo_error = sudo_password_error(self.stderr or self.stdout)
+            if sudo_error:
+                raise sudo_error
+            if DOCKER_SOCKET_DENIED in (self.stderr or self.stdout):
+                raise CommandFailed(f"{message}\nAdd the SSH user to the docker group on the server, or turn on "
+                                    f"the server's \"Use sudo for docker\" setting")
+            # 127 is the shell's "command not found"
+            if self.exit_code == 127 and 'docker' in (self.stderr or self.stdout):
+                raise DockerMissing(f"{message}\nCheck docker is installed and on PATH, or set the server's compose command "
+                                    f"(e.g. docker-compose for Compose v1)")
+            raise CommandFailed(message)
+        return self.stdout
+
+
+class TransientConnectionError(ConnectionFailed):
+    """A connection failure worth retrying (reset, refused, timed out, handshake error)"""
+
+
+class CommandTimedOut(Timeout):
+    """Raised when a remote command doesn't finish within its timeout"""
+    
+    def __init__(self, cmd: str, timeout: float):
+        self.cmd = cmd
+        self.timeout = timeout
+        super().__init__(f"Command '{cmd}' timed out after {timeout}s")
+
+
+class CommandCancelled(Cancelled):
+    """Raised when a remote command is aborted through its cancel event"""
+    
+    def __init__(self, cmd: str):
+        self.cmd = cmd
+        super().__init__(f"Command '{cmd}' was cancelled")
+
+
+DEFAULT_KNOWN_HOSTS_PATH = os.path.join('~', '.ssh', 'known_hosts')
+
+# Seconds between checks for output, exit, timeout and cancellation
+COMMAND_POLL_INTERVAL = 0.1
+# Commands allowed to run at once over one connection. Each gets its own channel, and
+# OpenSSH refuses more than MaxSessions (10 by default); the rest is left for shells and SFTP.
+MAX_CONCURRENT_CHANNELS = 8
+# Largest file read_text_file/write_text_file handle; they're for .env and compose files, not data
+MAX_EDITABLE_FILE_BYTES = 1024 * 1024
+# Put before docker commands when use_sudo is set; -n makes sudo fail instead of prompting for a password
+SUDO_PREFIX = "sudo -n "
+# What sudo -n prints when the user's sudoers entry isn't NOPASSWD
+SUDO_PASSWORD_PROMPT = "sudo: a password is required"
+# What docker prints when the user can't open /var/run/docker.sock
+DOCKER_SOCKET_DENIED = "permission denied while trying to connect to the Docker daemon"
+# Put before pipelines whose first command's failure matters. Shells without pipefail (dash) skip it
+# rather than abort, since a failed set would end the whole command there
+PIPEFAIL_PREFIX = "(set -o pipefail) 2>/dev/null && set -o pipefail; "
+
+
+def sudo_password_error(output: str) -> Optional[SudoPasswordRequired]:
+    """A SudoPasswordRequired explaining the fix if a command's output shows sudo -n wanted a password"""
+    if SUDO_PASSWORD_PROMPT not in output:
+        return None
+    return SudoPasswordRequired(
+        "sudo needs a password to run docker, but the orchestrator can't type one.\n"
+        "Allow passwordless sudo for docker on the server, e.g. with a sudoers entry like\n"
+        "  <user> ALL=(root) NOPASSWD: /usr/bin/docker\n"
+        "or add the SSH user to the docker group and turn off \"Use sudo for docker\""
+    )
+
+
+def decode_auth_banner(banner: Union[bytes, str, None]) -> Optional[str]:
+    """An auth banner as text, or None if the server didn't send one"""
+    if isinstance(banner, bytes):
+        banner = banner.decode('utf-8', errors='replace')
+    return (banner or "").strip() or None
+
+
+def sftp_path(path: str) -> str:
+    """A remote path as SFTP takes it: SFTP doesn't expand ~, but resolves relative paths from home"""
+    if path == '~':
+        return '.'
+    return path[2:] if path.startswith('~/') else path
 
 
 class SSHClient:
     """SSH client for connecting to remote servers and executing commands"""
     
-    def __init__(self, host: str, port: int, username: str, ssh_key_path: str, portal_path: str = ""):
-        self.host = host
-        self.port = port
+    def __init__(self, host: str, port: int, username: str, ssh_key_path: str, portal_path: str = "",
+                 password: Optional[str] = None, ssh_key_passphrase: Optional[str] = None,
+                 known_hosts_path: Optional[str] = None, strict_host_key_checking: bool = True,
+                 connect_timeout: int = 10, command_timeout: int = 30,
+                 jump_host: Optional['SSHClient'] = None, dry_run: bool = False,
+                 compose_file: Optional[str] = None, compose_profiles: Optional[List[str]] = None,
+                 auth_method: AuthMethod = AuthMethod.KEY, ssh_key_paths: Optional[List[str]] = None,
+                 health_scheme: str = "http", health_host: str = "localhost", health_path: Optional[str] = None,
+                 health_accepted_statuses: Optional[List[str]] = None, proxy_command: Optional[str] = None,
+                 compose_command: str = "docker compose", kex_algorithms: Optional[List[str]] = None,
+                 host_key_algorithms: Optional[List[str]] = None, ciphers: Optional[List[str]] = None,
+                 use_sudo: bool = False):
+        self.host, self.port = split_host_port(host, port)
         self.username = username
         self.ssh_key_path = ssh_key_path
         self.portal_path = portal_path  # Kept for backward compatibility
+        # A placeholder is a secret the keychain couldn't give back, so there's nothing to send
+        password, ssh_key_passphrase = (None if secret == KEYCHAIN_PLACEHOLDER else secret
+                                        for secret in (password, ssh_key_passphrase))
+        self.password = password
+        # Blank means "no passphrase configured" rather than an empty passphrase
+        self.ssh_key_passphrase = ssh_key_passphrase or None
+        self.known_hosts_path = os.path.expanduser(known_hosts_path or DEFAULT_KNOWN_HOSTS_PATH)
+        self.strict_host_key_checking = strict_host_key_checking
+        self.connect_timeout = connect_timeout
+        self.command_timeout = command_timeout
+        self.jump_host = jump_host  # Unconnected client for the bastion, if any
+        self.proxy_command = proxy_command  # Spawned per connection; its stdio replaces the TCP socket
+        # Override paramiko's preferences when set, e.g. to reach old appliances
+        self.kex_algorithms = kex_algorithms or []
+        self.host_key_algorithms = host_key_algorithms or []
+        self.ciphers = ciphers or []
+        # Dry run records commands in dry_run_commands and returns them as output instead of running them
+        self.dry_run = dry_run
+        self.dry_run_commands: List[str] = []
+        self.compose_file = compose_file
+        self.compose_profiles = compose_profiles or []
+        self.compose_command = compose_command or "docker compose"
+        self.use_sudo = use_sudo
+        # Every docker invocation, compose included, starts with this
+        self.docker_command = f"{SUDO_PREFIX}docker" if use_sudo else "docker"
+        self.auth_method = AuthMethod(auth_method)
+        # Tried in order, like OpenSSH with several IdentityFile entries. ~ is expanded as config
+        # validation does, so a path like ~/.ssh/id_rsa that passed there is found here too
+        self.key_paths = unique_key_paths([os.path.expanduser(p or '') for p in [ssh_key_path] + list(ssh_key_paths or [])])
+        self.health_scheme = health_scheme
+        self.health_host = health_host
+        self.health_path = health_path  # Overrides the per-service path when set
+        self.health_accepted_statuses = health_accepted_statuses or ["200"]
         self.client = None
+        # Shared by every thread using this (pooled) connection; see _channel_slot
+        self._channel_slots = threading.BoundedSemaphore(MAX_CONCURRENT_CHANNELS)
+    
+    @classmethod
+    def from_server(cls, server: ServerConfig) -> 'SSHClient':
+        """Create a client from a server configuration"""
+        jump_host = None
+        if server.jump_host:
+            # The bastion shares the target's host key and timeout settings
+            jump_host = cls(
+                host=server.jump_host.host,
+                port=server.jump_host.port,
+                username=server.jump_host.username,
+                ssh_key_path=server.jump_host.ssh_key_path,
+                password=server.jump_host.password,
+                ssh_key_passphrase=server.jump_host.ssh_key_passphrase,
+                known_hosts_path=server.known_hosts_path,
+                strict_host_key_checking=server.strict_host_key_checking,
+                connect_timeout=server.connect_timeout_secs,
+                command_timeout=server.command_timeout_secs
+            )
+        
+        return cls(
+            host=server.host,
+            port=server.port,
+            username=server.username,
+            ssh_key_path=server.ssh_key_path,
+            password=server.password,
+            ssh_key_passphrase=server.ssh_key_passphrase,
+            known_hosts_path=server.known_hosts_path,
+            strict_host_key_checking=server.strict_host_key_checking,
+            connect_timeout=server.connect_timeout_secs,
+            command_timeout=server.command_timeout_secs,
+            jump_host=jump_host,
+            compose_file=server.compose_file,
+            compose_profiles=server.compose_profiles,
+            auth_method=server.auth_method,
+            ssh_key_paths=server.ssh_key_paths,
+            health_scheme=server.health_scheme,
+            health_host=server.health_host,
+            health_path=server.health_path,
+            health_accepted_statuses=server.health_accepted_statuses,
+            proxy_command=server.proxy_command,
+            compose_command=server.compose_command,
+            kex_algorithms=server.kex_algorithms,
+            host_key_algorithms=server.host_key_algorithms,
+            ciphers=server.ciphers,
+            use_sudo=server.use_sudo
+        )
     
     def connect(self) -> None:
-        """Establish SSH connection"""
+        """Establish SSH connection using the configured auth method (key auth falls back to password)"""
+        if self.dry_run:
+            return
+        
+        # Only the key method considers key files; password auth skips straight to the password
+        key_paths = self._existing_key_paths() if self.auth_method == AuthMethod.KEY else []
+        
+        if self.auth_method == AuthMethod.PASSWORD and not self.password:
+            raise InvalidConfig(f"Password authentication selected for {self.username}@{self.host}:{self.port} but no password is set")
+        if self.auth_method == AuthMethod.KEY and not key_paths and not self.password:
+            raise InvalidConfig(
+                f"No SSH authentication method configured for {self.username}@{self.host}:{self.port}\n"
+                f"SSH key not found at path: {', '.join(self.key_paths) or '(none)'} and no password is set."
+            )
+        
+        if self.kex_algorithms or self.host_key_algorithms or self.ciphers:
+            logger.warning("Using custom SSH algorithms for %s:%s; legacy algorithms weaken security", self.host, self.port)
+        
+        if self.jump_host:
+            self._connect_jump_host()
+        
+        failures = []
+        
         try:
-            self.client = paramiko.SSHClient()
-            self.client.set_missing_host_key_policy(paramiko.AutoAddPolicy())
+            if self.auth_method == AuthMethod.AGENT:
+                self._connect_with_agent()
+                return
             
-            # Check if key file exists
-            import os
-            if not os.path.exists(self.ssh_key_path):
-                raise Exception(f"SSH key not found at path: {self.ssh_key_path}\nPlease check the file exists and the path is correct.")
+            for key_path in key_paths:
+                # Read key file to check format
+                with open(key_path, 'r') as f:
+                    key_content = f.read()
+                
+                # Log key format (paramiko handles OpenSSH format natively!)
+                if "BEGIN OPENSSH PRIVATE KEY" in key_content:
+                    logger.debug("Note: Detected OpenSSH format key (ED25519 supported by paramiko)")
+                elif "BEGIN RSA PRIVATE KEY" in key_content or "BEGIN EC PRIVATE KEY" in key_content:
+                    logger.debug("Note: Detected PEM format key")
+                
+                pkey = self._load_key(key_path, failures)
+                if pkey:
+                    try:
+                        self._open(pkey=pkey)
+                        logger.info(f"Successfully connected to {self.username}@{self.host}:{self.port} (key {key_path})")
+                        return
+                    except paramiko.AuthenticationException as e:
+                        failures.append(f"key {key_path}: {e}")
             
-            # Read key file to check format
-            with open(self.ssh_key_path, 'r') as f:
-                key_content = f.read()
+            if self.auth_method == AuthMethod.KEY:
+                for key_path in self.key_paths:
+                    if key_path not in key_paths:
+                        failures.append(f"key {key_path}: file not found")
+                if not key_paths:
+                    logger.info(f"SSH key not found at {', '.join(self.key_paths) or '(none)'}, falling back to password")
             
-            # Log key format (paramiko handles OpenSSH format natively!)
-            if "BEGIN OPENSSH PRIVATE KEY" in key_content:
-                print("Note: Detected OpenSSH format key (ED25519 supported by paramiko)")
-            elif "BEGIN RSA PRIVATE KEY" in key_content or "BEGIN EC PRIVATE KEY" in key_content:
-                print("Note: Detected PEM format key")
+            if self.password:
+                try:
+                    self._open(password=self.password)
+                    logger.info(f"Successfully connected to {self.username}@{self.host}:{self.port} (password)")
+                    return
+                except paramiko.AuthenticationException as e:
+                    failures.append(f"password: {e}")
             
-            # Connect using private key (paramiko supports OpenSSH format including ED25519)
+        except (HostKeyRejected, AuthFailed):
+            raise
+        except paramiko.BadHostKeyException as e:
+            raise HostKeyRejected(
+                f"Host key verification failed for {self.host}:{self.port}: the key offered by the server "
+                f"({e.key.get_name()} {e.key.fingerprint}) does not match the one in {self.known_hosts_path}.\n"
+                f"Someone may be intercepting the connection, or the server was reinstalled. "
+                f"Remove the old entry from known_hosts only if you are sure the new key is legitimate."
+            )
+        except paramiko.SSHException as e:
+            # Auth failures are handled above, so this is a handshake/protocol error
+            raise TransientConnectionError(f"SSH connection failed{self._route()}: {e}")
+        except (OSError, EOFError) as e:
+            # Refused/reset connections and timeouts
+            raise TransientConnectionError(f"Failed to connect to {self.host}:{self.port}{self._route()}: {e}")
+        except Exception as e:
+            raise ConnectionFailed(f"Failed to connect to {self.host}:{self.port}{self._route()}: {e}")
+        
+        raise AuthFailed(
+            f"SSH authentication failed: all authentication methods were rejected\n"
+            f"Username: {self.username}\n" + "\n".join(f"  - {f}" for f in failures)
+        )
+    
+    def _connect_with_agent(self) -> None:
+        """Authenticate with each identity offered by the running ssh-agent until one is accepted"""
+        keys = paramiko.Agent().get_keys()
+        if not keys:
+            raise AuthFailed(
+                "SSH agent authentication failed: the agent has no identities "
+                "(is ssh-agent running, SSH_AUTH_SOCK set, and the key added with ssh-add?)"
+            )
+        
+        for key in keys:
+            try:
+                self._open(pkey=key)
+                logger.info(f"Successfully connected to {self.username}@{self.host}:{self.port} (agent key {key.fingerprint})")
+                return
+            except paramiko.AuthenticationException:
+                continue
+        
+        raise AuthFailed(
+            f"SSH agent authentication failed: none of the agent's {len(keys)} identities "
+            f"were accepted for {self.username}@{self.host}"
+        )
+    
+    def connect_with_retry(self, attempts: int, backoff_secs: float) -> None:
+        """
+        Connect, retrying transient network/handshake failures with exponential backoff.
+        
+        Auth and host key failures are never retried: they won't succeed on retry and
+        repeated bad logins can lock the account.
+        """
+        for attempt in range(1, max(attempts, 1) + 1):
+            try:
+                self.connect()
+                return
+            except TransientConnectionError as e:
+                if attempt >= attempts:
+                    raise
+                delay = backoff_secs * 2 ** (attempt - 1)
+                logger.warning("Connection attempt %s/%s to %s:%s failed (%s), retrying in %ss", attempt, attempts, self.host, self.port, e, delay)
+                time.sleep(delay)
+    
+    def _existing_key_paths(self) -> List[str]:
+        """Configured key files that exist on disk, in the order they should be tried"""
+        return [p for p in self.key_paths if os.path.exists(p)]
+    
+    def _load_key(self, key_path: str, failures: List[str]) -> Optional[paramiko.PKey]:
+        """Load a private key, decrypting it with the configured passphrase if needed"""
+        try:
+            # paramiko supports OpenSSH format including ED25519
+            return paramiko.PKey.from_path(key_path, passphrase=self.ssh_key_passphrase)
+        except paramiko.PasswordRequiredException:
+            failures.append(f"key {key_path}: key is encrypted and no passphrase is configured")
+        except (paramiko.SSHException, ValueError) as e:
+            if self.ssh_key_passphrase:
+                failures.append(f"key {key_path}: key passphrase was rejected ({e})")
+            else:
+                failures.append(f"key {key_path}: could not load key ({e})")
+        return None
+    
+    def _route(self) -> str:
+        """Describe how the target is reached, for error messages"""
+        if self.proxy_command:
+            return f" (via proxy command '{self._expand_proxy_command()}')"
+        return f" (via jump host {self.jump_host.host}:{self.jump_host.port})" if self.jump_host else ""
+    
+    def _expand_proxy_command(self) -> str:
+        """proxy_command with OpenSSH's %h, %p, %r and %% tokens substituted"""
+        tokens = {'h': self.host, 'p': str(self.port), 'r': self.username, '%': '%'}
+        return re.sub(r'%([hpr%])', lambda m: tokens[m.group(1)], self.proxy_command)
+    
+    def _open_proxy(self) -> paramiko.ProxyCommand:
+        """
+        Spawn proxy_command and use its stdin/stdout as the connection.
+        
+        The command runs without a terminal, so proxies must already be logged in, and
+        connect_timeout only covers the SSH handshake, not whatever the proxy does first.
+        """
+        command = self._expand_proxy_command()
+        try:
+            return paramiko.ProxyCommand(command)
+        except Exception as e:
+            raise ConnectionFailed(f"Could not start proxy command '{command}': {e}")
+    
+    def _connect_jump_host(self) -> None:
+        """Connect to the bastion, naming it in any error so the failing hop is clear"""
+        try:
+            self.jump_host.connect()
+        except TransientConnectionError as e:
+            raise TransientConnectionError(f"Jump host {self.jump_host.host}:{self.jump_host.port} failed: {e}")
+        except Exception as e:
+            raise command_error(e, f"Jump host {self.jump_host.host}:{self.jump_host.port} failed")
+    
+    def _open_socket(self):
+        """
+        Raw connection to the target for bare-transport operations (proxy command, jump host
+        tunnel or TCP). A jump host is left connected; callers disconnect it when done.
+        """
+        if self.proxy_command:
+            return self._open_proxy()
+        if self.jump_host:
+            self._connect_jump_host()
+            return self._open_jump_channel()
+        return socket.create_connection((self.host, self.port), timeout=self.connect_timeout)
+    
+    def _open_jump_channel(self) -> paramiko.Channel:
+        """Open a direct-tcpip channel from the bastion to the target server"""
+        try:
+            return self.jump_host.client.get_transport().open_channel(
+                'direct-tcpip', (self.host, self.port), ('127.0.0.1', 0), timeout=self.connect_timeout
+            )
+        except Exception as e:
+            raise ConnectionFailed(
+                f"Jump host {self.jump_host.host}:{self.jump_host.port} could not open a tunnel "
+                f"to {self.host}:{self.port}: {e}"
+            )
+    
+    def _open(self, pkey: Optional[paramiko.PKey] = None, password: Optional[str] = None) -> None:
+        """Open a fresh connection using exactly one authentication method"""
+        self._close_target()
+        # Each attempt needs its own tunnel since a failed connect closes the socket
+        if self.proxy_command:
+            sock = self._open_proxy()
+        else:
+            sock = self._open_jump_channel() if self.jump_host else None
+        self.client = paramiko.SSHClient()
+        if self.strict_host_key_checking:
+            if os.path.exists(self.known_hosts_path):
+                self.client.load_host_keys(self.known_hosts_path)
+            self.client.set_missing_host_key_policy(_RejectUnknownHostKeyPolicy(self.known_hosts_path))
+        else:
+            self.client.set_missing_host_key_policy(paramiko.AutoAddPolicy())
+        try:
             self.client.connect(
                 hostname=self.host,
                 port=self.port,
                 username=self.username,
-                key_filename=self.ssh_key_path,
-                timeout=10,
-                look_for_keys=False,  # Only use the specified key
-                allow_agent=False
+                pkey=pkey,
+                password=password,
+                timeout=self.connect_timeout,  # TCP connect
+                banner_timeout=self.connect_timeout,  # SSH banner/handshake
+                auth_timeout=self.connect_timeout,
+                look_for_keys=False,  # Only use the configured credentials
+                allow_agent=False,
+                sock=sock,
+                transport_factory=self._new_transport
             )
-            print(f"Successfully connected to {self.username}@{self.host}:{self.port}")
-            
-        except paramiko.AuthenticationException as e:
-            raise Exception(f"SSH authentication failed: {e}\nUsername: {self.username}\nKey: {self.ssh_key_path}")
-        except paramiko.SSHException as e:
-            raise Exception(f"SSH connection failed: {e}")
-        except Exception as e:
-            raise Exception(f"Failed to connect to {self.host}:{self.port}: {e}")
+        except Exception:
+            self._close_target()
+            raise
     
-    def execute_command(self, cmd: str) -> str:
-        """Execute a command on the remote server"""
+    def _new_transport(self, sock, **kwargs) -> paramiko.Transport:
+        """Transport over sock with any algorithm overrides applied before the handshake"""
+        transport = paramiko.Transport(sock, **kwargs)
+        options = transport.get_security_options()
+        if self.kex_algorithms:
+            options.kex = self.kex_algorithms
+        if self.host_key_algorithms:
+            options.key_types = self.host_key_algorithms
+        if self.ciphers:
+            options.ciphers = self.ciphers
+        return transport
+    
+    def _known_hosts_entry_name(self) -> str:
+        """Host name as written in known_hosts ([host]:port for non-default ports)"""
+        return self.host if self.port == 22 else f"[{self.host}]:{self.port}"
+    
+    def fetch_host_key(self) -> paramiko.PKey:
+        """Perform a handshake only (no auth) and return the server's host key"""
+        try:
+            sock = self._open_socket()
+            transport = self._new_transport(sock)
+            try:
+                transport.start_client(timeout=self.connect_timeout)
+                return transport.get_remote_server_key()
+            finally:
+                transport.close()
+        finally:
+            if self.jump_host:
+                self.jump_host.disconnect()
+    
+    def test_auth(self) -> AuthTiming:
+        """
+        Connect, authenticate and run echo on a bare transport, timing each step.
+        
+        Only needs host, port, username and credentials, so it works before any docker
+        or service settings are filled in.
+        """
+        start = time.monotonic()
+        try:
+            # Through a jump host this includes connecting to the bastion
+            sock = self._open_socket()
+            connected = time.monotonic()
+            transport = self._new_transport(sock)
+            try:
+                transport.start_client(timeout=self.connect_timeout)
+                handshaken = time.monotonic()
+                self._verify_host_key(transport.get_remote_server_key())
+                
+                method = self._authenticate_transport(transport)
+                authenticated = time.monotonic()
+                
+                channel = transport.open_session(timeout=self.connect_timeout)
+                channel.settimeout(self.command_timeout)
+                channel.exec_command("echo ok")
+                output = channel.makefile('r').read().decode('utf-8').strip()
+                if channel.recv_exit_status() != 0 or output != "ok":
+                    raise Exception(f"Authenticated, but running echo failed (output: {output!r})")
+                finished = time.monotonic()
+            finally:
+                transport.close()
+        finally:
+            if self.jump_host:
+                self.jump_host.disconnect()
+        
+        return AuthTiming(
+            auth_method=method,
+            connect_ms=round((connected - start) * 1000),
+            handshake_ms=round((handshaken - connected) * 1000),
+            auth_ms=round((authenticated - handshaken) * 1000),
+            command_ms=round((finished - authenticated) * 1000)
+        )
+    
+    def diagnose(self) -> ConnectionDiagnosis:
+        """
+        Resolve, connect, handshake, check the host key and authenticate, one stage at a time.
+        
+        Never raises: a failing stage is recorded with its error and the later stages are skipped.
+        """
+        diagnosis = ConnectionDiagnosis()
+        
+        def stage(name: str, step: Callable[[], Tuple[Any, str]]) -> Any:
+            if diagnosis.failed_stage:
+                diagnosis.stages.append(DiagnosticStage(name, "skipped", detail=f"Not run because {diagnosis.failed_stage} failed"))
+                return None
+            start = time.monotonic()
+            try:
+                result, detail = step()
+                status = "ok"
+            except Exception as e:
+                result, detail, status = None, str(e) or type(e).__name__, "failed"
+                diagnosis.failed_stage = name
+            diagnosis.stages.append(DiagnosticStage(name, status, round((time.monotonic() - start) * 1000), detail))
+            return result
+        
+        def resolve():
+            host, port = (self.jump_host.host, self.jump_host.port) if self.jump_host else (self.host, self.port)
+            addresses = {info[4][0] for info in socket.getaddrinfo(host, port, type=socket.SOCK_STREAM)}
+            return None, f"{host} resolved to {', '.join(sorted(addresses))}"
+        
+        def tcp():
+            if self.proxy_command:
+                return self._open_proxy(), f"Started proxy command '{self._expand_proxy_command()}'"
+            if self.jump_host:
+                self._connect_jump_host()
+                return self._open_jump_channel(), f"Tunnelled from jump host {self.jump_host.host}:{self.jump_host.port}"
+            return socket.create_connection((self.host, self.port), timeout=self.connect_timeout), f"Connected to port {self.port}"
+        
+        def handshake():
+            transport = self._new_transport(sock)
+            try:
+                transport.start_client(timeout=self.connect_timeout)
+            except Exception:
+                transport.close()
+                raise
+            diagnosis.server_version = transport.remote_version
+            return transport, f"Server version {transport.remote_version}"
+        
+        def host_key():
+            key = transport.get_remote_server_key()
+            self._verify_host_key(key)
+            checked = "" if self.strict_host_key_checking else " (not checked: strict host key checking is off)"
+            return None, f"{key.get_name()} {key.fingerprint}{checked}"
+        
+        def auth():
+            try:
+                method = self._authenticate_transport(transport)
+            finally:
+                diagnosis.auth_banner = decode_auth_banner(transport.get_banner())
+            return None, f"Authenticated via {method}"
+        
+        sock = transport = None
+        try:
+            if self.proxy_command:
+                diagnosis.stages.append(DiagnosticStage("resolve", "skipped", detail="The proxy command resolves the host"))
+            else:
+                stage("resolve", resolve)
+            sock = stage("tcp", tcp)
+            transport = stage("handshake", handshake)
+            stage("host_key", host_key)
+            stage("auth", auth)
+        finally:
+            if transport:
+                transport.close()
+            elif sock:
+                sock.close()
+            if self.jump_host:
+                self.jump_host.disconnect()
+        return diagnosis
+    
+    def _verify_host_key(self, key: paramiko.PKey) -> None:
+        """Check a host key against known_hosts, as connect() does under strict checking"""
+        if not self.strict_host_key_checking:
+            return
+        entry_name = self._known_hosts_entry_name()
+        host_keys = paramiko.HostKeys()
+        if os.path.exists(self.known_hosts_path):
+            host_keys.load(self.known_hosts_path)
+        
+        existing = host_keys.lookup(entry_name)
+        if not existing or key.get_name() not in existing:
+            raise UnknownHostKeyError(entry_name, key, self.known_hosts_path)
+        if existing[key.get_name()] != key:
+            raise HostKeyRejected(
+                f"Host key verification failed for {self.host}:{self.port}: the key offered by the server "
+                f"({key.get_name()} {key.fingerprint}) does not match the one in {self.known_hosts_path}."
+            )
+    
+    def _authenticate_transport(self, transport: paramiko.Transport) -> str:
+        """Authenticate a bare transport with the configured method, returning the one that worked"""
+        failures = []
+        
+        if self.auth_method == AuthMethod.AGENT:
+            for key in paramiko.Agent().get_keys():
+                try:
+                    transport.auth_publickey(self.username, key)
+                    return "agent"
+                except paramiko.AuthenticationException as e:
+                    failures.append(f"agent key {key.fingerprint}: {e}")
+            if not failures:
+                failures.append("agent: the agent has no identities")
+        
+        if self.auth_method == AuthMethod.KEY:
+            for key_path in self._existing_key_paths():
+                pkey = self._load_key(key_path, failures)
+                if pkey:
+                    try:
+                        transport.auth_publickey(self.username, pkey)
+                        return "key"
+                    except paramiko.AuthenticationException as e:
+                        failures.append(f"key {key_path}: {e}")
+        
+        if self.auth_method != AuthMethod.AGENT and self.password:
+            try:
+                transport.auth_password(self.username, self.password)
+                return "password"
+            except paramiko.AuthenticationException as e:
+                failures.append(f"password: {e}")
+        
+        if not failures:
+            failures.append("no SSH key found and no password set")
+        raise AuthFailed(
+            f"SSH authentication failed for {self.username}@{self.host}:{self.port}\n"
+            + "\n".join(f"  - {f}" for f in failures)
+        )
+    
+    def trust_host_key(self) -> str:
+        """Append the server's current host key to known_hosts and return its fingerprint"""
+        key = self.fetch_host_key()
+        entry_name = self._known_hosts_entry_name()
+        
+        host_keys = paramiko.HostKeys()
+        if os.path.exists(self.known_hosts_path):
+            host_keys.load(self.known_hosts_path)
+        
+        existing = host_keys.lookup(entry_name)
+        if existing and key.get_name() in existing:
+            if existing[key.get_name()] == key:
+                return key.fingerprint  # Already trusted
+            raise Exception(
+                f"{self.known_hosts_path} already contains a different {key.get_name()} key for {entry_name}. "
+                f"Refusing to overwrite it; remove the old entry manually if the server was reinstalled."
+            )
+        
+        os.makedirs(os.path.dirname(self.known_hosts_path), exist_ok=True)
+        with open(self.known_hosts_path, 'a') as f:
+            f.write(f"{entry_name} {key.get_name()} {key.get_base64()}\n")
+        logger.info(f"Added host key for {entry_name} to {self.known_hosts_path}")
+        return key.fingerprint
+    
+    @contextmanager
+    def _channel_slot(self) -> Iterator[None]:
+        """
+        Hold one of the connection's channel slots while a command runs.
+        
+        paramiko multiplexes channels over the transport safely, so concurrent commands
+        (e.g. a log stream and a status poll) each get their own channel and only queue
+        here once MAX_CONCURRENT_CHANNELS are already open.
+        """
+        if not self._channel_slots.acquire(timeout=self.command_timeout):
+            raise Timeout(f"Timed out waiting for a free SSH channel on {self.host} "
+                            f"({MAX_CONCURRENT_CHANNELS} commands already running)")
+        try:
+            yield
+        finally:
+            self._channel_slots.release()
+    
+    def run_command(self, cmd: str) -> CommandResult:
+        """Execute a command on the remote server, returning its output and exit code without raising on failure"""
+        if self.dry_run:
+            return self._record_dry_run(cmd)
+        
         if not self.client:
-            raise Exception("Not connected to SSH server")
+            raise ConnectionFailed("Not connected to SSH server")
         
         try:
-            stdin, stdout, stderr = self.client.exec_command(cmd, timeout=30)
-            exit_status = stdout.channel.recv_exit_status()
-            
-            output = stdout.read().decode('utf-8')
-            error = stderr.read().decode('utf-8')
-            
-            if exit_status != 0:
-                raise Exception(f"Command failed with exit status {exit_status}: {error or output}")
-            
-            return output
+            with self._channel_slot():
+                stdin, stdout, stderr = self.client.exec_command(cmd, timeout=self.command_timeout)
+                exit_status = stdout.channel.recv_exit_status()
+                
+                return CommandResult(
+                    stdout=stdout.read().decode('utf-8'),
+                    stderr=stderr.read().decode('utf-8'),
+                    exit_code=exit_status
+                )
         except Exception as e:
-            raise Exception(f"Failed to execute command '{cmd}': {e}")
+            raise command_error(e, f"Failed to execute command '{cmd}'")
+    
+    def _record_dry_run(self, cmd: str) -> CommandResult:
+        """Record a command that would have run, echoing it back as its output"""
+        logger.debug(f"[dry run] {cmd}")
+        self.dry_run_commands.append(cmd)
+        return CommandResult(stdout=cmd, stderr="", exit_code=0)
+    
+    def execute_command(self, cmd: str) -> str:
+        """Execute a command on the remote server, raising if it exits non-zero"""
+        return self.run_command(cmd).check(cmd)
+    
+    def execute_command_with_timeout(self, cmd: str, timeout: Optional[float],
+                                     cancel_event: Optional[threading.Event] = None) -> str:
+        """
+        Execute a command, giving up after timeout seconds of wall-clock time (None for no limit).
+        
+        Raises CommandTimedOut or CommandCancelled (when cancel_event is set). Either way the
+        channel is closed, which hangs up the remote command.
+        """
+        if self.dry_run:
+            return self._record_dry_run(cmd).stdout
+        
+        if not self.client:
+            raise ConnectionFailed("Not connected to SSH server")
+        
+        with self._channel_slot():
+            channel = self.client.get_transport().open_session()
+            try:
+                channel.exec_command(cmd)
+                deadline = time.monotonic() + timeout if timeout is not None else None
+                output, error = [], []
+                
+                # Drain output while polling so a chatty command can't fill the window and stall
+                while True:
+                    while channel.recv_ready():
+                        output.append(channel.recv(4096))
+                    while channel.recv_stderr_ready():
+                        error.append(channel.recv_stderr(4096))
+                    if channel.exit_status_ready() and not channel.recv_ready() and not channel.recv_stderr_ready():
+                        break
+                    if cancel_event is not None and cancel_event.is_set():
+                        raise CommandCancelled(cmd)
+                    if deadline is not None and time.monotonic() > deadline:
+                        raise CommandTimedOut(cmd, timeout)
+                    time.sleep(COMMAND_POLL_INTERVAL)
+                
+                exit_status = channel.recv_exit_status()
+            finally:
+                channel.close()
+        
+        return CommandResult(
+            stdout=b"".join(output).decode('utf-8', errors='replace'),
+            stderr=b"".join(error).decode('utf-8', errors='replace'),
+            exit_code=exit_status
+        ).check(cmd)
+    
+    def get_session_info(self) -> SessionInfo:
+        """The connected server's identification string and auth banner"""
+        if not self.client:
+            raise ConnectionFailed("Not connected to SSH server")
+        transport = self.client.get_transport()
+        return SessionInfo(server_version=transport.remote_version, auth_banner=decode_auth_banner(transport.get_banner()))
+    
+    def check_prerequisites(self) -> Prerequisites:
+        """Check that docker and docker compose are available on the server"""
+        docker = self.run_command(f"{self.docker_command} --version")
+        compose = self.run_command(f"{self._compose_cmd()} version")
+        # Otherwise a sudo that wants a password would look like docker isn't installed
+        sudo_error = sudo_password_error(docker.stderr + docker.stdout)
+        if sudo_error:
+            raise sudo_error
+        return Prerequisites(
+            docker_installed=docker.exit_code == 0,
+            docker_version=docker.stdout.strip() if docker.exit_code == 0 else "",
+            compose_installed=compose.exit_code == 0,
+            compose_version=compose.stdout.strip() if compose.exit_code == 0 else "",
+            compose_command=self.compose_command
+        )
+    
+    def _compose_cmd(self) -> str:
+        """The docker compose invocation, including any custom compose file and profiles"""
+        cmd = f"{SUDO_PREFIX}{self.compose_command}" if self.use_sudo else self.compose_command
+        if self.compose_file:
+            cmd += f" -f {shlex.quote(self.compose_file)}"
+        for profile in self.compose_profiles:
+            cmd += f" --profile {shlex.quote(profile)}"
+        return cmd
     
     def check_containers(self) -> List[ContainerStatus]:
         """Check Docker containers status (uses portal_path for backward compatibility)"""
@@ -94,52 +1296,100 @@ class SSHClient:
         # Combine path and compose file checks into one command for speed
         try:
             # Single combined check: path exists AND compose file exists
-            combined_check = f"test -d {path} && (test -f {path}/docker-compose.yml || test -f {path}/docker-compose.yaml) && echo 'ok' || echo 'fail'"
+            if self.compose_file:
+                file_check = f"test -f {shlex.quote(self.compose_file)}"
+            else:
+                file_check = "(test -f docker-compose.yml || test -f docker-compose.yaml)"
+            combined_check = f"test -d {quote_path(path)} && cd {quote_path(path)} && {file_check} && echo 'ok' || echo 'fail'"
             check_result = self.execute_command(combined_check).strip()
             
             if check_result != 'ok':
-                print(f"Warning: Path does not exist or no docker-compose file found at {path}")
+                logger.warning("No docker compose file found at %s on %s (or the path does not exist)", path, self.host)
                 return []
         except Exception as e:
-            print(f"Warning: Could not verify path {path}: {e}")
+            logger.warning("Could not verify path %s on %s: %s", path, self.host, e)
             # Continue anyway - docker compose will fail with a clearer error
         
         # Try to get containers
         try:
-            cmd = f"cd {path} && docker compose ps --format json 2>&1"
+            cmd = f"cd {quote_path(path)} && {self._compose_cmd()} ps --format json 2>&1"
             output = self.execute_command(cmd)
-            
-            containers = []
-            for line in output.strip().split('\n'):
-                if not line or line.strip() == '':
-                    continue
-                try:
-                    container = json.loads(line)
-                    containers.append(ContainerStatus(
-                        name=container.get('Name', ''),
-                        status=container.get('Status', ''),
-                        state=container.get('State', '')
-                    ))
-                except json.JSONDecodeError:
-                    # Skip non-JSON lines (like error messages)
-                    continue
-            
-            return containers
+            return parse_compose_ps(output)
         except Exception as e:
             error_msg = str(e)
             # Check if it's a "no configuration file" error
             if 'no configuration file' in error_msg.lower() or 'not found' in error_msg.lower():
-                print(f"Error: No docker-compose.yml found at {path}")
-                print(f"This usually means:")
-                print(f"  1. The path '{path}' is incorrect")
-                print(f"  2. The docker-compose.yml file doesn't exist at that location")
-                print(f"  3. The file might be named differently (docker-compose.yaml)")
-                print(f"Please check the path in Settings and ensure docker-compose.yml exists.")
+                logger.error("No docker-compose.yml found at %s on %s. Check the path in Settings: it may be wrong, "
+                             "or the file may be missing or named differently (docker-compose.yaml)", path, self.host)
                 # Return empty list instead of failing - allows graceful handling
                 return []
             # For other errors, re-raise
             raise
     
+    def check_docker_daemon(self) -> Tuple[bool, str]:
+        """Whether the docker daemon answers, with its version or why it doesn't (e.g. not running)"""
+        result = self.run_command(f"{self.docker_command} info --format '{{{{.ServerVersion}}}}'")
+        if result.exit_code == 0:
+            return True, f"Docker {result.stdout.strip()}"
+        output = (result.stderr or result.stdout).strip()
+        sudo_error = sudo_password_error(output)
+        return False, str(sudo_error) if sudo_error else output
+    
+    def inspect_project(self, path: str) -> ComposeProject:
+        """
+        Containers of the compose project at a path, including stopped ones, and whether it
+        exists, has never been brought up, or couldn't be checked because compose failed
+        """
+        if self.compose_file:
+            file_check = f"test -f {shlex.quote(self.compose_file)}"
+        else:
+            file_check = "(test -f docker-compose.yml || test -f docker-compose.yaml)"
+        if self.run_command(f"test -d {quote_path(path)} && cd {quote_path(path)} && {file_check}").exit_code != 0:
+            return ComposeProject(ProjectState.MISSING, detail=f"No compose file found at {path}")
+        
+        result = self.run_command(f"cd {quote_path(path)} && {self._compose_cmd()} ps -a --format json")
+        if result.exit_code != 0:
+            return ComposeProject(ProjectState.UNAVAILABLE, detail=(result.stderr or result.stdout).strip())
+        containers = parse_compose_ps(result.stdout)
+        return ComposeProject(project_state(result.exit_code, containers), containers)
+    
+    def check_container_stats(self) -> List[ContainerStats]:
+        """Get CPU and memory usage for all running containers on the server"""
+        output = self.execute_command(f"{self.docker_command} stats --no-stream --format '{{{{json .}}}}'")
+        
+        stats = []
+        for line in output.strip().split('\n'):
+            if not line.strip():
+                continue
+            try:
+                entry = json.loads(line)
+            except json.JSONDecodeError:
+                continue
+            
+            # MemUsage looks like "1.5GiB / 7.6GiB"
+            usage, _, limit = entry.get('MemUsage', '').partition('/')
+            stats.append(ContainerStats(
+                name=entry.get('Name', ''),
+                cpu_percent=parse_percent(entry.get('CPUPerc', '')),
+                memory_usage_bytes=parse_size(usage),
+                memory_limit_bytes=parse_size(limit),
+                memory_percent=parse_percent(entry.get('MemPerc', ''))
+            ))
+        
+        return stats
+    
+    def get_disk_usage(self) -> DockerDiskUsage:
+        """Space used by images, containers, volumes and build cache (docker system df)"""
+        output = self.execute_command(f"{self.docker_command} system df --format '{{{{json .}}}}'")
+        return parse_disk_usage(output)
+    
+    def prune(self, what: str) -> PruneResult:
+        """Run the PRUNE_COMMANDS cleanup for what, reporting the space reclaimed"""
+        if what not in PRUNE_COMMANDS:
+            raise InvalidConfig(f"Unknown prune target '{what}' (expected one of {', '.join(PRUNE_COMMANDS)})")
+        output = self.execute_command(f"{self.docker_command} {PRUNE_COMMANDS[what]}")
+        return PruneResult(what=what, reclaimed_bytes=parse_reclaimed_space(output), output=output)
+    
     def start_portal(self) -> None:
         """Start the portal Docker containers (deprecated, use start_service)"""
         self.start_service(self.portal_path, None)
@@ -148,64 +1398,392 @@ class SSHClient:
         """Stop the portal Docker containers (deprecated, use stop_service)"""
         self.stop_service(self.portal_path, None)
     
-    def start_service(self, path: str, service_name: Optional[str] = None, pre_launch_command: Optional[str] = None) -> None:
-        """Start a Docker service at a specific path"""
+    def start_service(self, path: str, service_name: Optional[str] = None, pre_launch_command: Optional[str] = None,
+                      cancel_event: Optional[threading.Event] = None) -> None:
+        """Start a Docker service at a specific path (raises CommandCancelled if cancel_event is set)"""
+        def run(cmd: str):
+            if cancel_event is None:
+                self.execute_command(cmd)
+            else:
+                # No timeout: pulls during 'up' can legitimately take minutes
+                self.execute_command_with_timeout(cmd, None, cancel_event)
+        
         # Run pre-launch command if specified (e.g., for setup, env file creation)
         if pre_launch_command:
-            print(f"Running pre-launch command: {pre_launch_command}")
-            pre_cmd = f"cd {path} && {pre_launch_command}"
-            self.execute_command(pre_cmd)
+            logger.info(f"Running pre-launch command: {pre_launch_command}")
+            pre_cmd = f"cd {quote_path(path)} && {pre_launch_command}"
+            run(pre_cmd)
         
         # Start the service
         if service_name:
-            cmd = f"cd {path} && docker compose up -d {service_name}"
+            cmd = f"cd {quote_path(path)} && {self._compose_cmd()} up -d {shlex.quote(service_name)}"
         else:
-            cmd = f"cd {path} && docker compose up -d"
+            cmd = f"cd {quote_path(path)} && {self._compose_cmd()} up -d"
+        try:
+            run(cmd)
+        except CommandFailed as e:
+            conflict = self._port_conflict_error(str(e))
+            if conflict:
+                raise conflict from e
+            raise
+    
+    def _port_conflict_error(self, output: str) -> Optional[PortInUse]:
+        """A PortInUse naming the port and, where ss can see it, the process holding it"""
+        port = parse_port_conflict(output)
+        if port is None:
+            return None
+        # Without root ss can't name other users' processes (docker-proxy runs as root), but still lists the socket
+        holder = self.run_command(f"ss -ltnp 2>/dev/null | grep -E ':{port}[[:space:]]'").stdout.strip()
+        message = f"Port {port} is already in use on {self.host}"
+        message += f", held by:\n{holder}" if holder else ""
+        return PortInUse(f"{message}\nStop whatever is using it, or change the service's port mapping")
+    
+    def stop_service(self, path: str, service_name: Optional[str] = None) -> Optional[DownResult]:
+        """Stop one compose service, or bring the whole project down if service_name is None"""
+        if not service_name:
+            return self.down_services(path)
+        cmd = f"cd {quote_path(path)} && {self._compose_cmd()} stop {shlex.quote(service_name)}"
         self.execute_command(cmd)
+        return None
     
-    def stop_service(self, path: str, service_name: Optional[str] = None) -> None:
-        """Stop a Docker service at a specific path"""
-        if service_name:
-            cmd = f"cd {path} && docker compose stop {service_name}"
+    def down_services(self, path: str, remove_orphans: bool = False, remove_volumes: bool = False) -> DownResult:
+        """
+        Run "docker compose down" at a path and report what it stopped and removed.
+        
+        If down fails part way, the error lists what had already been removed.
+        """
+        flags = ""
+        if remove_orphans:
+            flags += " --remove-orphans"
+        if remove_volumes:
+            flags += " --volumes"
+        cmd = f"cd {quote_path(path)} && {self._compose_cmd()} down{flags}"
+        result = self.run_command(cmd)
+        # Compose writes its progress to stderr
+        down = parse_compose_down(result.stdout + "\n" + result.stderr)
+        if result.exit_code != 0:
+            removed = ', '.join(down.removed) or 'nothing'
+            raise CommandFailed(f"docker compose down exited with status {result.exit_code} "
+                            f"(removed so far: {removed}): {result.stderr.strip() or result.stdout.strip()}")
+        return down
+    
+    def restart_service(self, path: str, service_name: Optional[str] = None, hard: bool = False) -> None:
+        """Restart a Docker service at a specific path (hard recreates containers via down + up)"""
+        target = f" {shlex.quote(service_name)}" if service_name else ""
+        if hard:
+            if service_name:
+                cmd = f"cd {quote_path(path)} && {self._compose_cmd()} rm -s -f{target} && {self._compose_cmd()} up -d{target}"
+            else:
+                cmd = f"cd {quote_path(path)} && {self._compose_cmd()} down && {self._compose_cmd()} up -d"
         else:
-            cmd = f"cd {path} && docker compose down"
+            cmd = f"cd {quote_path(path)} && {self._compose_cmd()} restart{target}"
+        self.execute_command(cmd)
+    
+    def list_compose_services(self, path: str) -> List[str]:
+        """Every service defined in the compose file at a path, whether or not it has a container"""
+        cmd = f"cd {quote_path(path)} && {self._compose_cmd()} config --services"
+        return [line.strip() for line in self.execute_command(cmd).splitlines() if line.strip()]
+    
+    def list_services(self, path: str) -> List[ComposeServiceState]:
+        """Every defined compose service at a path with its container state (NOT_CREATED if none)"""
+        return merge_service_states(self.list_compose_services(path), self.check_containers_at_path(path))
+    
+    def control_service(self, path: str, service_name: str, action: ServiceAction) -> None:
+        """Start, stop or restart a single compose service, validating that it exists first"""
+        known_services = self.list_compose_services(path)
+        if service_name not in known_services:
+            raise NotFound(
+                f"Service '{service_name}' not found at {path}. "
+                f"Known services: {', '.join(known_services) or '(none)'}"
+            )
+        
+        # "start" only starts existing containers, so use "up -d", which creates them if needed
+        verb = "up -d" if action == ServiceAction.START else action.value
+        cmd = f"cd {quote_path(path)} && {self._compose_cmd()} {verb} {shlex.quote(service_name)}"
         self.execute_command(cmd)
     
     def restart_container(self, path: str, container_name: str) -> None:
         """Restart a specific Docker container using docker restart (not docker compose)"""
         # Use 'docker restart' which takes container names, not 'docker compose restart' which takes service names
-        cmd = f"docker restart {container_name}"
+        cmd = f"{self.docker_command} restart {shlex.quote(container_name)}"
         self.execute_command(cmd)
     
-    def get_logs(self, service: Optional[str] = None, lines: int = 100) -> str:
-        """Get Docker container logs"""
-        if service:
-            cmd = f"cd {self.portal_path} && docker compose logs --tail {lines} {service}"
-        else:
-            cmd = f"cd {self.portal_path} && docker compose logs --tail {lines}"
-        return self.execute_command(cmd)
+    def upload_file(self, local_path: str, remote_path: str) -> int:
+        """Upload a file over SFTP (streamed in chunks), returning the bytes transferred"""
+        if not self.client:
+            raise ConnectionFailed("Not connected to SSH server")
+        
+        with self.client.open_sftp() as sftp:
+            attrs = sftp.put(os.path.expanduser(local_path), remote_path)
+        return attrs.st_size
+    
+    def download_file(self, remote_path: str, local_path: str) -> int:
+        """Download a file over SFTP (streamed in chunks), returning the bytes transferred"""
+        if not self.client:
+            raise ConnectionFailed("Not connected to SSH server")
+        
+        local_path = os.path.expanduser(local_path)
+        with self.client.open_sftp() as sftp:
+            sftp.get(remote_path, local_path)
+        return os.path.getsize(local_path)
     
-    def check_portal_health(self, port: int) -> bool:
-        """Check if the portal is responding (deprecated, use check_service_health)"""
-        return self.check_service_health(port, "/")
+    def read_text_file(self, remote_path: str) -> str:
+        """Read a small UTF-8 text file over SFTP, refusing large or binary files"""
+        if not self.client:
+            raise ConnectionFailed("Not connected to SSH server")
+        
+        path = sftp_path(remote_path)
+        with self.client.open_sftp() as sftp:
+            size = sftp.stat(path).st_size
+            if size > MAX_EDITABLE_FILE_BYTES:
+                raise InvalidConfig(f"{remote_path} is {size} bytes; only files up to {MAX_EDITABLE_FILE_BYTES} bytes can be edited")
+            with sftp.open(path, 'rb') as f:
+                data = f.read()
+        
+        if b'\0' in data:
+            raise InvalidConfig(f"{remote_path} looks like a binary file")
+        try:
+            return data.decode('utf-8')
+        except UnicodeDecodeError:
+            raise InvalidConfig(f"{remote_path} isn't UTF-8 text")
+    
+    def write_text_file(self, remote_path: str, contents: str) -> int:
+        """
+        Replace a text file over SFTP atomically: write a temporary file beside it, copy the
+        original's permissions, then rename it over the original. Returns the bytes written.
+        """
+        if not self.client:
+            raise ConnectionFailed("Not connected to SSH server")
+        
+        data = contents.encode('utf-8')
+        if len(data) > MAX_EDITABLE_FILE_BYTES:
+            raise InvalidConfig(f"Contents are {len(data)} bytes; only files up to {MAX_EDITABLE_FILE_BYTES} bytes can be edited")
+        
+        path = sftp_path(remote_path)
+        temp_path = f"{path}.orchestrator-tmp"
+        with self.client.open_sftp() as sftp:
+            try:
+                mode = sftp.stat(path).st_mode
+            except FileNotFoundError:
+                mode = None  # A new file gets the server's default permissions
+            try:
+                with sftp.open(temp_path, 'wb') as f:
+                    f.write(data)
+                if mode is not None:
+                    sftp.chmod(temp_path, mode & 0o7777)
+                # posix-rename@openssh.com replaces the target in one step, unlike plain SFTP rename
+                sftp.posix_rename(temp_path, path)
+            except Exception:
+                try:
+                    sftp.remove(temp_path)
+                except IOError:
+                    pass
+                raise
+        return len(data)
+    
+    def get_logs(self, service: Optional[str] = None, lines: int = 100,
+                 since: Optional[str] = None, grep: Optional[str] = None) -> str:
+        """Get Docker container logs (uses portal_path for backward compatibility)"""
+        return self.get_service_logs(self.portal_path, service, lines, since, grep)
+    
+    def get_service_logs(self, path: str, service_name: Optional[str] = None, lines: int = 100,
+                         since: Optional[str] = None, grep: Optional[str] = None) -> str:
+        """Get docker compose logs for a service at a specific path"""
+        return self.fetch_logs(path, service_name, lines, since, grep).output
+    
+    def fetch_logs(self, path: str, service_name: Optional[str] = None, lines: int = 100,
+                   since: Optional[str] = None, grep: Optional[str] = None) -> LogOutput:
+        """
+        Get the last `lines` lines of docker compose logs, filtered on the server so only
+        what's wanted crosses the connection.
+        
+        since is anything docker accepts (an RFC 3339 timestamp or a duration like 10m), and
+        grep a basic regular expression lines must match.
+        """
+        # pipefail so a failing compose (bad path, daemon down) isn't masked by the pipeline's tail
+        cmd = f"{PIPEFAIL_PREFIX}cd {quote_path(path)} && {self._compose_cmd()} logs"
+        if since:
+            cmd += f" --since {shlex.quote(since)}"
+        if not grep:
+            # Without a filter docker can apply the limit itself (per container, so tail again below)
+            cmd += f" --tail {lines + 1}"
+        if service_name:
+            cmd += f" {shlex.quote(service_name)}"
+        cmd += " 2>&1"
+        if grep:
+            # grep exits 1 when nothing matches, which is an empty result rather than an error
+            cmd += f" | {{ grep -e {shlex.quote(grep)} || [ $? -eq 1 ]; }}"
+        # One line more than asked for shows whether the limit cut anything off
+        cmd += f" | tail -n {lines + 1}"
+        return trim_log_tail(self.execute_command(cmd), lines)
+    
+    def stream_logs(self, path: str, service_name: Optional[str], on_line: Callable[[str], None],
+                    stop_event: threading.Event, lines: int = 100) -> None:
+        """Follow docker compose logs, calling on_line for each complete line until stopped"""
+        cmd = f"cd {quote_path(path)} && {self._compose_cmd()} logs -f --tail {lines}"
+        if service_name:
+            cmd += f" {shlex.quote(service_name)}"
+        
+        self.stream_command(cmd, on_line, stop_event)
+    
+    def stream_command(self, cmd: str, on_line: Callable[[str], None],
+                       stop_event: Optional[threading.Event] = None) -> int:
+        """Run a command, calling on_line for each line of combined output. Returns the exit status (-1 if stopped)."""
+        if self.dry_run:
+            on_line(self._record_dry_run(cmd).stdout)
+            return 0
+        
+        if not self.client:
+            raise ConnectionFailed("Not connected to SSH server")
+        
+        stop_event = stop_event or threading.Event()
+        with self._channel_slot():
+            channel = self.client.get_transport().open_session()
+            try:
+                channel.exec_command(f"{cmd} 2>&1")
+                # Short timeout so the stop event is noticed promptly
+                channel.settimeout(0.5)
+                
+                pending = b""
+                while not stop_event.is_set():
+                    try:
+                        data = channel.recv(4096)
+                    except socket.timeout:
+                        continue
+                    if not data:
+                        break  # Remote command exited
+                    
+                    # Hold back partial lines until their newline arrives
+                    pending += data
+                    *complete, pending = pending.split(b"\n")
+                    for line in complete:
+                        on_line(line.decode('utf-8', errors='replace').rstrip('\r'))
+                
+                if stop_event.is_set():
+                    return -1
+                if pending:
+                    on_line(pending.decode('utf-8', errors='replace').rstrip('\r'))
+                return channel.recv_exit_status()
+            finally:
+                channel.close()
+    
+    def get_image_ids(self, path: str) -> Dict[str, str]:
+        """Local image ID for each image referenced by a compose project ('' if not pulled yet)"""
+        cmd = (f"cd {quote_path(path)} && for img in $({self._compose_cmd()} config --images); do "
+               f"echo \"$img $({self.docker_command} image inspect --format '{{{{.Id}}}}' $img 2>/dev/null)\"; done")
+        return parse_image_ids(self.execute_command(cmd))
+    
+    def pull_images(self, path: str, on_line: Callable[[str], None]) -> PullResult:
+        """
+        Run docker compose pull, streaming its output to on_line.
+        
+        Compose's pull output doesn't reliably say whether an image changed, so image IDs
+        are compared before and after to tell updated images from ones already up to date.
+        """
+        before = self.get_image_ids(path)
+        output = []
+        
+        def collect(line: str):
+            output.append(line)
+            on_line(line)
+        
+        exit_status = self.stream_command(f"cd {quote_path(path)} && {self._compose_cmd()} pull", collect)
+        if exit_status != 0:
+            raise sudo_password_error("\n".join(output)) or CommandFailed(f"docker compose pull failed with exit status {exit_status}")
+        after = self.get_image_ids(path)
+        
+        result = PullResult()
+        for image, image_id in after.items():
+            if image_id and image_id == before.get(image):
+                result.up_to_date.append(image)
+            else:
+                result.updated.append(image)
+        return result
+    
+    def apply_updates(self, path: str, on_line: Callable[[str], None]) -> UpResult:
+        """
+        Run docker compose up -d, which recreates only services whose image or config changed.
+        
+        Output is streamed to on_line and parsed into what happened to each service (container
+        names are translated to their compose service where possible).
+        """
+        output = []
+        
+        def collect(line: str):
+            output.append(line)
+            on_line(line)
+        
+        exit_status = self.stream_command(f"cd {quote_path(path)} && {self._compose_cmd()} up -d", collect)
+        if exit_status != 0:
+            conflict = self._port_conflict_error("\n".join(output))
+            if conflict:
+                raise conflict
+            raise sudo_password_error("\n".join(output)) or CommandFailed(f"docker compose up failed with exit status {exit_status}")
+        
+        services = {c.name: c.service for c in self.check_containers_at_path(path) if c.service}
+        result = UpResult()
+        for container, action in parse_compose_up("\n".join(output)).items():
+            getattr(result, action).append(services.get(container, container))
+        return result
+    
+    def get_events(self, since_secs: int) -> List[ContainerEvent]:
+        """Container events from the last since_secs seconds (bounded with --until so the command returns)"""
+        cmd = (f"now=$(date +%s); {self.docker_command} events --since $((now - {int(since_secs)})) --until $now "
+               f"--filter type=container --format '{{{{json .}}}}'")
+        return parse_container_events(self.execute_command(cmd))
+    
+    def open_shell(self, cols: int = 80, rows: int = 24) -> paramiko.Channel:
+        """Open an interactive PTY-backed login shell"""
+        if not self.client:
+            raise ConnectionFailed("Not connected to SSH server")
+        
+        channel = self.client.get_transport().open_session()
+        channel.get_pty(term='xterm-256color', width=cols, height=rows)
+        channel.invoke_shell()
+        return channel
+    
+    def check_portal_health(self, port: int) -> ServiceHealth:
+        """Check whether the portal is ready, still starting, or unreachable (deprecated, use get_service_health)"""
+        return self.get_service_health(port, "/")
     
     def check_service_health(self, port: int, path: str = "/") -> bool:
         """Check if a service is responding at a specific port and path"""
+        return self.get_service_health(port, path).state == HealthState.READY
+    
+    def get_service_health(self, port: int, path: str = "/") -> ServiceHealth:
+        """Check whether a service is ready, still starting, or unreachable"""
+        return ServiceHealth.from_status_code(self.get_service_health_code(port, path), self.health_accepted_statuses)
+    
+    def health_url(self, port: int, path: str = "/") -> str:
+        """URL the health check requests, from the server's point of view"""
+        return f"{self.health_scheme}://{url_host(self.health_host)}:{port}{self.health_path or path}"
+    
+    def get_service_health_code(self, port: int, path: str = "/") -> int:
+        """Get the HTTP status code of a service's health endpoint (0 if unreachable)"""
         # Add --max-time 2 to limit health check to 2 seconds (prevents hanging)
-        cmd = f"curl -s -o /dev/null -w '%{{http_code}}' --max-time 2 http://localhost:{port}{path} || echo '000'"
+        # -k because internal https endpoints usually have self-signed certificates
+        url = shlex.quote(self.health_url(port, path))
+        cmd = f"curl -sk -o /dev/null -w '%{{http_code}}' --max-time 2 {url} || echo '000'"
         try:
             output = self.execute_command(cmd)
-            status_code = output.strip()
-            return status_code == "200"
+            # curl prints 000 itself on failure, so the fallback echo may double it
+            status_code = output.strip()[:3]
+            return int(status_code) if status_code.isdigit() else 0
         except:
-            return False
+            return 0
     
-    def disconnect(self) -> None:
-        """Close SSH connection"""
+    def _close_target(self) -> None:
+        """Close the connection to the target server only"""
         if self.client:
             self.client.close()
             self.client = None
     
+    def disconnect(self) -> None:
+        """Close SSH connection (and the jump host connection, if any)"""
+        self._close_target()
+        if self.jump_host:
+            self.jump_host.disconnect()
+    
     def __enter__(self):
         """Context manager entry"""
         self.connect()
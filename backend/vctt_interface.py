@@ -19,10 +19,13 @@ import subprocess
 import sys
 import os
 import json
+import logging
 import platform
 from pathlib import Path
 from typing import Optional, Tuple, Dict, Any, List
 
+logger = logging.getLogger(__name__)
+
 
 class VCTTInterface:
     """Interface for orchestrator to manage VCTT application."""
@@ -213,7 +216,7 @@ class VCTTInterface:
                     # Use call to properly handle batch files with spaces in paths
                     # subprocess.Popen with list format handles quoting automatically
                     cmd = ['cmd.exe', '/k', 'call', bootstrap_abs, install_dir_abs]
-                    print(f"[VCTT] Spawning terminal with command: cmd.exe /k call \"{bootstrap_abs}\" \"{install_dir_abs}\"")
+                    logger.debug(f"[VCTT] Spawning terminal with command: cmd.exe /k call \"{bootstrap_abs}\" \"{install_dir_abs}\"")
                     process = subprocess.Popen(
                         cmd,
                         cwd=work_dir_abs,
@@ -222,7 +225,7 @@ class VCTTInterface:
                     # Store process handle for tracking (use absolute path as key)
                     # The process handle is for cmd.exe which stays alive while terminal is open
                     VCTTInterface._bootstrap_processes[install_dir_abs] = process
-                    print(f"[VCTT] Bootstrap process started with PID: {process.pid}")
+                    logger.info(f"[VCTT] Bootstrap process started with PID: {process.pid}")
                     return 0, "Bootstrap installer started in new terminal window"
             else:
                 # Mac/Linux: run shell script
@@ -472,9 +475,9 @@ end tell
                         else:
                             self.launch_script = self.vctt_path / "launch_vctt.sh"
                         valid_path = True
-                        print(f"Found VCTT installation at: {best_path}")
+                        logger.info(f"Found VCTT installation at: {best_path}")
                 except Exception as e:
-                    print(f"Error searching for VCTT installations: {e}")
+                    logger.warning("Error searching for VCTT installations: %s", e)
             
             return {
                 "installed": installed,
@@ -494,9 +497,7 @@ end tell
                 }
             }
         except Exception as e:
-            print(f"Error in get_status: {e}")
-            import traceback
-            traceback.print_exc()
+            logger.exception("Error in get_status: %s", e)
             return {
                 "installed": False,
                 "configured": False,
@@ -0,0 +1,84 @@
+"""Remembered window positions and sizes, restored when a window is reopened"""
+import json
+import logging
+from dataclasses import asdict, dataclass
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+from config import AppConfig
+
+logger = logging.getLogger(__name__)
+
+# Pixels of a window that must be on a screen, both ways, for it to count as visible
+MIN_VISIBLE_PIXELS = 100
+
+
+@dataclass
+class WindowGeometry:
+    """Position and size of a window in screen coordinates"""
+    x: int
+    y: int
+    width: int
+    height: int
+
+
+def get_state_path() -> Path:
+    """File holding each window type's last geometry"""
+    return AppConfig.get_config_path().parent / 'windows.json'
+
+
+def _load_all() -> Dict[str, WindowGeometry]:
+    path = get_state_path()
+    if not path.exists():
+        return {}
+    try:
+        with open(path, 'r') as f:
+            return {window_type: WindowGeometry(**geometry) for window_type, geometry in json.load(f).items()}
+    except Exception as e:
+        logger.warning("Could not read window state: %s", e)
+        return {}
+
+
+def load(window_type: str) -> Optional[WindowGeometry]:
+    """Last saved geometry of a window type, if any"""
+    return _load_all().get(window_type)
+
+
+def save(window_type: str, geometry: WindowGeometry) -> None:
+    """Remember a window type's geometry for next time it opens"""
+    state = _load_all()
+    state[window_type] = geometry
+    try:
+        with open(get_state_path(), 'w') as f:
+            json.dump({name: asdict(g) for name, g in state.items()}, f, indent=2)
+    except Exception as e:
+        logger.warning("Could not save window state: %s", e)
+
+
+def clamp_to_screens(geometry: WindowGeometry, screens: List[Tuple[int, int, int, int]]) -> WindowGeometry:
+    """
+    Keep a window reachable: if too little of it is on any screen (e.g. its monitor was
+    unplugged), move it, shrinking if needed, onto the first (primary) screen.
+    
+    screens are (x, y, width, height) tuples; with none the geometry is returned unchanged.
+    """
+    if not screens:
+        return geometry
+    
+    for screen_x, screen_y, screen_width, screen_height in screens:
+        visible_width = min(geometry.x + geometry.width, screen_x + screen_width) - max(geometry.x, screen_x)
+        visible_height = min(geometry.y + geometry.height, screen_y + screen_height) - max(geometry.y, screen_y)
+        # The title bar must be on screen too, or the window can't be dragged back
+        title_bar_visible = screen_y <= geometry.y < screen_y + screen_height
+        if (visible_width >= min(MIN_VISIBLE_PIXELS, geometry.width)
+                and visible_height >= min(MIN_VISIBLE_PIXELS, geometry.height) and title_bar_visible):
+            return geometry
+    
+    screen_x, screen_y, screen_width, screen_height = screens[0]
+    width = min(geometry.width, screen_width)
+    height = min(geometry.height, screen_height)
+    return WindowGeometry(
+        x=min(max(geometry.x, screen_x), screen_x + screen_width - width),
+        y=min(max(geometry.y, screen_y), screen_y + screen_height - height),
+        width=width,
+        height=height
+    )
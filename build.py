@@ -22,6 +22,16 @@ def build():
         '--hidden-import=backend.config',
         '--hidden-import=backend.ssh_client',
         '--hidden-import=backend.process_manager',
+        '--hidden-import=backend.connection_pool',
+        '--hidden-import=backend.keychain',
+        '--hidden-import=backend.autostart',
+        '--hidden-import=backend.app_logging',
+        '--hidden-import=backend.errors',
+        '--hidden-import=backend.clipboard',
+        '--hidden-import=backend.window_state',
+        '--hidden-import=backend.local_api',
+        '--hidden-import=backend.last_status',
+        '--hidden-import=backend.maintenance',
         '--hidden-import=backend.api',
         '--clean',
     ]
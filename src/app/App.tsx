@@ -120,6 +120,12 @@ export default function App() {
       }
     }
 
+    // Offer the default server's instance first
+    const defaultServerId = config.preferences?.default_server_id;
+    if (defaultServerId) {
+      instances.sort((a, b) => Number(b.serverId === defaultServerId) - Number(a.serverId === defaultServerId));
+    }
+
     return instances;
   };
 
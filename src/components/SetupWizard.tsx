@@ -88,12 +88,15 @@ export default function SetupWizard({ onComplete, onSkip }: SetupWizardProps) {
         ssh_key_path: serverData.ssh_key_path,
       });
 
-      if (result.includes('Successfully connected')) {
+      if (!result.prerequisites.docker_installed) {
+        setConnectionStatus('error');
+        setConnectionMessage('SSH works, but docker is not installed on the server');
+      } else if (!result.prerequisites.compose_installed) {
+        setConnectionStatus('error');
+        setConnectionMessage('SSH works, but docker compose is not installed on the server');
+      } else {
         setConnectionStatus('success');
         setConnectionMessage('Connection successful!');
-      } else {
-        setConnectionStatus('error');
-        setConnectionMessage(result);
       }
     } catch (error: any) {
       setConnectionStatus('error');
@@ -137,7 +140,10 @@ export default function SetupWizard({ onComplete, onSkip }: SetupWizardProps) {
       });
 
       // Save config
-      await api.save_config(config);
+      const result = await api.save_config(config);
+      if (!result.saved) {
+        throw new Error(result.errors.map((e) => e.message).join('\n'));
+      }
 
       // Mark setup as completed
       await api.mark_setup_completed();
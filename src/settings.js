@@ -13,9 +13,10 @@ function waitForAPI() {
     });
 }
 
-// Generate unique ID
-function generateId() {
-    return 'id-' + Date.now() + '-' + Math.random().toString(36).substr(2, 9);
+// Generate unique ID (the backend picks it, so it can't collide with saved servers, services or apps)
+async function generateId(kind) {
+    await waitForAPI();
+    return window.pywebview.api.generate_id(kind);
 }
 
 // Load configuration
@@ -23,7 +24,6 @@ async function loadConfig() {
     try {
         await waitForAPI();
         config = await window.pywebview.api.load_config();
-        console.log("Loaded config:", config);
         
         // Ensure config has the right structure
         if (!config.servers) config.servers = [];
@@ -32,7 +32,7 @@ async function loadConfig() {
         // If no servers, add a default one
         if (config.servers.length === 0) {
             config.servers.push({
-                id: generateId(),
+                id: await generateId('server'),
                 name: "New Server",
                 host: "",
                 port: 22,
@@ -44,6 +44,14 @@ async function loadConfig() {
         
         renderServers();
         renderLocalApps();
+        document.getElementById('keep-warm').checked = !!config.preferences?.keep_warm;
+        document.getElementById('stop-portal-on-quit').checked = !!config.preferences?.stop_portal_on_quit;
+        document.getElementById('enable-local-api').checked = !!config.preferences?.enable_local_api;
+        document.getElementById('local-api-port').value = config.preferences?.local_api_port || '';
+        document.getElementById('health-monitor-interval').value = config.preferences?.health_monitor_interval_secs ?? 15;
+        document.getElementById('health-alert-cooldown').value = config.preferences?.health_alert_cooldown_secs ?? 300;
+        document.getElementById('max-log-size-mb').value = config.preferences?.max_log_size_mb ?? 10;
+        document.getElementById('max-log-age-days').value = config.preferences?.max_log_age_days ?? 30;
         
     } catch (error) {
         console.error("Failed to load config:", error);
@@ -68,6 +76,8 @@ function validateServers() {
         const port = server.port || 22;
         const username = (server.username || '').trim();
         const sshKey = (server.ssh_key_path || '').trim();
+        const password = server.password || '';
+        const authMethod = server.auth_method || 'key';
         
         // Check required fields
         if (!name) {
@@ -79,8 +89,10 @@ function validateServers() {
         if (!username) {
             errors.push(`Server ${serverNum}: Username is required`);
         }
-        if (!sshKey) {
-            errors.push(`Server ${serverNum}: SSH key path is required`);
+        if (authMethod === 'key' && !sshKey && !password) {
+            errors.push(`Server ${serverNum}: SSH key path or password is required`);
+        } else if (authMethod === 'password' && !password) {
+            errors.push(`Server ${serverNum}: Password is required for password authentication`);
         }
         
         // Check for duplicate names
@@ -119,33 +131,102 @@ function validateServers() {
 }
 
 // Save configuration
-async function saveConfig() {
+async function saveConfig(showSuccess = true) {
     try {
         await waitForAPI();
         
         // Collect data from UI
         collectServerData();
         collectLocalAppData();
+        collectPreferences();
         
         // Validate before saving
         const validation = validateServers();
         if (!validation.valid) {
             const errorMsg = "Configuration validation failed:\n\n" + validation.errors.join('\n');
             alert(errorMsg);
-            return;
+            return false;
         }
         
-        console.log("Saving config:", config);
-        await window.pywebview.api.save_config(config);
-        alert("Configuration saved successfully!");
+        const result = await window.pywebview.api.save_config(config);
+        if (!result.saved) {
+            showSaveErrors(result.errors);
+            return false;
+        }
+        if (!showSaveWarnings(result.warnings || []) && showSuccess) {
+            alert("Configuration saved successfully!");
+        }
+        return true;
         
     } catch (error) {
         console.error("Failed to save config:", error);
         const errorMsg = error.message || String(error);
         alert("Failed to save configuration: " + errorMsg);
+        return false;
     }
 }
 
+// Highlight fields rejected by backend validation
+const SERVER_FIELD_INPUTS = {
+    id: 'name',
+    name: 'name',
+    host: 'host',
+    port: 'port',
+    username: 'username',
+    ssh_key_path: 'ssh-key',
+    compose_command: 'compose-command'
+};
+
+// LocalAppConfig fields -> settings input id suffix (inputs are app-<index>-<suffix>)
+const APP_FIELD_INPUTS = {
+    executable_path: 'path',
+    working_directory: 'workdir',
+    depends_on: 'depends-on'
+};
+
+function showSaveErrors(errors) {
+    errors.forEach(error => {
+        if (error.app_id) {
+            const appIndex = config.local_apps.findIndex(a => a.id === error.app_id);
+            const appSuffix = APP_FIELD_INPUTS[error.field];
+            if (appIndex !== -1 && appSuffix) {
+                document.getElementById(`app-${appIndex}-${appSuffix}`)?.classList.add('error');
+            }
+            return;
+        }
+        const serverIndex = config.servers.findIndex(s => s.id === error.server_id);
+        const inputSuffix = SERVER_FIELD_INPUTS[error.field];
+        if (serverIndex === -1 || !inputSuffix) {
+            return;
+        }
+        const prefix = `server-${serverIndex}`;
+        document.getElementById(`${prefix}-${inputSuffix}`)?.classList.add('error');
+        const errorDiv = document.getElementById(`${prefix}-${inputSuffix}-error`);
+        if (errorDiv) {
+            errorDiv.textContent = error.message;
+            errorDiv.style.display = 'block';
+        }
+    });
+    alert("Configuration validation failed:\n\n" + errors.map(e => e.message).join('\n'));
+}
+
+// Flag local apps that saved but won't launch on this machine yet; returns whether there were any
+function showSaveWarnings(warnings) {
+    document.querySelectorAll('[id^="app-"].warning').forEach(input => input.classList.remove('warning'));
+    if (!warnings.length) {
+        return false;
+    }
+    warnings.forEach(warning => {
+        const appIndex = config.local_apps.findIndex(a => a.id === warning.app_id);
+        const appSuffix = APP_FIELD_INPUTS[warning.field];
+        if (appIndex !== -1 && appSuffix) {
+            document.getElementById(`app-${appIndex}-${appSuffix}`)?.classList.add('warning');
+        }
+    });
+    alert("Configuration saved, but some apps can't be launched on this machine:\n\n" + warnings.map(w => w.message).join('\n'));
+    return true;
+}
+
 // Collect server data from UI
 function collectServerData() {
     config.servers.forEach((server, serverIndex) => {
@@ -155,6 +236,11 @@ function collectServerData() {
         server.port = parseInt(document.getElementById(`${prefix}-port`)?.value) || 22;
         server.username = document.getElementById(`${prefix}-username`)?.value || "";
         server.ssh_key_path = document.getElementById(`${prefix}-ssh-key`)?.value || "";
+        server.ssh_key_passphrase = document.getElementById(`${prefix}-key-passphrase`)?.value || null;
+        server.password = document.getElementById(`${prefix}-password`)?.value || null;
+        server.auth_method = document.getElementById(`${prefix}-auth-method`)?.value || "key";
+        server.compose_command = document.getElementById(`${prefix}-compose-command`)?.value.trim() || "docker compose";
+        server.use_sudo = document.getElementById(`${prefix}-use-sudo`)?.checked || false;
         
         // Collect service data
         server.services.forEach((service, serviceIndex) => {
@@ -165,10 +251,47 @@ function collectServerData() {
             service.path = document.getElementById(`${sPrefix}-path`)?.value || "";
             service.healthcheck_path = document.getElementById(`${sPrefix}-health`)?.value || "/";
             service.pre_launch_command = document.getElementById(`${sPrefix}-prelaunch`)?.value || null;
+            service.scheme = document.getElementById(`${sPrefix}-scheme`)?.value || "http";
+            service.self_signed_cert = document.getElementById(`${sPrefix}-self-signed`)?.checked || false;
         });
     });
 }
 
+// Collect preferences edited on this page, keeping the rest as loaded
+function collectPreferences() {
+    config.preferences = {
+        ...(config.preferences || {}),
+        default_server_id: document.getElementById('default-server')?.value || null,
+        keep_warm: document.getElementById('keep-warm')?.checked || false,
+        stop_portal_on_quit: document.getElementById('stop-portal-on-quit')?.checked || false,
+        enable_local_api: document.getElementById('enable-local-api')?.checked || false,
+        local_api_port: parseInt(document.getElementById('local-api-port')?.value) || 0,
+        health_monitor_interval_secs: integerInput('health-monitor-interval', 15),
+        health_alert_cooldown_secs: integerInput('health-alert-cooldown', 300),
+        max_log_size_mb: integerInput('max-log-size-mb', 10),
+        max_log_age_days: integerInput('max-log-age-days', 30)
+    };
+}
+
+// Whole number in an input, or the fallback if it's blank (unlike `|| fallback`, keeps an explicit 0)
+function integerInput(id, fallback) {
+    const value = parseInt(document.getElementById(id)?.value);
+    return Number.isNaN(value) ? fallback : Math.max(0, value);
+}
+
+// Fill the default server dropdown (the first server is used when none is chosen)
+function renderDefaultServerSelect() {
+    const select = document.getElementById('default-server');
+    if (!select) {
+        return;
+    }
+    // Keep an unsaved choice across re-renders
+    const selected = select.value || config.preferences?.default_server_id || '';
+    select.innerHTML = '<option value="">First server</option>' + config.servers.map(server =>
+        `<option value="${server.id}" ${server.id === selected ? 'selected' : ''}>${server.name || server.host || server.id}</option>`
+    ).join('');
+}
+
 // Collect local app data from UI
 function collectLocalAppData() {
     config.local_apps.forEach((app, appIndex) => {
@@ -176,6 +299,13 @@ function collectLocalAppData() {
         app.name = document.getElementById(`${prefix}-name`)?.value || "";
         app.executable_path = document.getElementById(`${prefix}-path`)?.value || "";
         app.working_directory = document.getElementById(`${prefix}-workdir`)?.value || null;
+        app.create_working_dir = document.getElementById(`${prefix}-create-workdir`)?.checked || false;
+        app.singleton_match = document.getElementById(`${prefix}-singleton`)?.value || null;
+        app.auto_launch = document.getElementById(`${prefix}-auto-launch`)?.checked || false;
+        app.hide_window = document.getElementById(`${prefix}-hide-window`)?.checked || false;
+        app.detached = document.getElementById(`${prefix}-detached`)?.checked || false;
+        app.depends_on = (document.getElementById(`${prefix}-depends-on`)?.value || "")
+            .split(",").map(dep => dep.trim()).filter(Boolean);
         app.use_shell = document.getElementById(`${prefix}-use-shell`)?.checked || false;
         app.conda_env = app.use_shell ? (document.getElementById(`${prefix}-conda`)?.value || null) : null;
         app.shell_command = app.use_shell ? (document.getElementById(`${prefix}-shell-cmd`)?.value || null) : null;
@@ -205,6 +335,7 @@ function renderServers() {
                 switchTab(index);
             }
         };
+        makeReorderable(tab, index, 'server', moveServer);
         tabsContainer.appendChild(tab);
         
         // Create tab content
@@ -224,6 +355,7 @@ function renderServers() {
     
     // Attach event listeners
     attachServerEventListeners();
+    renderDefaultServerSelect();
 }
 
 // Render server content
@@ -240,7 +372,7 @@ function renderServerContent(server, serverIndex) {
             </div>
             <div class="form-group">
                 <label for="${prefix}-host">Host / IP Address <span class="required">*</span></label>
-                <input type="text" id="${prefix}-host" value="${server.host || ''}" placeholder="192.168.1.100" class="server-host-input" data-server-index="${serverIndex}">
+                <input type="text" id="${prefix}-host" value="${server.host || ''}" placeholder="192.168.1.100, host:2222 or [::1]" class="server-host-input" data-server-index="${serverIndex}">
                 <div class="validation-error" id="${prefix}-host-error" style="display: none;"></div>
             </div>
             <div class="form-group">
@@ -251,12 +383,43 @@ function renderServerContent(server, serverIndex) {
             <div class="form-group">
                 <label for="${prefix}-username">Username <span class="required">*</span></label>
                 <input type="text" id="${prefix}-username" value="${server.username || ''}" placeholder="calvin">
+                <div class="validation-error" id="${prefix}-username-error" style="display: none;"></div>
+            </div>
+            <div class="form-group">
+                <label for="${prefix}-auth-method">Authentication</label>
+                <select id="${prefix}-auth-method">
+                    <option value="key" ${(server.auth_method || 'key') === 'key' ? 'selected' : ''}>SSH key (falls back to password)</option>
+                    <option value="password" ${server.auth_method === 'password' ? 'selected' : ''}>Password</option>
+                    <option value="agent" ${server.auth_method === 'agent' ? 'selected' : ''}>SSH agent</option>
+                </select>
             </div>
             <div class="form-group">
-                <label for="${prefix}-ssh-key">SSH Key Path <span class="required">*</span></label>
+                <label for="${prefix}-ssh-key">SSH Key Path</label>
                 <input type="text" id="${prefix}-ssh-key" value="${server.ssh_key_path || ''}" placeholder="~/.ssh/id_rsa">
+                <div class="validation-error" id="${prefix}-ssh-key-error" style="display: none;"></div>
+            </div>
+            <div class="form-group">
+                <label for="${prefix}-key-passphrase">Key Passphrase</label>
+                <input type="password" id="${prefix}-key-passphrase" value="${server.ssh_key_passphrase || ''}" placeholder="Leave blank if the key is not encrypted">
+            </div>
+            <div class="form-group">
+                <label for="${prefix}-password">Password</label>
+                <input type="password" id="${prefix}-password" value="${server.password || ''}" placeholder="Used if the key is missing or rejected">
+            </div>
+            <div class="form-group">
+                <label for="${prefix}-compose-command">Compose Command</label>
+                <input type="text" id="${prefix}-compose-command" value="${server.compose_command || 'docker compose'}" placeholder="docker compose">
+                <small>Use docker-compose for Compose v1, or a full path if docker isn't on the server's PATH</small>
+            </div>
+            <div class="form-group">
+                <label>
+                    <input type="checkbox" id="${prefix}-use-sudo" ${server.use_sudo ? 'checked' : ''}>
+                    Use sudo for docker
+                </label>
+                <small>For users outside the docker group; needs passwordless sudo for docker on the server</small>
             </div>
             <button class="test-connection-btn" data-server-index="${serverIndex}">Test Connection</button>
+            <button class="secondary clone-server-btn" data-server-index="${serverIndex}">Clone Server</button>
             <div id="${prefix}-test-result" class="test-result" style="display: none;"></div>
         </div>
         
@@ -303,6 +466,18 @@ function renderServices(services, serverIndex) {
                         <label for="${prefix}-port">Port</label>
                         <input type="number" id="${prefix}-port" value="${service.port || 8080}">
                     </div>
+                    <div class="form-group">
+                        <label for="${prefix}-scheme">Opened With</label>
+                        <select id="${prefix}-scheme">
+                            <option value="http" ${service.scheme !== 'https' ? 'selected' : ''}>http://</option>
+                            <option value="https" ${service.scheme === 'https' ? 'selected' : ''}>https://</option>
+                        </select>
+                        <label>
+                            <input type="checkbox" id="${prefix}-self-signed" ${service.self_signed_cert ? 'checked' : ''}>
+                            Uses a self-signed certificate
+                        </label>
+                        <small>Browsers warn about self-signed certificates; the status window says which certificate problem it finds</small>
+                    </div>
                     <div class="form-group">
                         <label for="${prefix}-path">Path on Server</label>
                         <input type="text" id="${prefix}-path" value="${service.path || ''}" placeholder="/home/user/app">
@@ -318,6 +493,12 @@ function renderServices(services, serverIndex) {
                         <input type="text" id="${prefix}-prelaunch" value="${service.pre_launch_command || ''}" placeholder="python launcher.py">
                         <small>Command to run before starting (e.g., for env setup, permission fixes)</small>
                     </div>
+                    <div class="form-group">
+                        <label>Remote Files</label>
+                        <button class="secondary edit-remote-file-btn" data-server="${serverIndex}" data-service="${serviceIndex}" data-file=".env">Edit .env</button>
+                        <button class="secondary edit-remote-file-btn" data-server="${serverIndex}" data-service="${serviceIndex}" data-file="${service.compose_file || 'docker-compose.yml'}">Edit ${service.compose_file || 'docker-compose.yml'}</button>
+                        <small>Edits the file on the server; save the server first</small>
+                    </div>
                 </div>
             </div>
         `;
@@ -358,6 +539,39 @@ function renderLocalApps() {
                         <label for="${prefix}-workdir">Working Directory</label>
                         <input type="text" id="${prefix}-workdir" value="${app.working_directory || ''}" placeholder="C:\\Users\\user\\app">
                     </div>
+                    <div class="form-group">
+                        <label>
+                            <input type="checkbox" id="${prefix}-create-workdir" ${app.create_working_dir ? 'checked' : ''}>
+                            Create the working directory if it doesn't exist
+                        </label>
+                    </div>
+                    <div class="form-group">
+                        <label for="${prefix}-singleton">Single Instance Match (optional)</label>
+                        <input type="text" id="${prefix}-singleton" value="${app.singleton_match || ''}" placeholder="vctt/main.py">
+                        <small>Process name or command-line text; launching is refused if a matching process is already running</small>
+                    </div>
+                    <div class="form-group">
+                        <label>
+                            <input type="checkbox" id="${prefix}-auto-launch" ${app.auto_launch ? 'checked' : ''}>
+                            Include in "Launch All"
+                        </label>
+                    </div>
+                    <div class="form-group">
+                        <label>
+                            <input type="checkbox" id="${prefix}-hide-window" ${app.hide_window ? 'checked' : ''}>
+                            Hide console window (Windows)
+                        </label>
+                        <label>
+                            <input type="checkbox" id="${prefix}-detached" ${app.detached ? 'checked' : ''}>
+                            Keep running after the orchestrator quits
+                        </label>
+                        <small>Use for GUI apps like VCTT; the app is still tracked, and picked up again on next start</small>
+                    </div>
+                    <div class="form-group">
+                        <label for="${prefix}-depends-on">Depends On (optional)</label>
+                        <input type="text" id="${prefix}-depends-on" value="${(app.depends_on || []).join(', ')}" placeholder="portal:server-1, other-app-id">
+                        <small>Comma-separated app ids or portal:&lt;server id&gt;; these are launched and healthy first</small>
+                    </div>
                     <div class="form-group">
                         <label>
                             <input type="checkbox" id="${prefix}-use-shell" ${app.use_shell ? 'checked' : ''}>
@@ -406,7 +620,7 @@ function switchTab(index) {
 }
 
 // Add server
-function addServer() {
+async function addServer() {
     // Generate unique default name
     let defaultName = "New Server";
     let counter = 1;
@@ -417,7 +631,7 @@ function addServer() {
     }
     
     config.servers.push({
-        id: generateId(),
+        id: await generateId('server'),
         name: defaultName,
         host: "",
         port: 22,
@@ -429,6 +643,22 @@ function addServer() {
     renderServers();
 }
 
+// Save, then copy a server under a new id and switch to the copy for editing
+async function cloneServer(index) {
+    const name = config.servers[index].name || `Server ${index + 1}`;
+    if (!confirm(`Save your changes and clone "${name}"?`) || !await saveConfig(false)) {
+        return;
+    }
+    try {
+        const cloneId = await window.pywebview.api.clone_server(config.servers[index].id);
+        await loadConfig();
+        switchTab(config.servers.findIndex(s => s.id === cloneId));
+    } catch (error) {
+        console.error("Failed to clone server:", error);
+        alert("Failed to clone server: " + (error.message || error));
+    }
+}
+
 // Remove server
 function removeServer(index) {
     if (config.servers.length <= 1) {
@@ -446,20 +676,72 @@ function removeServer(index) {
 }
 
 // Add service
-function addService(serverIndex) {
+async function addService(serverIndex) {
     config.servers[serverIndex].services.push({
-        id: generateId(),
+        id: await generateId('service'),
         name: "New Service",
         container_name: "",
         port: 8080,
         path: "",
         healthcheck_path: "/",
-        pre_launch_command: null
+        pre_launch_command: null,
+        scheme: "http",
+        self_signed_cert: false
     });
     renderServers();
     switchTab(serverIndex);
 }
 
+// Open a small text file of a service (e.g. .env) from the server in an editor overlay
+async function editRemoteFile(serverIndex, serviceIndex, fileName) {
+    const server = config.servers[serverIndex];
+    const service = server.services[serviceIndex];
+    if (!server.id || !service.path) {
+        alert("Save the server with a service path before editing its files.");
+        return;
+    }
+    const remotePath = service.path.replace(/\/+$/, '') + '/' + fileName;
+    
+    let contents;
+    try {
+        await waitForAPI();
+        contents = await window.pywebview.api.read_remote_file(server.id, remotePath);
+    } catch (error) {
+        if (errorKind(error) !== "NotFound" || !confirm(`${remotePath} doesn't exist. Create it?`)) {
+            if (errorKind(error) !== "NotFound") alert("Could not open file: " + (error.message || error));
+            return;
+        }
+        contents = "";
+    }
+    
+    const overlay = document.createElement('div');
+    overlay.style.cssText = "position: fixed; inset: 0; background: rgba(0,0,0,0.5); display: flex; align-items: center; justify-content: center; z-index: 1000;";
+    overlay.innerHTML = `
+        <div style="background: white; padding: 16px; border-radius: 8px; width: 80%; max-width: 800px;">
+            <h3 style="margin-top: 0;"></h3>
+            <textarea style="width: 100%; height: 400px; font-family: monospace; box-sizing: border-box;" spellcheck="false"></textarea>
+            <div style="margin-top: 8px; text-align: right;">
+                <button class="secondary remote-file-cancel">Cancel</button>
+                <button class="primary remote-file-save">Save</button>
+            </div>
+        </div>
+    `;
+    overlay.querySelector('h3').textContent = `${server.name}: ${remotePath}`;
+    const textarea = overlay.querySelector('textarea');
+    textarea.value = contents;
+    overlay.querySelector('.remote-file-cancel').onclick = () => overlay.remove();
+    overlay.querySelector('.remote-file-save').onclick = async () => {
+        try {
+            await window.pywebview.api.write_remote_file(server.id, remotePath, textarea.value);
+            overlay.remove();
+        } catch (error) {
+            alert("Could not save file: " + (error.message || error));
+        }
+    };
+    document.body.appendChild(overlay);
+    textarea.focus();
+}
+
 // Remove service
 function removeService(serverIndex, serviceIndex) {
     if (confirm(`Remove service "${config.servers[serverIndex].services[serviceIndex].name || 'Service ' + (serviceIndex + 1)}"?`)) {
@@ -470,12 +752,18 @@ function removeService(serverIndex, serviceIndex) {
 }
 
 // Add local app
-function addLocalApp() {
+async function addLocalApp() {
     config.local_apps.push({
-        id: generateId(),
+        id: await generateId('app'),
         name: "New App",
         executable_path: "",
         working_directory: null,
+        create_working_dir: false,
+        singleton_match: null,
+        auto_launch: false,
+        depends_on: [],
+        hide_window: false,
+        detached: false,
         use_shell: false,
         conda_env: null,
         shell_command: null,
@@ -485,6 +773,52 @@ function addLocalApp() {
     renderLocalApps();
 }
 
+// Drag-to-reorder: dropping one element of a kind onto another calls onMove(fromIndex, toIndex)
+function makeReorderable(element, index, kind, onMove) {
+    const type = `application/x-orchestrator-${kind}`;
+    element.draggable = true;
+    element.addEventListener('dragstart', (e) => e.dataTransfer.setData(type, String(index)));
+    element.addEventListener('dragover', (e) => {
+        if (e.dataTransfer.types.includes(type)) {
+            e.preventDefault();
+        }
+    });
+    element.addEventListener('drop', (e) => {
+        e.preventDefault();
+        const from = parseInt(e.dataTransfer.getData(type));
+        if (!Number.isNaN(from) && from !== index) {
+            onMove(from, index);
+        }
+    });
+}
+
+function moveServer(from, to) {
+    collectServerData();
+    config.servers.splice(to, 0, config.servers.splice(from, 1)[0]);
+    activeServerTab = to;
+    renderServers();
+    saveOrder('reorder_servers', config.servers);
+}
+
+function moveLocalApp(from, to) {
+    collectLocalAppData();
+    config.local_apps.splice(to, 0, config.local_apps.splice(from, 1)[0]);
+    renderLocalApps();
+    saveOrder('reorder_local_apps', config.local_apps);
+}
+
+// Save a new order straight away; with unsaved items in the list the backend refuses, and it's saved with the rest
+async function saveOrder(method, items) {
+    try {
+        await waitForAPI();
+        await window.pywebview.api[method](items.map(item => item.id));
+    } catch (error) {
+        if (errorKind(error) !== "InvalidConfig") {
+            alert("Failed to save the new order: " + (error.message || error));
+        }
+    }
+}
+
 // Remove local app
 function removeLocalApp(index) {
     if (confirm(`Remove app "${config.local_apps[index].name || 'App ' + (index + 1)}"?`)) {
@@ -505,21 +839,105 @@ async function testConnection(serverIndex) {
         await waitForAPI();
         
         const serverConfig = {
+            id: config.servers[serverIndex]?.id,
             host: document.getElementById(`${prefix}-host`).value,
             port: parseInt(document.getElementById(`${prefix}-port`).value) || 22,
             username: document.getElementById(`${prefix}-username`).value,
-            ssh_key_path: document.getElementById(`${prefix}-ssh-key`).value
+            ssh_key_path: document.getElementById(`${prefix}-ssh-key`).value,
+            ssh_key_passphrase: document.getElementById(`${prefix}-key-passphrase`).value || null,
+            password: document.getElementById(`${prefix}-password`).value || null,
+            auth_method: document.getElementById(`${prefix}-auth-method`).value,
+            compose_command: document.getElementById(`${prefix}-compose-command`).value.trim() || "docker compose",
+            use_sudo: document.getElementById(`${prefix}-use-sudo`).checked
         };
         
         const result = await window.pywebview.api.test_connection(serverConfig);
-        resultDiv.innerHTML = "✓ Connection successful!<br>" + result;
-        resultDiv.className = "test-result success";
+        const missing = describeMissingPrerequisites(result.prerequisites);
+        if (missing) {
+            resultDiv.innerHTML = "⚠ SSH works, but " + missing + " not installed on the server";
+            resultDiv.className = "test-result warning";
+        } else {
+            resultDiv.innerHTML = "✓ Connection successful!<br>" + result.prerequisites.compose_version;
+            resultDiv.className = "test-result success";
+        }
+        if (result.session.server_version) {
+            resultDiv.appendChild(sessionDetail("Server: " + result.session.server_version));
+        }
+        if (result.session.auth_banner) {
+            resultDiv.appendChild(sessionDetail("Login banner:\n" + result.session.auth_banner));
+        }
     } catch (error) {
-        resultDiv.innerHTML = "✗ Connection failed: " + error;
+        const kind = errorKind(error);
+        if (kind === "HostKeyRejected" && String(error).includes("Unknown host key") && await confirmTrustHostKey(serverIndex, error)) {
+            return testConnection(serverIndex);
+        }
+        resultDiv.innerHTML = "✗ " + (CONNECTION_ERROR_TITLES[kind] || "Connection failed") + ": " + (error.message || error);
         resultDiv.className = "test-result error";
     }
 }
 
+// A line of server-provided text under a connection test result, kept as plain text
+function sessionDetail(text) {
+    const detail = document.createElement('pre');
+    detail.style.cssText = "margin: 6px 0 0; white-space: pre-wrap; font-size: 12px;";
+    detail.textContent = text;
+    return detail;
+}
+
+// Headlines for the backend's CommandError kinds that a connection test can raise
+const CONNECTION_ERROR_TITLES = {
+    AuthFailed: "Authentication failed",
+    HostKeyRejected: "Host key rejected",
+    ConnectionFailed: "Could not reach the server",
+    Timeout: "Connection timed out",
+    InvalidConfig: "Incomplete server settings",
+    SudoPasswordRequired: "SSH works, but sudo needs a password"
+};
+
+// Kind of a backend error: pywebview passes the CommandError subclass name as error.name
+function errorKind(error) {
+    return (error && error.name) || "CommandError";
+}
+
+// Describe which of docker / docker compose are missing, or null if both are present
+function describeMissingPrerequisites(prerequisites) {
+    if (!prerequisites.docker_installed) {
+        return "docker is";
+    }
+    if (!prerequisites.compose_installed) {
+        return `"${prerequisites.compose_command}" is`;
+    }
+    return null;
+}
+
+// Ask the user to trust an unknown host key (server must be saved first)
+async function confirmTrustHostKey(serverIndex, error) {
+    const message = String(error) + "\n\nTrust this host key and add it to known_hosts?";
+    if (!confirm(message)) {
+        return false;
+    }
+    try {
+        await window.pywebview.api.trust_host_key(config.servers[serverIndex].id);
+        return true;
+    } catch (trustError) {
+        alert("Failed to trust host key: " + trustError + "\n\nSave the configuration and try again.");
+        return false;
+    }
+}
+
+// Split "host:port", "[v6]:port" or "[v6]" into [host, port]; port is null if none was given
+function splitHostPort(host) {
+    const bracketed = host.match(/^\[([^\]]+)\](?::(\d+))?$/);
+    if (bracketed) {
+        return [bracketed[1], bracketed[2] ? parseInt(bracketed[2]) : null];
+    }
+    const combined = host.match(/^([^:]+):(\d+)$/);
+    if (combined) {
+        return [combined[1], parseInt(combined[2])];
+    }
+    return [host, null];  // Includes bare IPv6 literals such as ::1
+}
+
 // Validate individual server field
 function validateServerField(serverIndex, fieldType) {
     collectServerData(); // Update config with current values
@@ -566,7 +984,13 @@ function validateServerField(serverIndex, fieldType) {
     
     // Validate host/IP
     if (fieldType === 'host' || fieldType === 'all') {
-        const host = (hostInput?.value || '').trim();
+        // A port typed into the host field moves to the port field
+        const [host, hostPort] = splitHostPort((hostInput?.value || '').trim());
+        if (hostPort !== null && hostInput && portInput) {
+            hostInput.value = host;
+            portInput.value = hostPort;
+            collectServerData();
+        }
         const port = parseInt(portInput?.value) || 22;
         const hostError = document.getElementById(`${prefix}-host-error`);
         
@@ -647,6 +1071,11 @@ function attachServerEventListeners() {
         btn.onclick = () => testConnection(parseInt(btn.dataset.serverIndex));
     });
     
+    // Clone server buttons
+    document.querySelectorAll('.clone-server-btn').forEach(btn => {
+        btn.onclick = () => cloneServer(parseInt(btn.dataset.serverIndex));
+    });
+    
     // Add service buttons
     document.querySelectorAll('.add-service-btn').forEach(btn => {
         btn.onclick = () => addService(parseInt(btn.dataset.serverIndex));
@@ -660,6 +1089,11 @@ function attachServerEventListeners() {
         };
     });
     
+    // Edit remote file buttons
+    document.querySelectorAll('.edit-remote-file-btn').forEach(btn => {
+        btn.onclick = () => editRemoteFile(parseInt(btn.dataset.server), parseInt(btn.dataset.service), btn.dataset.file);
+    });
+    
     // Real-time validation for server name
     document.querySelectorAll('.server-name-input').forEach(input => {
         const serverIndex = parseInt(input.dataset.serverIndex);
@@ -760,6 +1194,11 @@ function attachLocalAppEventListeners() {
         }
     });
     
+    // Drag headers to reorder apps
+    document.querySelectorAll('#local-apps-container .accordion-header').forEach(header => {
+        makeReorderable(header, parseInt(header.dataset.app), 'app', moveLocalApp);
+    });
+    
     // Accordion toggles
     document.querySelectorAll('#local-apps-container .accordion-header').forEach(header => {
         header.onclick = (e) => {
@@ -18,11 +18,11 @@ function waitForAPI() {
     });
 }
 
-// Refresh status for all servers
-async function refreshAllStatus() {
+// Refresh status for all servers (force skips the backend's short status cache)
+async function refreshAllStatus(force = false) {
     try {
         await waitForAPI();
-        allStatus = await window.pywebview.api.get_all_status();
+        allStatus = await window.pywebview.api.get_all_status(force);
         console.log("All status:", allStatus);
         
         if (!allStatus || allStatus.length === 0) {
@@ -39,6 +39,65 @@ async function refreshAllStatus() {
     }
 }
 
+// Show the last known status straight away; the backend then pushes a fresh one as 'status-updated'
+async function loadCachedStatus() {
+    try {
+        await waitForAPI();
+        const cached = await window.pywebview.api.get_cached_status();
+        // A live status may already have arrived
+        if (cached.length > 0 && allStatus.length === 0) {
+            allStatus = cached;
+            renderServerTabs();
+        }
+    } catch (error) {
+        console.error("Failed to load cached status:", error);
+        await refreshAllStatus();
+    }
+}
+
+// Note on a stale (last known) status saying when it was fetched
+function staleBadge(serverStatus) {
+    if (!serverStatus.stale) {
+        return '';
+    }
+    const saved = serverStatus.saved_at ? new Date(serverStatus.saved_at * 1000).toLocaleString() : "earlier";
+    return `<span class="status-badge not-ready" title="Updating...">Last known, ${saved}</span>`;
+}
+
+// Banner for a server in maintenance mode, where launches and restarts are refused
+function maintenanceBanner(serverStatus) {
+    if (!serverStatus.maintenance) {
+        return '';
+    }
+    const since = new Date(serverStatus.maintenance.since * 1000).toLocaleString();
+    const reason = serverStatus.maintenance.reason ? `: ${serverStatus.maintenance.reason}` : '';
+    return `
+        <div style="background: #fff3cd; border: 1px solid #ffc107; border-radius: 4px; padding: 8px 12px; margin-bottom: 10px;">
+            🚧 In maintenance mode since ${since}${reason}. Launches and restarts are blocked.
+        </div>
+    `;
+}
+
+// Connected badge, saying so when SSH works but the docker daemon doesn't answer
+function connectionBadge(serverStatus) {
+    if (serverStatus.docker_available === false) {
+        return '<span class="status-badge not-ready">SSH OK, Docker daemon not running</span>';
+    }
+    return '<span class="status-badge connected">Connected</span>';
+}
+
+// Why docker isn't answering, as docker (or sudo) reported it; the text may contain angle brackets
+function dockerDownNotice(serverStatus) {
+    if (serverStatus.docker_available !== false) {
+        return '';
+    }
+    const notice = document.createElement('p');
+    notice.style.cssText = "color: #856404; white-space: pre-wrap;";
+    notice.textContent = "Services can't be checked or launched until docker is running on this server.\n" +
+        (serverStatus.docker_detail || "");
+    return notice.outerHTML;
+}
+
 // Render server tabs
 function renderServerTabs() {
     const tabsContainer = document.getElementById('server-tabs');
@@ -74,7 +133,9 @@ function renderServerContent(serverStatus) {
                     <h2>${serverStatus.server_name}</h2>
                     <span class="status-badge disconnected">Disconnected</span>
                 </div>
+                ${maintenanceBanner(serverStatus)}
                 <p style="color: #666;">Unable to connect to server. Check your SSH credentials in Settings.</p>
+                <button class="secondary" onclick="diagnoseConnection('${serverStatus.server_id}')">🩺 Diagnose</button>
             </div>
         `;
     }
@@ -84,19 +145,41 @@ function renderServerContent(serverStatus) {
             <div class="status-card">
                 <div class="status-header">
                     <h2>${serverStatus.server_name}</h2>
-                    <span class="status-badge connected">Connected</span>
+                    ${connectionBadge(serverStatus)}
+                    ${staleBadge(serverStatus)}
                 </div>
+                ${maintenanceBanner(serverStatus)}
+                ${dockerDownNotice(serverStatus)}
                 <p class="no-services">No services configured for this server.</p>
             </div>
         `;
     }
     
+    const maintenance = serverStatus.maintenance_commands || [];
+    const maintenanceHtml = maintenance.length > 0 ? `
+            <div style="display: flex; gap: 5px; flex-wrap: wrap; margin-top: 10px;">
+                ${maintenance.map(command => `
+                    <button class="secondary" title="${command.description || command.command}"
+                            onclick="runMaintenance('${serverStatus.server_id}', '${command.name}')">🔧 ${command.name}</button>
+                `).join('')}
+            </div>
+    ` : '';
+    
     return `
         <div class="status-card">
             <div class="status-header">
                 <h2>${serverStatus.server_name}</h2>
-                <span class="status-badge connected">Connected</span>
+                ${connectionBadge(serverStatus)}
+                ${staleBadge(serverStatus)}
             </div>
+            ${maintenanceBanner(serverStatus)}
+            ${dockerDownNotice(serverStatus)}
+            <button class="secondary" onclick="toggleMaintenanceMode('${serverStatus.server_id}', ${!serverStatus.maintenance})">
+                ${serverStatus.maintenance ? '✅ End Maintenance' : '🚧 Enter Maintenance Mode'}
+            </button>
+            <button class="secondary" onclick="showDiskUsage('${serverStatus.server_id}')">💾 Storage</button>
+            ${maintenanceHtml}
+            ${diskUsagePanel(serverStatus.server_id)}
         </div>
         
         ${serverStatus.services.map(service => renderServiceCard(serverStatus.server_id, service)).join('')}
@@ -105,11 +188,17 @@ function renderServerContent(serverStatus) {
         
 // Render service card
 function renderServiceCard(serverId, service) {
-    const statusBadge = service.ready ? 
+    const statusBadge = service.ready && service.client_reachable === false ?
+        `<span class="status-badge not-ready" title="${service.client_detail}">Up, but unreachable from here</span>` :
+        service.ready ?
         '<span class="status-badge ready">Ready</span>' : 
+        service.health === 'starting' ?
+            `<span class="status-badge not-ready">Warming up (${service.status_code})</span>` :
         service.running ?
             '<span class="status-badge not-ready">Starting...</span>' :
-            '<span class="status-badge disconnected">Stopped</span>';
+        PROJECT_STATE_BADGES[service.project_state] ?
+            `<span class="status-badge disconnected" title="${service.project_detail || ''}">${PROJECT_STATE_BADGES[service.project_state]}</span>` :
+            '<span class="status-badge disconnected">Offline</span>';
     
     const containersHtml = service.containers && service.containers.length > 0 ? `
         <ul class="container-list">
@@ -135,7 +224,35 @@ function renderServiceCard(serverId, service) {
                     </li>
             `).join('')}
         </ul>
-    ` : '<p style="color: #666; font-size: 13px;">No containers found</p>';
+    ` : service.project_state === 'not-created'
+        ? '<p style="color: #666; font-size: 13px;">No containers yet: this project has never been started</p>'
+        : `<p style="color: #666; font-size: 13px;">No containers found${service.project_detail ? `: ${service.project_detail}` : ''}</p>`;
+    
+    const composeServicesHtml = service.compose_services && service.compose_services.length > 0 ? `
+        <details style="margin-top: 10px;">
+            <summary style="cursor: pointer; font-size: 13px; font-weight: bold;">Compose Services</summary>
+            <ul class="container-list">
+                ${service.compose_services.map(composeService => `
+                    <li class="container-item">
+                        <strong>${composeService.name}</strong>
+                        <div style="display: flex; gap: 5px; align-items: center;">
+                            <span class="container-state ${composeService.state}">${composeService.state}</span>
+                            <button class="secondary" style="padding: 4px 8px; font-size: 11px;" 
+                                    onclick="controlComposeService('${serverId}', '${service.id}', '${composeService.name}', '${composeService.state === 'running' ? 'stop' : 'start'}')">
+                                ${composeService.state === 'running' ? '⏹ Stop' : '▶ Start'}
+                            </button>
+                        </div>
+                    </li>
+                `).join('')}
+            </ul>
+        </details>
+    ` : '';
+    
+    const certBadge = service.client_cert_issue ?
+        `<span class="status-badge not-ready" title="${service.client_detail}">${CERT_ISSUE_LABELS[service.client_cert_issue]}</span>` : '';
+    
+    const startedBadge = service.started_by_app ?
+        '<span class="status-badge connected" title="Launched by the orchestrator this session">You started this</span>' : '';
     
     const containerCountBadge = service.container_count > 0 ? 
         `<span style="background: #e9ecef; padding: 3px 8px; border-radius: 3px; font-size: 11px; margin-left: 10px;">${service.container_count} container${service.container_count > 1 ? 's' : ''}</span>` : '';
@@ -144,6 +261,8 @@ function renderServiceCard(serverId, service) {
         <div class="service-card">
             <div class="service-header">
                 <span class="service-name">${service.name}${containerCountBadge}</span>
+                ${startedBadge}
+                ${certBadge}
                 ${statusBadge}
             </div>
             
@@ -166,6 +285,9 @@ function renderServiceCard(serverId, service) {
                     <button class="danger" onclick="stopService('${serverId}', '${service.id}')">
                         ⏹ Stop Service
                     </button>
+                    <button class="secondary" onclick="stopService('${serverId}', '${service.id}', true)">
+                        🧹 Full Cleanup
+                    </button>
                 `}
                 <button class="secondary" onclick="refreshServiceStatus('${serverId}')">
                     🔄 Refresh
@@ -176,6 +298,8 @@ function renderServiceCard(serverId, service) {
                 <summary style="cursor: pointer; font-size: 13px; font-weight: bold;">Container Details</summary>
                 ${containersHtml}
             </details>
+            
+            ${composeServicesHtml}
         </div>
     `;
     }
@@ -209,20 +333,28 @@ async function launchService(serverId, serviceId) {
         console.log(`Service launched: ${url}`);
         
         // Refresh status
-        await refreshAllStatus();
+        await refreshAllStatus(true);
         
         alert(`Service launched successfully!\nURL: ${url}`);
         
     } catch (error) {
+        if (error.name === "InProgress") {
+            // Another window or the tray is already launching it; its progress events update this one
+            console.log("Launch already in progress:", error.message);
+            return;
+        }
         console.error("Failed to launch service:", error);
         alert("Failed to launch service: " + error);
-        await refreshAllStatus();
+        await refreshAllStatus(true);
     }
 }
 
-// Stop service
-async function stopService(serverId, serviceId) {
-    if (!confirm("Are you sure you want to stop this service?")) {
+// Stop service (fullCleanup also removes orphan containers and volumes)
+async function stopService(serverId, serviceId, fullCleanup = false) {
+    const prompt = fullCleanup ?
+        "Stop this service and remove its orphan containers and volumes? Data stored in the volumes will be lost." :
+        "Are you sure you want to stop this service?";
+    if (!confirm(prompt)) {
         return;
     }
     
@@ -234,16 +366,16 @@ async function stopService(serverId, serviceId) {
         event.target.disabled = true;
         event.target.textContent = 'Stopping...';
         
-        await window.pywebview.api.stop_service(serverId, serviceId);
-        console.log(`Service stopped`);
+        const result = await window.pywebview.api.stop_service(serverId, serviceId, fullCleanup, fullCleanup);
+        console.log(`Service stopped, removed containers: ${result.removed.join(', ') || 'none'}`);
         
         // Refresh status
-        await refreshAllStatus();
+        await refreshAllStatus(true);
         
     } catch (error) {
         console.error("Failed to stop service:", error);
         alert("Failed to stop service: " + error);
-        await refreshAllStatus();
+        await refreshAllStatus(true);
         }
 }
 
@@ -251,7 +383,7 @@ async function stopService(serverId, serviceId) {
 async function refreshServiceStatus(serverId) {
     try {
         await waitForAPI();
-        const status = await window.pywebview.api.get_status(serverId);
+        const status = await window.pywebview.api.get_status(serverId, true);
         
         // Update the specific server in allStatus
         const index = allStatus.findIndex(s => s.server_id === serverId);
@@ -288,8 +420,8 @@ async function openServiceUrl(url) {
     try {
         await waitForAPI();
         // Use Python API to open URL (works better on macOS)
-        const success = await window.pywebview.api.open_url(url);
-        if (!success) {
+        const result = await window.pywebview.api.open_url(url);
+        if (!result.opened) {
             alert(`Failed to open URL: ${url}\n\nPlease open it manually in your browser.`);
         }
     } catch (error) {
@@ -301,13 +433,35 @@ async function openServiceUrl(url) {
 
 // Initialize on page load
 document.addEventListener("DOMContentLoaded", () => {
-    document.getElementById("refresh-all").addEventListener("click", refreshAllStatus);
+    document.getElementById("refresh-all").addEventListener("click", () => refreshAllStatus(true));
     
-    // Refresh status immediately and then every 5 seconds
-    refreshAllStatus();
+    // Show the last known status immediately, then refresh every 5 seconds
+    loadCachedStatus();
     refreshInterval = setInterval(refreshAllStatus, 5000);
 });
 
+// Fresh status fetched in the background after get_cached_status
+// Maintenance mode may be toggled from another window or over the local API
+window.addEventListener("maintenance-changed", () => refreshAllStatus(true));
+
+window.addEventListener("status-updated", (event) => {
+    allStatus = event.detail.statuses;
+    if (allStatus.length === 0) {
+        document.getElementById('server-tabs').innerHTML = '<p class="no-services">No servers configured</p>';
+        document.getElementById('server-tab-contents').innerHTML = '';
+        return;
+    }
+    renderServerTabs();
+});
+
+// Launches and stops started from the tray report failures here
+window.addEventListener("tray-action-failed", async (event) => {
+    const { action, error } = event.detail;
+    console.error(`Tray ${action} failed:`, error);
+    await refreshAllStatus(true);
+    alert(`Failed to ${action} portal from the tray:\n\n${error}`);
+});
+
 // Cleanup on page unload
 window.addEventListener("beforeunload", () => {
     if (refreshInterval) {
@@ -315,6 +469,190 @@ window.addEventListener("beforeunload", () => {
     }
 });
 
+// Start or stop a single compose service (including ones that have no container yet)
+async function controlComposeService(serverId, serviceId, composeService, action) {
+    try {
+        await waitForAPI();
+        event.target.disabled = true;
+        await window.pywebview.api.control_service(serverId, serviceId, composeService, action);
+    } catch (error) {
+        console.error(`Failed to ${action} ${composeService}:`, error);
+        alert(`Failed to ${action} ${composeService}: ` + error);
+    }
+    await refreshAllStatus(true);
+}
+
+// Badges for the https certificate problems browsers warn about
+const CERT_ISSUE_LABELS = {
+    expired: "Certificate expired",
+    'self-signed': "Self-signed certificate",
+    'hostname-mismatch': "Certificate name mismatch",
+    untrusted: "Untrusted certificate"
+};
+
+// Badges for compose projects that aren't simply stopped (see ProjectState in ssh_client.py)
+const PROJECT_STATE_BADGES = {
+    'not-created': "Not created yet",
+    missing: "No compose file",
+    unavailable: "Docker unavailable"
+};
+
+// Labels for the stages of a connection diagnosis
+const DIAGNOSTIC_STAGE_LABELS = {
+    resolve: "Resolve host",
+    tcp: "TCP connect",
+    handshake: "SSH handshake",
+    host_key: "Host key check",
+    auth: "Authentication"
+};
+
+// Run each connection stage separately and show where it fails
+async function diagnoseConnection(serverId) {
+    try {
+        await waitForAPI();
+        const diagnosis = await window.pywebview.api.diagnose_connection(serverId);
+        const icons = { ok: "✓", failed: "✗", skipped: "–" };
+        const report = diagnosis.stages.map(stage =>
+            `${icons[stage.status]} ${DIAGNOSTIC_STAGE_LABELS[stage.name]} (${stage.duration_ms} ms): ${stage.detail}`
+        ).join("\n");
+        const summary = diagnosis.failed_stage
+            ? `Connection fails at: ${DIAGNOSTIC_STAGE_LABELS[diagnosis.failed_stage]}`
+            : "Every stage succeeded";
+        const server = [
+            diagnosis.server_version ? `Server: ${diagnosis.server_version}` : "",
+            diagnosis.auth_banner ? `Login banner:\n${diagnosis.auth_banner}` : ""
+        ].filter(Boolean).join("\n");
+        alert(`${summary}\n\n${report}` + (server ? `\n\n${server}` : ""));
+    } catch (error) {
+        console.error("Failed to diagnose connection:", error);
+        alert("Failed to diagnose connection: " + error);
+    }
+}
+
+// Run one of the server's configured maintenance commands and show its output
+async function runMaintenance(serverId, commandName) {
+    if (!confirm(`Run "${commandName}" on this server?`)) {
+        return;
+    }
+    
+    try {
+        await waitForAPI();
+        const result = await window.pywebview.api.run_maintenance(serverId, commandName);
+        const output = (result.stdout + result.stderr).trim() || "(no output)";
+        alert(`${commandName} exited with code ${result.exit_code}\n\n${output}`);
+    } catch (error) {
+        console.error(`Failed to run ${commandName}:`, error);
+        alert(`Failed to run ${commandName}: ` + error);
+    }
+    await refreshAllStatus(true);
+}
+
+// Last docker disk usage fetched per server id, kept so status refreshes don't hide the panel
+const diskUsageByServer = {};
+
+// Rows of the storage panel: DockerDiskUsage field -> label
+const DISK_USAGE_ROWS = {
+    images: "Images",
+    containers: "Containers",
+    volumes: "Volumes",
+    build_cache: "Build cache"
+};
+
+// Prune buttons: prune_docker target -> [label, confirmation]
+const PRUNE_TARGETS = {
+    images: ["Prune images", "Remove dangling images (e.g. ones replaced by updates)?"],
+    volumes: ["Prune volumes", "Remove volumes no container uses? Data in them is lost."],
+    all: ["Prune all", "Remove stopped containers, unused networks, dangling images and build cache? Volumes are kept."]
+};
+
+function formatBytes(bytes) {
+    const units = ["B", "KB", "MB", "GB", "TB"];
+    let value = bytes;
+    let unit = 0;
+    while (value >= 1000 && unit < units.length - 1) {
+        value /= 1000;
+        unit++;
+    }
+    return `${value.toFixed(unit === 0 ? 0 : 1)} ${units[unit]}`;
+}
+
+// Storage panel for a server, empty until showDiskUsage has fetched it
+function diskUsagePanel(serverId) {
+    const usage = diskUsageByServer[serverId];
+    if (!usage) {
+        return '';
+    }
+    return `
+        <div style="margin-top: 10px;">
+            <table style="width: 100%; font-size: 13px;">
+                <tr><th align="left">Type</th><th align="right">Count</th><th align="right">Size</th><th align="right">Reclaimable</th></tr>
+                ${Object.entries(DISK_USAGE_ROWS).map(([key, label]) => `
+                    <tr>
+                        <td>${label}</td>
+                        <td align="right">${usage[key].active} / ${usage[key].total_count}</td>
+                        <td align="right">${formatBytes(usage[key].size_bytes)}</td>
+                        <td align="right">${formatBytes(usage[key].reclaimable_bytes)}</td>
+                    </tr>
+                `).join('')}
+            </table>
+            <div style="display: flex; gap: 5px; flex-wrap: wrap; margin-top: 5px;">
+                ${Object.entries(PRUNE_TARGETS).map(([what, [label]]) => `
+                    <button class="secondary" onclick="pruneDocker('${serverId}', '${what}')">🧹 ${label}</button>
+                `).join('')}
+            </div>
+        </div>
+    `;
+}
+
+// Fetch docker's disk usage on a server and show the storage panel
+async function showDiskUsage(serverId) {
+    try {
+        await waitForAPI();
+        diskUsageByServer[serverId] = await window.pywebview.api.get_docker_disk_usage(serverId);
+        renderServerTabs();
+    } catch (error) {
+        console.error("Failed to get docker disk usage:", error);
+        alert("Failed to get docker disk usage: " + error);
+    }
+}
+
+// Clean up unused docker data on a server after confirming, then refresh the storage panel
+async function pruneDocker(serverId, what) {
+    if (!confirm(PRUNE_TARGETS[what][1])) {
+        return;
+    }
+    
+    try {
+        await waitForAPI();
+        const result = await window.pywebview.api.prune_docker(serverId, what);
+        alert(`Reclaimed ${formatBytes(result.reclaimed_bytes)}`);
+    } catch (error) {
+        console.error(`Failed to prune docker ${what}:`, error);
+        alert(`Failed to prune docker ${what}: ` + error);
+    }
+    await showDiskUsage(serverId);
+}
+
+// Block (or unblock) launches on a server while it's being worked on
+async function toggleMaintenanceMode(serverId, enabled) {
+    let reason = "";
+    if (enabled) {
+        reason = prompt("Maintenance mode blocks launches and restarts on this server. Reason (optional):", "");
+        if (reason === null) {
+            return;
+        }
+    }
+    
+    try {
+        await waitForAPI();
+        // The 'maintenance-changed' event refreshes the status
+        await window.pywebview.api.set_maintenance(serverId, enabled, reason);
+    } catch (error) {
+        console.error("Failed to set maintenance mode:", error);
+        alert("Failed to set maintenance mode: " + error);
+    }
+}
+
 // Restart container
 async function restartContainer(serverId, serviceId, containerName) {
     if (!confirm(`Restart container "${containerName}"?`)) {
@@ -559,5 +897,11 @@ window.refreshServiceStatus = refreshServiceStatus;
 window.copyServiceUrl = copyServiceUrl;
 window.openServiceUrl = openServiceUrl;
 window.restartContainer = restartContainer;
+window.runMaintenance = runMaintenance;
+window.showDiskUsage = showDiskUsage;
+window.pruneDocker = pruneDocker;
+window.toggleMaintenanceMode = toggleMaintenanceMode;
+window.diagnoseConnection = diagnoseConnection;
+window.controlComposeService = controlComposeService;
 window.viewContainerLogs = viewContainerLogs;
 
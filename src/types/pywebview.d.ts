@@ -18,6 +18,8 @@ interface ServiceConfig {
   path: string;
   healthcheck_path: string;
   pre_launch_command?: string;
+  scheme?: 'http' | 'https';
+  self_signed_cert?: boolean;
 }
 
 interface LocalAppConfig {
@@ -30,12 +32,33 @@ interface LocalAppConfig {
   shell_command?: string;
   install_dependencies?: boolean;
   requirements_file?: string;
+  create_working_dir?: boolean;
+  singleton_match?: string;
+  auto_launch?: boolean;
+  depends_on?: string[];  // App ids or portal:<server_id>
+  hide_window?: boolean;  // Windows: no console window
+  detached?: boolean;  // Own session; left running when the orchestrator quits
 }
 
 interface Preferences {
   auto_start_portal?: boolean;
   minimize_to_tray?: boolean;
   startup_launch?: boolean;
+  enable_notifications?: boolean;
+  preferred_browser?: string | null;
+  connect_retry_attempts?: number;
+  connect_retry_backoff_secs?: number;
+  log_level?: string;
+  status_cache_secs?: number;
+  default_server_id?: string | null;
+  keep_warm?: boolean;
+  stop_portal_on_quit?: boolean;
+  health_monitor_interval_secs?: number;  // 0 turns background health checks off
+  health_alert_cooldown_secs?: number;
+  max_log_size_mb?: number;
+  max_log_age_days?: number;
+  enable_local_api?: boolean;
+  local_api_port?: number;  // 0 picks a free port
   setup_completed?: boolean;
 }
 
@@ -57,49 +80,293 @@ interface ServiceStatus {
   name: string;
   url: string;
   ready: boolean;
+  health: 'ready' | 'starting' | 'unreachable';
+  status_code: number;
+  client_reachable: boolean | null;  // Whether this machine can connect to the port; null when the server says it's down
+  client_detail: string | null;
+  client_cert_issue: CertIssue | null;
   running: boolean;
   has_containers: boolean;
   containers: ContainerStatus[];
-  container_count: number;
+  container_count: number;  // Stopped containers included
+  project_state: 'running' | 'stopped' | 'not-created' | 'missing' | 'unavailable';
+  project_detail: string;  // Why the project couldn't be checked, for missing and unavailable
+  compose_services: ComposeServiceState[];
+  started_by_app?: boolean;  // Launched by this app session and not stopped since
   matched_container?: string;
 }
 
+interface PortalReachability {
+  url: string;
+  server_health: 'ready' | 'starting' | 'unreachable';
+  status_code: number;
+  reachable: boolean;
+  detail: string;
+  cert_issue: CertIssue | null;
+}
+
+// Why a browser would warn about an https service's certificate
+type CertIssue = 'expired' | 'self-signed' | 'hostname-mismatch' | 'untrusted';
+
+interface ComposeServiceState {
+  name: string;
+  state: string;  // Container state, or 'not-created'
+  container: string;
+}
+
+interface UpResult {
+  recreated: string[];
+  created: string[];
+  started: string[];
+  up_to_date: string[];
+}
+
+// error.name of a rejected API call: the backend's CommandError subclass for the failure
+type CommandErrorKind =
+  | 'ConnectionFailed'
+  | 'AuthFailed'
+  | 'HostKeyRejected'
+  | 'CommandFailed'
+  | 'PortInUse'
+  | 'SudoPasswordRequired'
+  | 'Timeout'
+  | 'Cancelled'
+  | 'InProgress'
+  | 'MaintenanceMode'
+  | 'NotFound'
+  | 'DockerMissing'
+  | 'InvalidConfig'
+  | 'CommandError';
+
+interface LaunchItemResult {
+  success: boolean;
+  error: string | null;
+}
+
+interface LaunchAllResult {
+  order: string[];  // App ids and portal:<server_id> items in launch order
+  results: Record<string, LaunchItemResult>;
+}
+
+interface AppStats {
+  running: boolean;
+  pid: number | null;
+  cpu_percent: number;
+  memory_rss_bytes: number;
+  process_count: number;
+}
+
+interface DownResult {
+  stopped: string[];
+  removed: string[];
+  networks: string[];
+  volumes: string[];
+}
+
+interface ContainerEvent {
+  time: number;
+  container: string;
+  service: string;
+  action: string;
+  health: string;
+}
+
 interface ServerStatus {
   server_id: string;
   server_name: string;
   connected: boolean;
+  docker_available?: boolean;  // SSH connected and the docker daemon answered
+  docker_detail?: string;  // Docker version, or why the daemon didn't answer
   services: ServiceStatus[];
+  maintenance_commands?: MaintenanceCommand[];
+  started_by_app?: boolean;  // Whether the portal (first service) was started by this session
+  error?: string;
+  stale?: boolean;  // From get_cached_status: the last known status, not a live one
+  saved_at?: number;  // Unix time a stale status was fetched
+  maintenance?: MaintenanceEntry | null;  // Set while the server is in maintenance mode
+}
+
+interface MaintenanceEntry {
+  since: number;  // Unix time maintenance mode was turned on
+  reason: string;
+}
+
+interface DiagnosticStage {
+  name: 'resolve' | 'tcp' | 'handshake' | 'host_key' | 'auth';
+  status: 'ok' | 'failed' | 'skipped';
+  duration_ms: number;
+  detail: string;
+}
+
+interface ConnectionDiagnosis {
+  stages: DiagnosticStage[];
+  failed_stage: string | null;
+  server_version: string | null;
+  auth_banner: string | null;
+}
+
+interface LogOutput {
+  output: string;
+  truncated: boolean;  // More lines matched than were returned
+}
+
+interface MaintenanceCommand {
+  name: string;
+  command: string;
+  description: string;
+}
+
+interface MaintenanceResult {
+  stdout: string;
+  stderr: string;
+  exit_code: number;
+}
+
+interface DiskUsageEntry {
+  total_count: number;
+  active: number;  // In use by a container
+  size_bytes: number;
+  reclaimable_bytes: number;
+}
+
+interface DockerDiskUsage {
+  images: DiskUsageEntry;
+  containers: DiskUsageEntry;
+  volumes: DiskUsageEntry;
+  build_cache: DiskUsageEntry;
+}
+
+type PruneTarget = 'images' | 'volumes' | 'all';
+
+interface PruneResult {
+  what: PruneTarget;
+  reclaimed_bytes: number;
+  output: string;
+}
+
+interface ValidationError {
+  server_id: string | null;
+  field: string | null;
+  message: string;
+  app_id?: string | null;
+}
+
+interface SaveConfigResult {
+  saved: boolean;
+  errors: ValidationError[];
+  warnings: ValidationError[];  // Saved anyway, e.g. a local app that isn't installed on this machine
+}
+
+interface Prerequisites {
+  docker_installed: boolean;
+  docker_version: string;
+  compose_installed: boolean;
+  compose_version: string;
+  compose_command: string;
+}
+
+interface SessionInfo {
+  server_version: string | null;
+  auth_banner: string | null;
+}
+
+interface PingResult {
+  version: string;
+  config_version: string;
+  uptime_secs: number;
+  pid: number;
+  tracked_processes: number;
+  pooled_connections: number;
+  log_streams: number;
+  shell_sessions: number;
+}
+
+interface TestConnectionResult {
+  message: string;
+  prerequisites: Prerequisites;
+  session: SessionInfo;
 }
 
 interface PyWebViewAPI {
   // Configuration
   load_config: () => Promise<AppConfig>;
-  save_config: (config: Record<string, any>) => Promise<void>;
+  save_config: (config: Record<string, any>) => Promise<SaveConfigResult>;
+  clone_server: (serverId: string) => Promise<string>;
+  generate_id: (kind: 'server' | 'service' | 'app') => Promise<string>;
+  reorder_servers: (ids: string[]) => Promise<void>;
+  reorder_local_apps: (ids: string[]) => Promise<void>;
+  get_config_path: () => Promise<string>;
+  export_config: (path: string, includeSecrets?: boolean) => Promise<void>;
+  import_config: (path: string, merge?: boolean) => Promise<{ servers: number; local_apps: number; warnings: ValidationError[] }>;
+  get_autostart_status: () => Promise<{ enabled: boolean; preference: boolean }>;
   
   // Remote service management
-  launch_service: (serverId: string, serviceId: string) => Promise<string>;
-  stop_service: (serverId: string, serviceId: string) => Promise<void>;
-  launch_portal: (serverId: string) => Promise<string>;
+  launch_service: (serverId: string, serviceId: string, wait?: boolean, requireHealthy?: boolean) => Promise<string>;  // wait=false skips the health wait
+  stop_service: (serverId: string, serviceId: string, removeOrphans?: boolean, removeVolumes?: boolean) => Promise<DownResult>;
+  launch_portal: (serverId: string, wait?: boolean, requireHealthy?: boolean) => Promise<string>;
+  cancel_launch: (serverId: string, teardown?: boolean) => Promise<boolean>;
+  update_portal: (serverId: string, serviceId?: string) => Promise<{ updated: string[]; up_to_date: string[]; applied: UpResult | null }>;
+  apply_updates: (serverId: string, serviceId?: string) => Promise<UpResult>;
+  upload_file: (serverId: string, localPath: string, remotePath: string) => Promise<{ bytes_transferred: number }>;
+  download_file: (serverId: string, remotePath: string, localPath: string) => Promise<{ bytes_transferred: number }>;
+  read_remote_file: (serverId: string, path: string) => Promise<string>;
+  write_remote_file: (serverId: string, path: string, contents: string) => Promise<{ bytes_written: number }>;
+  preview_service_commands: (serverId: string, serviceId: string) => Promise<Record<string, string[]>>;
   restart_container: (serverId: string, serviceId: string, containerName: string) => Promise<void>;
   
   // Local app management
+  launch_all: (serverId?: string) => Promise<LaunchAllResult>;
   launch_local_app: (appId: string) => Promise<void>;
   is_app_running: (appId: string) => Promise<boolean>;
+  clear_app_logs: (appId: string) => Promise<void>;
+  get_app_stats: (appId: string) => Promise<AppStats>;
+  get_service_logs: (serverId: string, serviceId: string, serviceName?: string | null, lines?: number,
+                     since?: string | null, grep?: string | null) => Promise<LogOutput>;
+  run_maintenance: (serverId: string, commandName: string) => Promise<MaintenanceResult>;
+  prune_docker: (serverId: string, what: PruneTarget) => Promise<PruneResult>;
   terminate_app: (appId: string) => Promise<void>;
   
   // Status
-  get_status: (serverId: string) => Promise<ServerStatus>;
-  get_all_status: () => Promise<ServerStatus[]>;
+  list_services: (serverId: string, serviceId: string) => Promise<ComposeServiceState[]>;
+  control_service: (serverId: string, serviceId: string, composeService: string, action: 'start' | 'stop' | 'restart') => Promise<void>;
+  check_portal_reachable: (serverId: string) => Promise<PortalReachability>;
+  get_docker_disk_usage: (serverId: string) => Promise<DockerDiskUsage>;
+  get_portal_url: (serverId: string) => Promise<string>;
+  copy_portal_url: (serverId: string) => Promise<string>;
+  get_status: (serverId: string, force?: boolean) => Promise<ServerStatus>;
+  get_all_status: (force?: boolean) => Promise<ServerStatus[]>;
+  ping: () => Promise<PingResult>;
+  set_maintenance: (serverId: string, enabled: boolean, reason?: string) => Promise<void>;
+  get_cached_status: () => Promise<ServerStatus[]>;
+  refresh_tray: () => Promise<void>;
+  get_container_events: (serverId: string, sinceSecs?: number) => Promise<ContainerEvent[]>;
   
   // Connection testing
-  test_connection: (server: Record<string, any>) => Promise<string>;
+  test_connection: (server: Record<string, any>) => Promise<TestConnectionResult>;
+  diagnose_connection: (serverId: string) => Promise<ConnectionDiagnosis>;
+  test_ssh_auth: (host: string, port: number, username: string, keyPath?: string, password?: string | null, authMethod?: string, serverId?: string) => Promise<{
+    auth_method: string;
+    connect_ms: number;
+    handshake_ms: number;
+    auth_ms: number;
+    command_ms: number;
+  }>;
+  
+  // Interactive shell
+  open_shell: (serverId: string, cols?: number, rows?: number) => Promise<string>;
+  write_shell: (sessionId: string, data: string) => Promise<void>;
+  resize_shell: (sessionId: string, cols: number, rows: number) => Promise<void>;
+  close_shell: (sessionId: string) => Promise<void>;
+  
+  // Logging
+  get_log_path: () => Promise<string>;
   
   // Window management
   open_settings_window: () => Promise<void>;
   open_status_window: () => Promise<void>;
   
   // Browser/URL management
-  open_url: (url: string) => Promise<boolean>;
+  open_url: (url: string) => Promise<{ opened: boolean; browser: string }>;
   
   // Setup wizard
   mark_setup_completed: () => Promise<void>;
@@ -8,8 +8,7 @@ sys.path.insert(0, str(Path(__file__).parent))
 
 from backend.config import AppConfig, ServerConfig, LocalAppConfig
 from backend.ssh_client import SSHClient
-from backend.process_manager import get_process_manager
-from backend.api import get_api
+from backend.api import API
 
 
 def test_config():
@@ -90,7 +89,7 @@ async def test_api():
     """Test API functions"""
     print("\nTesting API...")
     
-    api = get_api()
+    api = API()
     
     # Test load config
     config_dict = await api.load_config()
@@ -0,0 +1,102 @@
+"""Test script for the loopback HTTP control API"""
+import sys
+import json
+import urllib.error
+import urllib.request
+from pathlib import Path
+
+# Add backend to path
+sys.path.insert(0, str(Path(__file__).parent / 'backend'))
+
+from config import Preferences
+from errors import NotFound
+from local_api import LocalAPIServer, get_info_path
+
+
+class FakeAPI:
+    """Stands in for the pywebview API with a couple of commands"""
+    
+    def get_status(self, server_id, force=False):
+        if server_id != "srv":
+            raise NotFound(f"Server not found: {server_id}")
+        return {'server_id': server_id, 'connected': True, 'force': force}
+    
+    def open_settings_window(self):
+        raise AssertionError("Window commands must not be reachable over HTTP")
+
+
+def rpc(port, token, body):
+    """POST a request to /rpc, returning (HTTP status, decoded JSON body)"""
+    request = urllib.request.Request(
+        f"http://127.0.0.1:{port}/rpc",
+        data=json.dumps(body).encode(),
+        headers={'Authorization': f"Bearer {token}", 'Content-Type': 'application/json'}
+    )
+    try:
+        with urllib.request.urlopen(request, timeout=5) as response:
+            return response.status, json.loads(response.read())
+    except urllib.error.HTTPError as e:
+        return e.code, json.loads(e.read())
+
+
+def test_rpc_calls():
+    """Requests map onto API methods, with auth, an allowlist and error kinds"""
+    print("\n" + "=" * 60)
+    print("Testing local API requests")
+    print("=" * 60)
+    
+    server = LocalAPIServer()
+    server.apply(FakeAPI(), Preferences(enable_local_api=True, local_api_port=0))
+    try:
+        info = json.loads(get_info_path().read_text())
+        assert info['port'] == server.port
+        port, token = info['port'], info['token']
+        
+        status, body = rpc(port, token, {'method': 'get_status', 'params': {'server_id': 'srv', 'force': True}})
+        assert status == 200 and body == {'result': {'server_id': 'srv', 'connected': True, 'force': True}}, body
+        
+        status, body = rpc(port, token, {'method': 'get_status', 'params': ['missing']})
+        assert body['error']['kind'] == 'NotFound', body
+        assert "missing" in body['error']['message']
+        
+        status, body = rpc(port, "wrong-token", {'method': 'get_status', 'params': ['srv']})
+        assert status == 401, body
+        
+        status, body = rpc(port, token, {'method': 'open_settings_window'})
+        assert status == 404, body
+        
+        print("[PASS] Local API dispatches authenticated requests")
+    finally:
+        server.apply(FakeAPI(), Preferences(enable_local_api=False))
+    
+    assert server.port is None and not get_info_path().exists(), "Disabling removes the server and its info file"
+    return True
+
+
+def main():
+    """Run all tests"""
+    tests = [
+        test_rpc_calls,
+    ]
+    
+    try:
+        for test in tests:
+            if not test():
+                print(f"\n[FAIL] {test.__name__} failed!")
+                return False
+        
+        print("\n" + "=" * 60)
+        print("[SUCCESS] ALL TESTS PASSED!")
+        print("=" * 60)
+        return True
+    
+    except Exception as e:
+        print(f"\n[ERROR] Test failed with error: {e}")
+        import traceback
+        traceback.print_exc()
+        return False
+
+
+if __name__ == "__main__":
+    success = main()
+    sys.exit(0 if success else 1)
@@ -2,12 +2,58 @@
 import sys
 import os
 import json
+import tempfile
+from contextlib import contextmanager
 from pathlib import Path
 
 # Add backend to path
 sys.path.insert(0, str(Path(__file__).parent / 'backend'))
 
-from config import AppConfig, ServerConfig, ServiceConfig
+import config as config_module
+from config import CONFIG_DIR_ENV_VAR, AppConfig, ServerConfig, ServiceConfig
+from keychain import KEYCHAIN_PLACEHOLDER
+
+class FakeKeychain:
+    """Dictionary standing in for the OS keychain, patched into config for a test"""
+    
+    def __init__(self):
+        self.secrets = {}
+        self.readable = True
+        self._originals = {}
+    
+    def store_secret(self, key, value):
+        self.secrets[key] = value
+        return True
+    
+    def load_secret(self, key):
+        return self.secrets.get(key) if self.readable else None
+    
+    def delete_secret(self, key):
+        self.secrets.pop(key, None)
+    
+    def __enter__(self):
+        for name in ('store_secret', 'load_secret', 'delete_secret'):
+            self._originals[name] = getattr(config_module, name)
+            setattr(config_module, name, getattr(self, name))
+        return self
+    
+    def __exit__(self, *exc):
+        for name, original in self._originals.items():
+            setattr(config_module, name, original)
+
+@contextmanager
+def isolated_config():
+    """Point the config dir at a temporary directory for the duration of a test"""
+    previous = os.environ.get(CONFIG_DIR_ENV_VAR)
+    with tempfile.TemporaryDirectory() as config_dir:
+        os.environ[CONFIG_DIR_ENV_VAR] = config_dir
+        try:
+            yield Path(config_dir)
+        finally:
+            if previous is None:
+                os.environ.pop(CONFIG_DIR_ENV_VAR, None)
+            else:
+                os.environ[CONFIG_DIR_ENV_VAR] = previous
 
 def test_migration():
     """Test v1.0 to v2.0 config migration"""
@@ -230,6 +276,128 @@ def test_save_load():
     
     return True
 
+def test_clone_server():
+    """Cloned servers get a fresh id and a "(copy)" name"""
+    print("\n" + "=" * 60)
+    print("Testing Server Cloning")
+    print("=" * 60)
+    
+    config = AppConfig(version="2.0")
+    config.servers.append(ServerConfig(
+        id="base", name="GPU Box", host="10.0.0.5", port=22, username="calvin", ssh_key_path="",
+        services=[ServiceConfig(id="ai-portal", name="AI Portal", container_name="ai-portal", port=8080,
+                                path="/srv/portal", healthcheck_path="/")]
+    ))
+    
+    first = config.clone_server("base")
+    second = config.clone_server("base")
+    assert first.id not in ("base", second.id) and first.id.startswith("server-")
+    assert (first.name, second.name) == ("GPU Box (copy)", "GPU Box (copy 2)")
+    assert first.services[0].id == "ai-portal" and first.services is not config.servers[0].services
+    assert config.clone_server("missing") is None
+    
+    config.servers.append(ServerConfig(id="", name="Unsaved", host="10.0.0.6", port=22, username="", ssh_key_path=""))
+    config.assign_missing_ids()
+    assert config.servers[-1].id.startswith("server-")
+    print("[OK] Clones and generated ids are unique")
+    
+    # A clone shares its source's host and port, which mustn't block the next settings save
+    from api import API
+    AppConfig(version="2.0", servers=[ServerConfig(
+        id="base", name="GPU Box", host="10.0.0.5", port=22, username="calvin", ssh_key_path="", password="secret"
+    )]).save()
+    api = API()
+    clone_id = api.clone_server("base")
+    result = api.save_config(api.load_config())
+    assert result['saved'], result
+    assert [s.id for s in AppConfig.load().servers] == ["base", clone_id]
+    print("[OK] Cloned servers save with the rest of the settings")
+    
+    return True
+
+def test_reorder():
+    """Servers and apps can be reordered, but only by naming each exactly once"""
+    print("\n" + "=" * 60)
+    print("Testing Reordering")
+    print("=" * 60)
+    
+    config = AppConfig(version="2.0", servers=[
+        ServerConfig(id=server_id, name=server_id, host="10.0.0.5", port=22, username="", ssh_key_path="")
+        for server_id in ("a", "b", "c")
+    ])
+    config.reorder_servers(["c", "a", "b"])
+    assert [s.id for s in config.servers] == ["c", "a", "b"]
+    
+    for bad_order in (["c", "a"], ["c", "a", "b", "d"], ["c", "a", "a"]):
+        try:
+            config.reorder_servers(bad_order)
+            assert False, f"{bad_order} should be rejected"
+        except ValueError as e:
+            print(f"[OK] Rejected {bad_order}: {e}")
+    assert [s.id for s in config.servers] == ["c", "a", "b"], "A rejected order leaves the list alone"
+    
+    return True
+
+def test_masked_secrets():
+    """The settings page only ever sees placeholders for passwords, which save back to the stored values"""
+    print("\n" + "=" * 60)
+    print("Testing Secret Masking")
+    print("=" * 60)
+    
+    from api import API
+    AppConfig(version="2.0", servers=[ServerConfig(
+        id="base", name="GPU Box", host="10.0.0.5", port=22, username="calvin", ssh_key_path="",
+        password="secret", ssh_key_passphrase=None
+    )]).save()
+    api = API()
+    loaded = api.load_config()
+    assert loaded['servers'][0]['password'] == KEYCHAIN_PLACEHOLDER, loaded['servers'][0]
+    assert loaded['servers'][0]['ssh_key_passphrase'] is None, "Unset secrets stay unset"
+    print("[OK] load_config returns placeholders instead of secrets")
+    
+    assert api.save_config(loaded)['saved']
+    assert AppConfig.load().servers[0].password == "secret"
+    loaded['servers'][0]['password'] = "changed"
+    assert api.save_config(loaded)['saved']
+    assert AppConfig.load().servers[0].password == "changed"
+    print("[OK] Unchanged placeholders keep the stored secret, edits replace it")
+    
+    return True
+
+def test_unreadable_secrets():
+    """A secret the keychain can't give back survives saves; removed servers take theirs with them"""
+    print("\n" + "=" * 60)
+    print("Testing Unreadable Keychain Secrets")
+    print("=" * 60)
+    
+    with isolated_config(), FakeKeychain() as keychain:
+        AppConfig(version="2.0", servers=[
+            ServerConfig(id=server_id, name=server_id, host="10.0.0.5", port=22, username="calvin",
+                         ssh_key_path="", password=f"{server_id}-secret")
+            for server_id in ("a", "b")
+        ]).save()
+        assert keychain.secrets == {"a.password": "a-secret", "b.password": "b-secret"}, keychain.secrets
+        
+        keychain.readable = False
+        locked = AppConfig.load()
+        assert locked.servers[0].password == KEYCHAIN_PLACEHOLDER, "An unreadable secret keeps its placeholder"
+        from ssh_client import SSHClient
+        assert SSHClient.from_server(locked.servers[0]).password is None, "The placeholder is never sent as a password"
+        locked.save()
+        assert keychain.secrets == {"a.password": "a-secret", "b.password": "b-secret"}, keychain.secrets
+        print("[OK] Saving while the keychain is locked keeps stored passwords")
+        
+        keychain.readable = True
+        config = AppConfig.load()
+        assert config.servers[0].password == "a-secret"
+        config.servers[0].password = None
+        config.servers = config.servers[:1]
+        config.save()
+        assert keychain.secrets == {}, keychain.secrets
+        print("[OK] Cleared fields and removed servers drop their keychain entries")
+    
+    return True
+
 def main():
     """Run all tests"""
     print("\n" + "=" * 60)
@@ -252,6 +420,26 @@ def main():
             print("\n[FAIL] Save/Load test failed!")
             return False
         
+        # Test 4: Cloning
+        if not test_clone_server():
+            print("\n[FAIL] Clone test failed!")
+            return False
+        
+        # Test 5: Reordering
+        if not test_reorder():
+            print("\n[FAIL] Reorder test failed!")
+            return False
+        
+        # Test 6: Secret masking
+        if not test_masked_secrets():
+            print("\n[FAIL] Secret masking test failed!")
+            return False
+        
+        # Test 7: Unreadable secrets
+        if not test_unreadable_secrets():
+            print("\n[FAIL] Unreadable secrets test failed!")
+            return False
+        
         print("\n" + "=" * 60)
         print("[SUCCESS] ALL TESTS PASSED!")
         print("=" * 60)
@@ -271,6 +459,8 @@ def main():
         return False
 
 if __name__ == "__main__":
-    success = main()
+    # Saved configs and secrets stay out of the real config dir and OS keychain
+    with isolated_config(), FakeKeychain():
+        success = main()
     sys.exit(0 if success else 1)
 
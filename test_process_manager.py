@@ -0,0 +1,418 @@
+"""Test script for local app process management"""
+import sys
+import json
+import os
+import tempfile
+import time
+from pathlib import Path
+
+# Add backend to path
+sys.path.insert(0, str(Path(__file__).parent / 'backend'))
+
+from config import CONFIG_DIR_ENV_VAR, AppConfig, LocalAppConfig, ServerConfig
+from process_manager import WATCH_INTERVAL, ProcessKey, ProcessManager
+
+# Writes its arguments and one environment variable to the file named by its first argument
+ECHO_SCRIPT = """
+import json, os, sys
+with open(sys.argv[1], 'w') as f:
+    json.dump({'args': sys.argv[2:], 'env': os.environ.get('ORCHESTRATOR_TEST_VAR')}, f)
+"""
+
+
+def test_args_and_env():
+    """Test that launched apps receive configured args and env vars"""
+    print("=" * 60)
+    print("Testing local app args and environment")
+    print("=" * 60)
+    
+    with tempfile.TemporaryDirectory() as tmp:
+        script = Path(tmp) / 'echo_args.py'
+        script.write_text(ECHO_SCRIPT)
+        output = Path(tmp) / 'output.json'
+        
+        app = LocalAppConfig(
+            id="echo-test",
+            name="Echo Test",
+            executable_path=sys.executable,
+            working_directory=tmp,
+            args=[str(script), str(output), "--profile", "prod"],
+            env={"ORCHESTRATOR_TEST_VAR": "http://api.local"}
+        )
+        
+        manager = ProcessManager()
+        manager.launch_app(app)
+        manager.processes[ProcessKey.app(app.id)].wait(timeout=10)
+        
+        result = json.loads(output.read_text())
+        assert result['args'] == ["--profile", "prod"], f"Unexpected args: {result['args']}"
+        assert result['env'] == "http://api.local", f"Unexpected env: {result['env']}"
+        manager.cleanup_all()
+    
+    print("[PASS] Args and env passed to launched app")
+    return True
+
+
+def test_launch_validation():
+    """Test that a missing working directory or executable fails before spawning"""
+    print("\n" + "=" * 60)
+    print("Testing local app launch validation")
+    print("=" * 60)
+    
+    with tempfile.TemporaryDirectory() as tmp:
+        missing_dir = str(Path(tmp) / 'missing')
+        app = LocalAppConfig(id="validate-test", name="Validate Test",
+                             executable_path=sys.executable, working_directory=missing_dir)
+        assert app.launch_problems() == [('working_directory', f"Working directory not found: {missing_dir}")]
+        config = AppConfig(local_apps=[app])
+        assert config.validate() == [], "An app missing on this machine shouldn't block saving settings"
+        assert [w.field for w in config.launch_warnings()] == ['working_directory']
+        
+        manager = ProcessManager()
+        try:
+            manager.launch_app(app)
+            assert False, "Launch should fail when the working directory is missing"
+        except Exception as e:
+            assert "Working directory not found" in str(e), f"Unexpected error: {e}"
+        
+        # With create_working_dir the directory is made and the launch goes ahead
+        app.create_working_dir = True
+        app.args = ["-c", "pass"]
+        manager.launch_app(app)
+        manager.processes[ProcessKey.app(app.id)].wait(timeout=10)
+        assert Path(missing_dir).is_dir(), "Working directory should have been created"
+        manager.cleanup_all()
+        
+        script = Path(tmp) / 'not_executable.sh'
+        script.write_text("#!/bin/sh\n")
+        script.chmod(0o644)
+        fields = [f for f, _ in LocalAppConfig(id="x", name="X", executable_path=str(script)).launch_problems()]
+        if sys.platform != 'win32':
+            assert fields == ['executable_path'], f"Non-executable file should be reported: {fields}"
+        fields = [f for f, _ in LocalAppConfig(id="y", name="Y", executable_path="no-such-command-xyz").launch_problems()]
+        assert fields == ['executable_path'], f"Unknown command should be reported: {fields}"
+    
+    print("[PASS] Launch problems reported before spawning")
+    return True
+
+
+def test_resolve_executable():
+    """Test resolving absolute, relative-to-working-directory and bare-name executables"""
+    print("\n" + "=" * 60)
+    print("Testing executable path resolution")
+    print("=" * 60)
+    
+    with tempfile.TemporaryDirectory() as tmp:
+        app = LocalAppConfig(id="resolve-test", name="Resolve Test", executable_path=sys.executable,
+                             working_directory=tmp)
+        assert app.resolve_executable() == sys.executable, "Absolute paths are used as is"
+        
+        app.executable_path = os.path.join(".", "bin", "tool")
+        assert app.resolve_executable() == os.path.join(os.path.abspath(tmp), "bin", "tool"), \
+            f"Relative paths resolve against the working directory: {app.resolve_executable()}"
+        
+        app.executable_path = Path(sys.executable).name
+        resolved = app.resolve_executable()
+        assert resolved and os.path.isabs(resolved), f"Bare names are looked up on PATH: {resolved}"
+        
+        app.executable_path = "no-such-command-xyz"
+        assert app.resolve_executable() is None, "Unknown bare names don't resolve"
+        
+        # A relative executable launches even though it isn't under the orchestrator's directory
+        if sys.platform != 'win32':
+            app.executable_path = "./relative_app.sh"
+            script = Path(tmp) / "relative_app.sh"
+            script.write_text("#!/bin/sh\nsleep 2\n")
+            script.chmod(0o755)
+            manager = ProcessManager()
+            manager.launch_app(app)
+            assert manager.processes[ProcessKey.app(app.id)].poll() is None, "Relative executable should be running"
+            manager.cleanup_all()
+    
+    print("[PASS] Executable paths resolved correctly")
+    return True
+
+
+def test_launch_detects_immediate_exit():
+    """Test that an app exiting with an error right after spawn fails the launch"""
+    print("\n" + "=" * 60)
+    print("Testing post-spawn exit detection")
+    print("=" * 60)
+    
+    app = LocalAppConfig(
+        id="exit-test",
+        name="Exit Test",
+        executable_path=sys.executable,
+        args=["-c", "import sys; print('missing model file'); sys.exit(3)"]
+    )
+    manager = ProcessManager()
+    try:
+        manager.launch_app(app)
+        assert False, "Launch should fail when the app exits immediately"
+    except Exception as e:
+        assert "exited immediately with code 3" in str(e), f"Unexpected error: {e}"
+        assert "missing model file" in str(e), f"Error should include the app's output: {e}"
+    manager.cleanup_all()
+    
+    print("[PASS] Immediate exit reported with its exit code")
+    return True
+
+
+def test_failed_launch_not_restarted():
+    """Test that an app whose launch fails verification isn't relaunched by the crash watcher"""
+    print("\n" + "=" * 60)
+    print("Testing failed launches with a restart policy")
+    print("=" * 60)
+    
+    events = []
+    manager = ProcessManager()
+    manager.set_event_emitter(lambda event, payload: events.append((event, payload)))
+    app = LocalAppConfig(id="crash-on-start", name="Crash On Start", executable_path=sys.executable,
+                         args=["-c", "import sys; sys.exit(3)"], restart_policy="on_failure", max_restarts=3)
+    try:
+        manager.launch_app(app)
+        assert False, "Launch should fail when the app exits immediately"
+    except Exception as e:
+        assert "exited immediately with code 3" in str(e), f"Unexpected error: {e}"
+    
+    key = ProcessKey.app(app.id)
+    assert key not in manager._monitored and key not in manager._restart_at, "A failed launch isn't monitored"
+    time.sleep(WATCH_INTERVAL * 2 + 1)
+    names = [e for e, p in events if p['app_id'] == app.id]
+    assert 'app-restarted' not in names and 'app-crashed' not in names, f"Unexpected events: {names}"
+    manager.cleanup_all()
+    
+    print("[PASS] Failed launches aren't restarted")
+    return True
+
+
+def test_lifecycle_events():
+    """Test that starts, exits and terminations are pushed as events"""
+    print("\n" + "=" * 60)
+    print("Testing process lifecycle events")
+    print("=" * 60)
+    
+    events = []
+    manager = ProcessManager()
+    manager.set_event_emitter(lambda event, payload: events.append((event, payload)))
+    
+    quick = LocalAppConfig(id="quick-exit", name="Quick Exit", executable_path=sys.executable,
+                           args=["-c", "import time; time.sleep(1)"])
+    manager.launch_app(quick)
+    deadline = time.time() + WATCH_INTERVAL + 5
+    while not any(e == 'app-exited' for e, _ in events) and time.time() < deadline:
+        time.sleep(0.2)
+    exited = [p for e, p in events if e == 'app-exited']
+    assert exited and exited[0]['app_id'] == "quick-exit" and exited[0]['exit_code'] == 0, f"Events: {events}"
+    
+    long_running = LocalAppConfig(id="long-running", name="Long Running", executable_path=sys.executable,
+                                  args=["-c", "import time; time.sleep(30)"])
+    manager.launch_app(long_running)
+    manager.terminate(long_running.id, grace_period=2)
+    names = [e for e, p in events if p['app_id'] == "long-running"]
+    assert names == ['app-started', 'app-terminated'], f"Unexpected events for a stopped app: {names}"
+    
+    print("[PASS] Lifecycle events emitted")
+    return True
+
+
+def test_failed_relaunch():
+    """Test that a crash restart which can't launch retries with backoff, then stops monitoring the app"""
+    print("\n" + "=" * 60)
+    print("Testing failed crash restarts")
+    print("=" * 60)
+    
+    events = []
+    manager = ProcessManager()
+    manager.set_event_emitter(lambda event, payload: events.append((event, payload)))
+    app = LocalAppConfig(id="flaky", name="Flaky", executable_path=sys.executable,
+                         restart_policy="always", max_restarts=2)
+    key = ProcessKey.app(app.id)
+    
+    def fail_launch(config):
+        raise Exception("executable went missing")
+    manager._launch = fail_launch
+    manager._monitored[key] = app
+    
+    manager._restart_at[key] = 0
+    manager._check_monitored(key, app)
+    assert key in manager._monitored and manager._restart_at[key] > time.time(), "First failure should schedule a retry"
+    
+    manager._restart_at[key] = 0
+    manager._check_monitored(key, app)
+    assert key not in manager._monitored and key not in manager._restart_at, "Out of attempts, the app is dropped"
+    failed = [p for e, p in events if e == 'app-restart-failed']
+    assert failed == [{'app_id': "flaky", 'name': "Flaky", 'error': "executable went missing"}], f"Events: {events}"
+    
+    print("[PASS] Failed restarts back off, then give up with an event")
+    return True
+
+
+def test_singleton_matching():
+    """Test matching an already-running process by name or command line"""
+    print("\n" + "=" * 60)
+    print("Testing singleton process matching")
+    print("=" * 60)
+    
+    assert ProcessManager.matches_singleton("VCTT.exe", [], "vctt.exe"), "Names match case-insensitively"
+    assert ProcessManager.matches_singleton("python3", ["python3", "/opt/vctt/main.py"], "vctt/main.py")
+    assert not ProcessManager.matches_singleton("python3", ["python3", "other.py"], "vctt/main.py")
+    assert not ProcessManager.matches_singleton("vctt-helper", None, "vctt"), "Names must match exactly"
+    
+    print("[PASS] Singleton matching works")
+    return True
+
+
+def test_detached_apps():
+    """Detached apps run in their own session and are left running by cleanup_all"""
+    print("\n" + "=" * 60)
+    print("Testing detached local apps")
+    print("=" * 60)
+    
+    app = LocalAppConfig(id="detached-test", name="Detached Test", executable_path=sys.executable,
+                         args=["-c", "import time; time.sleep(30)"], detached=True)
+    manager = ProcessManager()
+    manager.launch_app(app)
+    process = manager.processes[ProcessKey.app(app.id)]
+    try:
+        if sys.platform != 'win32':
+            assert os.getsid(process.pid) == process.pid, "A detached app should lead its own session"
+        manager.cleanup_all()
+        assert process.poll() is None, "cleanup_all should leave detached apps running"
+        assert ProcessKey.app(app.id) in manager.processes, "Detached apps stay tracked"
+    finally:
+        process.kill()
+        process.wait(timeout=10)
+    
+    print("[PASS] Detached apps survive cleanup")
+    return True
+
+
+def test_independent_managers():
+    """Managers don't share processes, and state file keys carry their kind"""
+    print("\n" + "=" * 60)
+    print("Testing process manager isolation and keys")
+    print("=" * 60)
+    
+    app = LocalAppConfig(id="isolation-test", name="Isolation Test", executable_path=sys.executable,
+                         args=["-c", "import time; time.sleep(30)"])
+    first, second = ProcessManager(), ProcessManager()
+    first.launch_app(app)
+    try:
+        assert first.is_running(app.id)
+        assert not second.is_running(app.id) and not second.processes, "A new manager starts with no processes"
+    finally:
+        first.terminate(app.id, grace_period=1)
+    
+    key = ProcessKey.app("shared")
+    assert str(key) == "app:shared" and ProcessKey.parse(str(key)) == key
+    assert ProcessKey.parse("shared") == key, "Bare ids from older state files are apps"
+    assert ProcessKey.parse("portal:x") == ProcessKey.app("portal:x"), "Unknown kinds are part of the id"
+    
+    print("[PASS] Managers are independent")
+    return True
+
+
+def test_log_rotation():
+    """Oversized logs are rotated to .log.1, stale ones deleted, and clear_logs empties both"""
+    print("\n" + "=" * 60)
+    print("Testing captured log rotation")
+    print("=" * 60)
+    
+    manager = ProcessManager()
+    big = manager.get_log_path("rotate-big")
+    big.write_bytes(b"x" * (2 * 1024 * 1024))
+    stale = manager.get_log_path("rotate-stale")
+    stale.write_text("old output\n")
+    old = time.time() - 40 * 86400
+    os.utime(stale, (old, old))
+    
+    manager.rotate_logs(max_size_mb=1, max_age_days=30)
+    assert big.stat().st_size == 0 and manager.get_log_backup_path("rotate-big").stat().st_size == 2 * 1024 * 1024
+    assert not stale.exists(), "Logs untouched for longer than max_log_age_days are deleted"
+    
+    manager.clear_logs("rotate-big")
+    assert big.stat().st_size == 0 and not manager.get_log_backup_path("rotate-big").exists()
+    big.unlink()
+    
+    print("[PASS] Logs are rotated and expired")
+    return True
+
+
+def test_launch_order():
+    """Dependencies launch first, and unknown ids or cycles are rejected"""
+    print("\n" + "=" * 60)
+    print("Testing launch dependency ordering")
+    print("=" * 60)
+    
+    config = AppConfig(
+        servers=[ServerConfig(id="srv", name="Server", host="example.com", port=22, username="user", ssh_key_path="")],
+        local_apps=[
+            LocalAppConfig(id="vctt", name="VCTT", executable_path="vctt", depends_on=["worker", "portal:srv"]),
+            LocalAppConfig(id="worker", name="Worker", executable_path="worker", depends_on=["portal:srv"]),
+            LocalAppConfig(id="tool", name="Tool", executable_path="tool"),
+        ]
+    )
+    assert config.launch_order(["vctt", "tool"]) == ["portal:srv", "worker", "vctt", "tool"]
+    assert config.launch_order(["worker", "vctt"]) == ["portal:srv", "worker", "vctt"], "Shared dependencies appear once"
+    
+    config.get_local_app("tool").depends_on = ["missing"]
+    try:
+        config.launch_order(["tool"])
+        assert False, "Unknown dependency should be rejected"
+    except ValueError as e:
+        assert "missing" in str(e)
+    
+    config.get_local_app("tool").depends_on = []
+    config.get_local_app("worker").depends_on = ["vctt"]
+    try:
+        config.launch_order(["vctt"])
+        assert False, "Cycle should be rejected"
+    except ValueError as e:
+        assert "vctt -> worker -> vctt" in str(e), e
+    
+    print("[PASS] Launch order respects dependencies")
+    return True
+
+
+def main():
+    """Run all tests"""
+    tests = [
+        test_args_and_env,
+        test_launch_validation,
+        test_resolve_executable,
+        test_launch_detects_immediate_exit,
+        test_failed_launch_not_restarted,
+        test_lifecycle_events,
+        test_failed_relaunch,
+        test_singleton_matching,
+        test_detached_apps,
+        test_independent_managers,
+        test_log_rotation,
+        test_launch_order,
+    ]
+    
+    try:
+        for test in tests:
+            if not test():
+                print(f"\n[FAIL] {test.__name__} failed!")
+                return False
+        
+        print("\n" + "=" * 60)
+        print("[SUCCESS] ALL TESTS PASSED!")
+        print("=" * 60)
+        return True
+        
+    except Exception as e:
+        print(f"\n[ERROR] Test failed with error: {e}")
+        import traceback
+        traceback.print_exc()
+        return False
+
+
+if __name__ == "__main__":
+    # Logs, processes.json and any saved config go to a scratch directory rather than the real config dir
+    with tempfile.TemporaryDirectory() as config_dir:
+        os.environ[CONFIG_DIR_ENV_VAR] = config_dir
+        success = main()
+    sys.exit(0 if success else 1)
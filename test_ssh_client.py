@@ -0,0 +1,775 @@
+"""Test script for SSH client output parsing and command construction"""
+import os
+import socket
+import sys
+import tempfile
+import threading
+import time
+from pathlib import Path
+
+# Add backend to path
+sys.path.insert(0, str(Path(__file__).parent / 'backend'))
+
+from config import CONFIG_DIR_ENV_VAR, ALGORITHM_FIELDS, AppConfig, ServerConfig, ServiceConfig, split_host_port
+from errors import ErrorKind, InvalidConfig, command_error
+from ssh_client import MAX_CONCURRENT_CHANNELS, PIPEFAIL_PREFIX, CommandResult, TransientConnectionError, LogOutput, trim_log_tail, CommandTimedOut, ContainerStatus, SSHClient, decode_auth_banner, parse_compose_ps, parse_container_events, parse_image_ids, parse_disk_usage, parse_reclaimed_space, parse_compose_down, parse_compose_up, parse_port_conflict, merge_service_states, project_state, ProjectState, NOT_CREATED, quote_path, HealthState, ServiceHealth, is_accepted_status
+
+# Sample 'docker compose ps --format json' output from Compose v2.20+ (single JSON array)
+COMPOSE_PS_ARRAY = (
+    '[{"Name":"portal-ai-portal-1","Service":"ai-portal","State":"running",'
+    '"Status":"Up 2 hours (healthy)","Health":"healthy"},'
+    '{"Name":"portal-db-1","Service":"db","State":"exited","Status":"Exited (1) 5 minutes ago","Health":""}]'
+)
+
+# Sample output from older Compose versions (one JSON object per line)
+COMPOSE_PS_NDJSON = (
+    '{"Name":"portal-ai-portal-1","Service":"ai-portal","State":"running","Status":"Up 10 seconds (health: starting)","Health":"starting"}\n'
+    '{"Name":"portal-db-1","Service":"db","State":"running","Status":"Up 2 hours","Health":""}\n'
+)
+
+
+def test_parse_compose_ps_array():
+    """Test parsing the JSON array format"""
+    print("=" * 60)
+    print("Testing docker compose ps parsing (JSON array)")
+    print("=" * 60)
+    
+    containers = parse_compose_ps(COMPOSE_PS_ARRAY)
+    assert len(containers) == 2, "Should parse 2 containers"
+    assert containers[0].name == "portal-ai-portal-1"
+    assert containers[0].service == "ai-portal"
+    assert containers[0].state == "running"
+    assert containers[0].health == "healthy"
+    assert containers[1].state == "exited"
+    assert containers[1].health == ""
+    
+    print("[PASS] JSON array output parsed correctly")
+    return True
+
+
+def test_parse_compose_ps_ndjson():
+    """Test parsing the newline-delimited JSON format, including stray non-JSON lines"""
+    print("\n" + "=" * 60)
+    print("Testing docker compose ps parsing (NDJSON)")
+    print("=" * 60)
+    
+    output = 'WARN[0000] The "FOO" variable is not set. Defaulting to a blank string.\n' + COMPOSE_PS_NDJSON
+    containers = parse_compose_ps(output)
+    assert len(containers) == 2, "Should parse 2 containers and skip the warning line"
+    assert containers[0].health == "starting"
+    assert containers[1].service == "db"
+    
+    assert parse_compose_ps("") == [], "Empty output should parse to no containers"
+    assert parse_compose_ps("[]") == [], "Empty array should parse to no containers"
+    
+    print("[PASS] NDJSON output parsed correctly")
+    return True
+
+
+def test_container_matches():
+    """Test matching configured names against compose service and container names"""
+    print("\n" + "=" * 60)
+    print("Testing container name matching")
+    print("=" * 60)
+    
+    container = parse_compose_ps(COMPOSE_PS_ARRAY)[0]
+    assert container.matches("ai-portal"), "Should match the compose service name"
+    assert container.matches("portal-ai-portal-1"), "Should match the full container name"
+    assert not container.matches("db"), "Should not match a different service"
+    
+    # Service name missing (e.g. plain docker ps output): fall back to the project-prefixed name
+    unnamed = ContainerStatus(name="myproject_web_1", status="Up", state="running")
+    assert unnamed.matches("web"), "Should match a Compose v1 style container name"
+    
+    print("[PASS] Container names matched correctly")
+    return True
+
+
+def test_parse_image_ids():
+    """Test parsing image IDs used to tell updated images from up-to-date ones"""
+    print("\n" + "=" * 60)
+    print("Testing image ID parsing")
+    print("=" * 60)
+    
+    output = "ghcr.io/acme/ai-portal:latest sha256:abc123\npostgres:16 \n"
+    images = parse_image_ids(output)
+    assert images == {"ghcr.io/acme/ai-portal:latest": "sha256:abc123", "postgres:16": ""}, \
+        "Images missing locally should map to an empty ID"
+    
+    print("[PASS] Image IDs parsed correctly")
+    return True
+
+
+def test_parse_disk_usage():
+    """Test parsing docker system df rows and prune output into byte counts"""
+    print("\n" + "=" * 60)
+    print("Testing docker disk usage parsing")
+    print("=" * 60)
+    
+    output = "\n".join([
+        '{"Active":"2","Reclaimable":"1.5GB (60%)","Size":"2.5GB","TotalCount":"5","Type":"Images"}',
+        '{"Active":"2","Reclaimable":"0B (0%)","Size":"12kB","TotalCount":"3","Type":"Containers"}',
+        '{"Active":"1","Reclaimable":"512MB","Size":"1GB","TotalCount":"2","Type":"Local Volumes"}',
+        'WARNING: not json',
+    ])
+    usage = parse_disk_usage(output)
+    assert usage.images.total_count == 5 and usage.images.active == 2
+    assert usage.images.size_bytes == 2_500_000_000 and usage.images.reclaimable_bytes == 1_500_000_000
+    assert usage.containers.size_bytes == 12_000 and usage.containers.reclaimable_bytes == 0
+    assert usage.volumes.reclaimable_bytes == 512_000_000
+    assert usage.build_cache.total_count == 0, "Rows docker didn't print stay zero"
+    
+    assert parse_reclaimed_space("Deleted Images:\nuntagged: x\n\nTotal reclaimed space: 1.2GB") == 1_200_000_000
+    assert parse_reclaimed_space("Total reclaimed space: 0B") == 0
+    assert parse_reclaimed_space("") == 0
+    
+    ssh = SSHClient("example.com", 22, "deploy", "", password="unused", dry_run=True)
+    ssh.prune("images")
+    assert ssh.dry_run_commands == ["docker image prune -f"], ssh.dry_run_commands
+    try:
+        ssh.prune("everything")
+        assert False, "Unknown prune targets must be refused"
+    except InvalidConfig:
+        pass
+    
+    print("[PASS] Disk usage and reclaimed space parsed correctly")
+    return True
+
+
+def test_parse_container_events():
+    """Test parsing docker events output, including health transitions"""
+    print("\n" + "=" * 60)
+    print("Testing docker events parsing")
+    print("=" * 60)
+    
+    output = (
+        '{"status":"start","id":"0123456789abcdef","Type":"container","Action":"start",'
+        '"Actor":{"ID":"0123456789abcdef","Attributes":{"name":"portal-ai-portal-1","com.docker.compose.service":"ai-portal"}},'
+        '"time":1700000000}\n'
+        '{"status":"health_status: unhealthy","id":"0123456789abcdef","Type":"container","Action":"health_status: unhealthy",'
+        '"Actor":{"ID":"0123456789abcdef","Attributes":{"name":"portal-ai-portal-1"}},"time":1700000060}\n'
+    )
+    events = parse_container_events(output)
+    assert len(events) == 2, "Should parse 2 events"
+    assert events[0].action == "start" and events[0].service == "ai-portal"
+    assert events[1].action == "health_status" and events[1].health == "unhealthy"
+    assert events[1].time == 1700000060
+    
+    print("[PASS] Container events parsed correctly")
+    return True
+
+
+def test_dry_run_commands():
+    """Test that dry run records the generated commands instead of running them"""
+    print("\n" + "=" * 60)
+    print("Testing dry run command generation")
+    print("=" * 60)
+    
+    ssh = SSHClient("example.com", 22, "deploy", "", password="unused", dry_run=True)
+    ssh.connect()  # No-op in dry run mode
+    ssh.start_service("/opt/portal", None, "cp .env.example .env")
+    ssh.stop_service("/opt/portal", "ai-portal")
+    
+    assert ssh.dry_run_commands == [
+        "cd /opt/portal && cp .env.example .env",
+        "cd /opt/portal && docker compose up -d",
+        "cd /opt/portal && docker compose stop ai-portal",
+    ], f"Unexpected commands: {ssh.dry_run_commands}"
+    
+    print("[PASS] Dry run recorded the expected commands")
+    return True
+
+
+def test_parse_port_conflict():
+    """Docker's two wordings of a taken host port both yield the port"""
+    print("\n" + "=" * 60)
+    print("Testing port conflict detection")
+    print("=" * 60)
+    
+    allocated = ("Error response from daemon: driver failed programming external connectivity on endpoint "
+                 "portal-web-1: Bind for 0.0.0.0:8080 failed: port is already allocated")
+    assert parse_port_conflict(allocated) == 8080
+    in_use = "Error starting userland proxy: listen tcp4 0.0.0.0:3000: bind: address already in use"
+    assert parse_port_conflict(in_use) == 3000
+    assert parse_port_conflict("Error: image not found") is None
+    
+    print("[PASS] Port conflicts are detected")
+    return True
+
+
+def test_log_filters():
+    """since and grep are applied on the server, and the tail limit reports truncation"""
+    print("\n" + "=" * 60)
+    print("Testing filtered log commands")
+    print("=" * 60)
+    
+    ssh = SSHClient("example.com", 22, "deploy", "", password="unused", dry_run=True)
+    ssh.fetch_logs("/opt/portal", "web", 50)
+    ssh.fetch_logs("/opt/portal", "web", 50, since="10m", grep="ERROR'; reboot")
+    assert ssh.dry_run_commands == [
+        PIPEFAIL_PREFIX + "cd /opt/portal && docker compose logs --tail 51 web 2>&1 | tail -n 51",
+        PIPEFAIL_PREFIX + "cd /opt/portal && docker compose logs --since 10m web 2>&1"
+        " | { grep -e 'ERROR'\"'\"'; reboot' || [ $? -eq 1 ]; } | tail -n 51",
+    ], f"Unexpected commands: {ssh.dry_run_commands}"
+    
+    assert trim_log_tail("a\nb\nc\n", 2) == LogOutput(output="b\nc", truncated=True)
+    assert trim_log_tail("a\nb\n", 2) == LogOutput(output="a\nb", truncated=False)
+    assert trim_log_tail("", 2) == LogOutput(output="", truncated=False)
+    
+    print("[PASS] Log filters built and truncation detected")
+    return True
+
+
+def test_compose_file_and_profiles():
+    """Test that compose commands include a custom compose file and profiles"""
+    print("\n" + "=" * 60)
+    print("Testing compose file and profile flags")
+    print("=" * 60)
+    
+    ssh = SSHClient("example.com", 22, "deploy", "", password="unused", dry_run=True,
+                    compose_file="docker-compose.prod.yml", compose_profiles=["gpu", "monitoring"])
+    ssh.start_service("/opt/portal", "ai-portal")
+    
+    expected = "cd /opt/portal && docker compose -f docker-compose.prod.yml --profile gpu --profile monitoring up -d ai-portal"
+    assert ssh.dry_run_commands == [expected], f"Unexpected commands: {ssh.dry_run_commands}"
+    
+    legacy = SSHClient("example.com", 22, "deploy", "", password="unused", dry_run=True, compose_command="docker-compose")
+    legacy.start_service("/opt/portal", "ai-portal")
+    assert legacy.dry_run_commands == ["cd /opt/portal && docker-compose up -d ai-portal"], legacy.dry_run_commands
+    
+    sudo = SSHClient("example.com", 22, "deploy", "", password="unused", dry_run=True, use_sudo=True)
+    sudo.start_service("/opt/portal", "ai-portal")
+    sudo.restart_container("/opt/portal", "ai-portal")
+    assert sudo.dry_run_commands == ["cd /opt/portal && sudo -n docker compose up -d ai-portal",
+                                     "sudo -n docker restart ai-portal"], sudo.dry_run_commands
+    
+    # The format is a Go template, so it must reach docker with doubled braces
+    for client, prefix in ((ssh, "docker"), (sudo, "sudo -n docker")):
+        client.dry_run_commands.clear()
+        client.check_container_stats()
+        assert client.dry_run_commands == [f"{prefix} stats --no-stream --format '{{{{json .}}}}'"], client.dry_run_commands
+    
+    print("[PASS] Compose file and profiles included in commands")
+    return True
+
+
+def test_command_quoting():
+    """Test that paths and service names can't break out of the generated commands"""
+    print("\n" + "=" * 60)
+    print("Testing shell quoting of paths and service names")
+    print("=" * 60)
+    
+    assert quote_path("/opt/portal") == "/opt/portal", "Plain paths need no quoting"
+    assert quote_path("/opt/my portal") == "'/opt/my portal'", "Spaces must be quoted"
+    assert quote_path("~/my portal") == "~/'my portal'", "A leading ~/ must stay unquoted to expand"
+    assert quote_path("/opt/it's") == "'/opt/it'\"'\"'s'", "Single quotes must be escaped"
+    
+    ssh = SSHClient("example.com", 22, "deploy", "", password="unused", dry_run=True)
+    ssh.start_service("/opt/$HOME; rm -rf /", "web; reboot")
+    assert ssh.dry_run_commands == [
+        "cd '/opt/$HOME; rm -rf /' && docker compose up -d 'web; reboot'"
+    ], f"Unexpected commands: {ssh.dry_run_commands}"
+    
+    print("[PASS] Paths and service names quoted safely")
+    return True
+
+
+def test_service_health_states():
+    """Test that health status codes map to ready/starting/unreachable"""
+    print("\n" + "=" * 60)
+    print("Testing service health states")
+    print("=" * 60)
+    
+    assert ServiceHealth.from_status_code(200).state == HealthState.READY
+    assert ServiceHealth.from_status_code(503) == ServiceHealth(HealthState.STARTING, 503), "503 means warming up"
+    assert ServiceHealth.from_status_code(404).state == HealthState.STARTING
+    assert ServiceHealth.from_status_code(0).state == HealthState.UNREACHABLE, "000 means nothing is listening"
+    assert ServiceHealth.from_status_code(302, ["2xx", "3xx"]).state == HealthState.READY
+    assert is_accepted_status(204, ["2xx"]) and not is_accepted_status(404, ["2xx", "301"])
+    
+    ssh = SSHClient("example.com", 22, "deploy", "", password="unused", dry_run=True,
+                    health_scheme="https", health_path="/healthz")
+    assert ssh.health_url(8080, "/") == "https://localhost:8080/healthz", ssh.health_url(8080, "/")
+    
+    print("[PASS] Health states parsed correctly")
+    return True
+
+
+def test_parse_compose_down():
+    """Test parsing what docker compose down stopped and removed"""
+    print("\n" + "=" * 60)
+    print("Testing compose down output parsing")
+    print("=" * 60)
+    
+    output = """ Container portal-web-1  Stopping
+ Container portal-web-1  Stopped
+ Container portal-web-1  Removing
+ ✔ Container portal-web-1  Removed   0.3s
+ ✔ Container portal-old-1  Removed   0.1s
+ Volume portal_data  Removed
+ Network portal_default  Removed"""
+    down = parse_compose_down(output)
+    assert down.stopped == ["portal-web-1"], down.stopped
+    assert down.removed == ["portal-web-1", "portal-old-1"], down.removed
+    assert down.networks == ["portal_default"] and down.volumes == ["portal_data"]
+    
+    print("[PASS] Compose down output parsed correctly")
+    return True
+
+
+def test_parse_compose_up():
+    """Test parsing which containers compose up recreated, started or left alone"""
+    print("\n" + "=" * 60)
+    print("Testing compose up output parsing")
+    print("=" * 60)
+    
+    v2_output = """ Container portal-db-1  Running
+ Container portal-web-1  Recreate
+ Container portal-web-1  Recreated
+ Container portal-web-1  Starting
+ Container portal-web-1  Started
+ Container portal-worker-1  Created"""
+    assert parse_compose_up(v2_output) == {
+        "portal-db-1": "up_to_date",
+        "portal-web-1": "recreated",
+        "portal-worker-1": "created",
+    }, parse_compose_up(v2_output)
+    
+    v1_output = """portal_db_1 is up-to-date
+Recreating portal_web_1 ... done
+Starting portal_worker_1 ... done"""
+    assert parse_compose_up(v1_output) == {
+        "portal_db_1": "up_to_date",
+        "portal_web_1": "recreated",
+        "portal_worker_1": "started",
+    }, parse_compose_up(v1_output)
+    
+    print("[PASS] Compose up output parsed correctly")
+    return True
+
+
+def test_merge_service_states():
+    """Test that defined services without containers are reported as not created"""
+    print("\n" + "=" * 60)
+    print("Testing compose service state merging")
+    print("=" * 60)
+    
+    containers = [
+        ContainerStatus(name="portal-web-1", status="Exited (0)", state="exited", service="web"),
+        ContainerStatus(name="portal-web-2", status="Up 2 minutes", state="running", service="web"),
+        ContainerStatus(name="portal-db-1", status="Up 2 minutes", state="running", service="db"),
+    ]
+    states = {s.name: s for s in merge_service_states(["web", "db", "worker"], containers)}
+    assert states["web"].state == "running" and states["web"].container == "portal-web-2", "Running replica preferred"
+    assert states["db"].container == "portal-db-1"
+    assert states["worker"].state == NOT_CREATED and states["worker"].container == ""
+    
+    # A project compose ps succeeds on but lists nothing was never brought up, unlike a failing ps
+    assert project_state(0, []) == ProjectState.NOT_CREATED
+    assert project_state(1, []) == ProjectState.UNAVAILABLE
+    assert project_state(0, containers[:1]) == ProjectState.STOPPED
+    assert project_state(0, containers) == ProjectState.RUNNING
+    
+    print("[PASS] Service states merged correctly")
+    return True
+
+
+class FakeStream:
+    """Stand-in for the stdout/stderr files paramiko's exec_command returns"""
+    
+    def __init__(self, channel, data: bytes):
+        self.channel = channel
+        self._data = data
+    
+    def read(self) -> bytes:
+        return self._data
+
+
+class FakeChannel:
+    def __init__(self, exit_status: int = 0):
+        self._exit_status = exit_status
+    
+    def recv_exit_status(self) -> int:
+        return self._exit_status
+
+
+class FakeParamikoClient:
+    """Answers each command on its own fake channel, tracking how many are open at once"""
+    
+    def __init__(self):
+        self.lock = threading.Lock()
+        self.open_channels = 0
+        self.max_open_channels = 0
+    
+    def exec_command(self, cmd, timeout=None):
+        with self.lock:
+            self.open_channels += 1
+            self.max_open_channels = max(self.max_open_channels, self.open_channels)
+        try:
+            time.sleep(0.05)  # Let the other threads pile in
+            if " ps --format json" in cmd:
+                output = b'{"Name": "portal-web-1", "Service": "web", "State": "running", "Status": "Up"}'
+            elif " logs " in cmd:
+                output = b"web-1  | listening on :3000"
+            else:
+                output = b"ok"
+            channel = FakeChannel()
+            return None, FakeStream(channel, output), FakeStream(channel, b"")
+        finally:
+            with self.lock:
+                self.open_channels -= 1
+
+
+def test_concurrent_commands():
+    """Test that status polls and log fetches can share one connection from several threads"""
+    print("\n" + "=" * 60)
+    print("Testing concurrent commands over a shared connection")
+    print("=" * 60)
+    
+    ssh = SSHClient("example.com", 22, "deploy", "", password="unused")
+    ssh.client = FakeParamikoClient()
+    results, errors = [], []
+    
+    def poll_status():
+        containers = ssh.check_containers_at_path("/opt/portal")
+        results.append(('status', [c.name for c in containers]))
+    
+    def fetch_logs():
+        results.append(('logs', ssh.get_service_logs("/opt/portal", "web", 10)))
+    
+    def run(target):
+        try:
+            target()
+        except Exception as e:
+            errors.append(e)
+    
+    threads = [threading.Thread(target=run, args=(fn,)) for fn in [poll_status, fetch_logs] * 10]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join(timeout=10)
+    
+    assert not errors, f"Concurrent commands failed: {errors}"
+    assert results.count(('status', ["portal-web-1"])) == 10, results
+    assert results.count(('logs', "web-1  | listening on :3000")) == 10, results
+    assert ssh.client.max_open_channels <= MAX_CONCURRENT_CHANNELS, \
+        f"{ssh.client.max_open_channels} channels open at once"
+    
+    print("[PASS] Concurrent commands each got their own channel")
+    return True
+
+
+def test_error_kinds():
+    """Command failures carry a kind the frontend can branch on, even once wrapped"""
+    print("\n" + "=" * 60)
+    print("Testing structured error kinds")
+    print("=" * 60)
+    
+    def kind_of(result):
+        try:
+            result.check("docker compose ps")
+        except Exception as e:
+            return e.kind
+        return None
+    
+    assert kind_of(CommandResult("", "sh: docker: command not found", 127)) == ErrorKind.DOCKER_MISSING
+    assert kind_of(CommandResult("", "no such service", 1)) == ErrorKind.COMMAND_FAILED
+    assert kind_of(CommandResult("", "sudo: a password is required", 1)) == ErrorKind.SUDO_PASSWORD_REQUIRED
+    assert kind_of(CommandResult("ok", "", 0)) is None
+    
+    wrapped = command_error(CommandTimedOut("sleep 60", 30), "Failed to get status")
+    assert wrapped.kind == ErrorKind.TIMEOUT
+    assert type(wrapped).__name__ == "Timeout", "pywebview exposes the class name as error.name"
+    assert str(wrapped) == "Failed to get status: Command 'sleep 60' timed out after 30s"
+    assert command_error(ValueError("bad"), "Failed").kind == ErrorKind.UNKNOWN
+    
+    print("[PASS] Errors keep their kind")
+    return True
+
+
+def test_docker_daemon_check():
+    """A stopped docker daemon is reported with docker's reason, not as an empty server"""
+    print("\n" + "=" * 60)
+    print("Testing docker daemon availability")
+    print("=" * 60)
+    
+    ssh = SSHClient("example.com", 22, "deploy", "", password="unused")
+    down = "Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?"
+    ssh.run_command = lambda cmd: CommandResult("", down + "\n", 1)
+    assert ssh.check_docker_daemon() == (False, down)
+    
+    ssh.run_command = lambda cmd: CommandResult("27.1.1\n", "", 0)
+    assert ssh.check_docker_daemon() == (True, "Docker 27.1.1")
+    
+    ssh.run_command = lambda cmd: CommandResult("", "sudo: a password is required", 1)
+    available, detail = ssh.check_docker_daemon()
+    assert not available and "passwordless sudo" in detail, detail
+    
+    print("[PASS] Docker daemon availability reported")
+    return True
+
+
+def test_proxy_command_expansion():
+    """OpenSSH-style tokens in proxy_command expand to the target's details"""
+    print("\n" + "=" * 60)
+    print("Testing proxy command token expansion")
+    print("=" * 60)
+    
+    ssh = SSHClient(host="portal.example.com", port=2222, username="calvin", ssh_key_path="",
+                    proxy_command="cloudflared access ssh --hostname %h:%p --user %r --note 100%%")
+    assert ssh._expand_proxy_command() == "cloudflared access ssh --hostname portal.example.com:2222 --user calvin --note 100%"
+    assert "via proxy command" in ssh._route()
+    
+    print("[PASS] Proxy command tokens expand")
+    return True
+
+
+def test_host_forms():
+    """IPv4, IPv6 and hostnames, with or without a port, connect and build URLs correctly"""
+    print("\n" + "=" * 60)
+    print("Testing host and port parsing")
+    print("=" * 60)
+    
+    cases = [
+        ("192.168.1.10", ("192.168.1.10", 22), "http://192.168.1.10:8080"),
+        ("192.168.1.10:2222", ("192.168.1.10", 2222), "http://192.168.1.10:8080"),
+        ("portal.example.com", ("portal.example.com", 22), "http://portal.example.com:8080"),
+        ("portal.example.com:2222", ("portal.example.com", 2222), "http://portal.example.com:8080"),
+        ("::1", ("::1", 22), "http://[::1]:8080"),
+        ("fe80::1:2", ("fe80::1:2", 22), "http://[fe80::1:2]:8080"),
+        ("[::1]", ("::1", 22), "http://[::1]:8080"),
+        ("[2001:db8::5]:2222", ("2001:db8::5", 2222), "http://[2001:db8::5]:8080"),
+    ]
+    for entered, expected, url in cases:
+        assert split_host_port(entered, 22) == expected, entered
+        ssh = SSHClient(host=entered, port=22, username="calvin", ssh_key_path="")
+        assert (ssh.host, ssh.port) == expected, entered
+        server = ServerConfig(id="s", name="s", host=entered, port=22, username="calvin", ssh_key_path="",
+                              services=[ServiceConfig(id="p", name="Portal", container_name="portal", path="/srv", port=8080)])
+        assert (server.host, server.port) == expected, entered
+        assert server.portal_url() == url, (entered, server.portal_url())
+    
+    assert SSHClient(host="[::1]:2222", port=22, username="u", ssh_key_path="")._known_hosts_entry_name() == "[::1]:2222"
+    ssh = SSHClient(host="h", port=22, username="u", ssh_key_path="", health_host="::1")
+    assert ssh.health_url(8080) == "http://[::1]:8080/"
+    https = ServiceConfig(id="p", name="Portal", container_name="portal", path="/srv", port=8443, scheme="https")
+    assert ServerConfig(id="s", name="s", host="::1", port=22, username="u", ssh_key_path="").service_url(https) == "https://[::1]:8443"
+    
+    print("[PASS] Host forms parse and bracket correctly")
+    return True
+
+
+def test_algorithm_overrides():
+    """Legacy algorithm overrides are validated against what paramiko implements"""
+    print("\n" + "=" * 60)
+    print("Testing SSH algorithm override validation")
+    print("=" * 60)
+    
+    def algorithm_errors(**overrides):
+        server = ServerConfig(id="s", name="s", host="appliance", port=22, username="admin",
+                              ssh_key_path="", password="secret", **overrides)
+        return [e.field for e in AppConfig(servers=[server]).validate() if e.field in ALGORITHM_FIELDS]
+    
+    assert algorithm_errors() == []
+    assert algorithm_errors(kex_algorithms=["diffie-hellman-group14-sha1"], host_key_algorithms=["ssh-rsa"],
+                            ciphers=["aes128-cbc"]) == []
+    assert algorithm_errors(kex_algorithms=["made-up-kex"], ciphers=["rot13"]) == ["kex_algorithms", "ciphers"]
+    
+    print("[PASS] Algorithm overrides are validated")
+    return True
+
+
+def test_diagnose_refused_connection():
+    """A diagnosis stops at the first failing stage and skips the rest"""
+    print("\n" + "=" * 60)
+    print("Testing connection diagnosis")
+    print("=" * 60)
+    
+    # Find a free port, then close it so connecting is refused
+    with socket.socket() as s:
+        s.bind(("127.0.0.1", 0))
+        port = s.getsockname()[1]
+    
+    diagnosis = SSHClient(host="127.0.0.1", port=port, username="calvin", ssh_key_path="",
+                          password="secret", connect_timeout=2).diagnose()
+    assert [(stage.name, stage.status) for stage in diagnosis.stages] == [
+        ("resolve", "ok"), ("tcp", "failed"), ("handshake", "skipped"), ("host_key", "skipped"), ("auth", "skipped")
+    ], diagnosis
+    assert diagnosis.failed_stage == "tcp" and diagnosis.server_version is None and diagnosis.auth_banner is None
+    assert "127.0.0.1" in diagnosis.stages[0].detail
+    
+    assert decode_auth_banner(b"Maintenance window tonight\r\n") == "Maintenance window tonight"
+    assert decode_auth_banner(b"") is None and decode_auth_banner(None) is None
+    
+    print("[PASS] Diagnosis reports the failing stage")
+    return True
+
+
+def test_auth_through_jump_host():
+    """Bare-transport operations tunnel through the jump host and name it when it fails"""
+    print("\n" + "=" * 60)
+    print("Testing auth test and host key fetch through a jump host")
+    print("=" * 60)
+    
+    class FakeJumpHost:
+        host, port = "bastion.example.com", 2222
+        
+        def __init__(self, refuse):
+            self.refuse = refuse
+            self.connected = False
+        
+        def connect(self):
+            if self.refuse:
+                raise TransientConnectionError("Connection refused")
+            self.connected = True
+        
+        def disconnect(self):
+            self.connected = False
+    
+    class StopAfterSocket(Exception):
+        pass
+    
+    tunnel = object()
+    sockets = []
+    
+    def new_transport(sock):
+        sockets.append(sock)
+        raise StopAfterSocket()
+    
+    jump = FakeJumpHost(refuse=False)
+    ssh = SSHClient("10.0.0.5", 22, "deploy", "", password="unused", jump_host=jump)
+    ssh._open_jump_channel = lambda: tunnel
+    ssh._new_transport = new_transport
+    for operation in (ssh.test_auth, ssh.fetch_host_key):
+        try:
+            operation()
+            assert False, "The fake transport stops every operation"
+        except StopAfterSocket:
+            pass
+        assert sockets.pop() is tunnel, "The target must be reached through the bastion's tunnel"
+        assert not jump.connected, "The jump host is disconnected afterwards"
+    
+    refused = SSHClient("10.0.0.5", 22, "deploy", "", password="unused", jump_host=FakeJumpHost(refuse=True))
+    try:
+        refused.test_auth()
+        assert False, "A refused jump host must fail the auth test"
+    except TransientConnectionError as e:
+        assert "Jump host bastion.example.com:2222 failed" in str(e), str(e)
+    
+    print("[PASS] Jump host used for bare-transport operations")
+    return True
+
+
+def test_crash_detection_sees_exited_containers():
+    """The startup crash check finds a portal container that only docker compose ps -a lists"""
+    print("\n" + "=" * 60)
+    print("Testing startup crash detection")
+    print("=" * 60)
+    
+    exited = ('[{"Name":"portal-ai-portal-1","Service":"ai-portal","State":"exited",'
+              '"Status":"Exited (1) 3 seconds ago","Health":""}]')
+    
+    class ExitedPortalClient(SSHClient):
+        def run_command(self, cmd):
+            # Plain ps only lists running containers, so the exited portal is missing from it
+            if " ps -a " in cmd:
+                return CommandResult(exited, "", 0)
+            if " ps " in cmd:
+                return CommandResult("[]", "", 0)
+            return CommandResult("", "", 0)
+        
+        def get_service_logs(self, path, service_name=None, lines=100):
+            return "Error: model file not found"
+    
+    from api import API
+    ssh = ExitedPortalClient("example.com", 22, "deploy", "", password="unused")
+    service = ServiceConfig(id="ai-portal", name="AI Portal", container_name="ai-portal", port=8080,
+                            path="/opt/portal")
+    try:
+        API()._check_not_crashed(ssh, service)
+        assert False, "An exited portal container should fail the launch"
+    except Exception as e:
+        assert "portal-ai-portal-1 is exited" in str(e) and "model file not found" in str(e), str(e)
+    
+    print("[PASS] Exited containers fail the startup wait")
+    return True
+
+def test_key_path_expansion():
+    """Key paths starting with ~ are looked up in the home directory, as config validation does"""
+    print("\n" + "=" * 60)
+    print("Testing key path expansion")
+    print("=" * 60)
+    
+    with tempfile.TemporaryDirectory() as home:
+        previous_home = os.environ.get('HOME')
+        os.environ['HOME'] = home
+        try:
+            key = Path(home) / '.ssh' / 'id_rsa'
+            key.parent.mkdir()
+            key.write_text("not really a key\n")
+            ssh = SSHClient("example.com", 22, "deploy", "~/.ssh/id_rsa", ssh_key_paths=[str(key), "~/.ssh/missing"])
+            assert ssh.key_paths == [str(key), str(Path(home) / '.ssh' / 'missing')], ssh.key_paths
+            assert ssh._existing_key_paths() == [str(key)]
+        finally:
+            if previous_home is None:
+                os.environ.pop('HOME', None)
+            else:
+                os.environ['HOME'] = previous_home
+    
+    print("[PASS] ~ in key paths expands to the home directory")
+    return True
+
+def main():
+    """Run all tests"""
+    tests = [
+        test_parse_compose_ps_array,
+        test_parse_compose_ps_ndjson,
+        test_container_matches,
+        test_parse_image_ids,
+        test_parse_disk_usage,
+        test_parse_container_events,
+        test_dry_run_commands,
+        test_parse_port_conflict,
+        test_log_filters,
+        test_compose_file_and_profiles,
+        test_command_quoting,
+        test_service_health_states,
+        test_parse_compose_down,
+        test_parse_compose_up,
+        test_merge_service_states,
+        test_concurrent_commands,
+        test_error_kinds,
+        test_docker_daemon_check,
+        test_proxy_command_expansion,
+        test_host_forms,
+        test_algorithm_overrides,
+        test_diagnose_refused_connection,
+        test_auth_through_jump_host,
+        test_crash_detection_sees_exited_containers,
+        test_key_path_expansion,
+    ]
+    
+    try:
+        for test in tests:
+            if not test():
+                print(f"\n[FAIL] {test.__name__} failed!")
+                return False
+        
+        print("\n" + "=" * 60)
+        print("[SUCCESS] ALL TESTS PASSED!")
+        print("=" * 60)
+        return True
+        
+    except Exception as e:
+        print(f"\n[ERROR] Test failed with error: {e}")
+        import traceback
+        traceback.print_exc()
+        return False
+
+
+if __name__ == "__main__":
+    # Anything the API saves goes to a scratch directory rather than the real config dir
+    with tempfile.TemporaryDirectory() as config_dir:
+        os.environ[CONFIG_DIR_ENV_VAR] = config_dir
+        success = main()
+    sys.exit(0 if success else 1)